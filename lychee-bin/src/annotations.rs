@@ -0,0 +1,118 @@
+use lychee_lib::{Input, ResponseBody};
+
+use crate::{
+    sarif::{artifact_uri, rule},
+    stats::ResponseStats,
+};
+
+/// Format the failures of a run as GitHub Actions workflow commands
+/// (`::error file=...,line=...::...`), which show up as annotations
+/// at the location of the links in pull requests.
+/// See <https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions>
+pub(crate) fn annotations(stats: &ResponseStats) -> String {
+    stats
+        .failures()
+        .into_iter()
+        .flat_map(|(input, responses)| {
+            responses
+                .into_iter()
+                .flat_map(move |response| commands(input, response))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One command per location of the link, or a single one
+/// if the link can't be located in its input
+fn commands(input: &Input, response: &ResponseBody) -> Vec<String> {
+    let rule = rule(&response.status);
+    let message = escape_data(&format!("{} ({})", response.uri, response.status));
+    let title = format!("title={}", escape_property(rule.description));
+    let command = |location: Option<String>| match location {
+        Some(location) => format!("::{} {},{}::{}", rule.level, location, title, message),
+        None => format!("::{} {}::{}", rule.level, title, message),
+    };
+
+    let file = match artifact_uri(input) {
+        Some(file) => format!("file={}", escape_property(&file)),
+        None => return vec![command(None)],
+    };
+    if response.spans.is_empty() {
+        return vec![command(Some(file))];
+    }
+    response
+        .spans
+        .iter()
+        .map(|span| {
+            command(Some(format!(
+                "{},line={},col={}",
+                file, span.line, span.column
+            )))
+        })
+        .collect()
+}
+
+/// Escape the message of a workflow command
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value of a workflow command
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use lychee_lib::{ErrorKind, Input, Response, ResponseBody, Span, Status, Uri};
+    use reqwest::Url;
+
+    use super::{annotations, escape_property};
+    use crate::stats::ResponseStats;
+
+    #[test]
+    fn test_annotations() {
+        let mut body = ResponseBody::new(
+            Uri::from(Url::parse("https://example.org/docs").unwrap()),
+            ErrorKind::MissingContent("example.org".to_owned()).into(),
+        );
+        body.spans = vec![
+            Span {
+                start: 30,
+                end: 53,
+                line: 3,
+                column: 7,
+            },
+            Span {
+                start: 90,
+                end: 113,
+                line: 8,
+                column: 1,
+            },
+        ];
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::FsPath(PathBuf::from("./docs/README.md")),
+            body,
+        ));
+
+        let output = annotations(&stats);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(
+            "::error file=docs/README.md,line=3,col=7,title=Link is broken::https://example.org/docs"
+        ));
+        assert!(lines[1].starts_with("::error file=docs/README.md,line=8,col=1,"));
+    }
+
+    #[test]
+    fn test_escape_property() {
+        assert_eq!(escape_property("a,b: 100%\n"), "a%2Cb%3A 100%25%0A");
+    }
+}
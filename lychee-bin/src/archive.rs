@@ -0,0 +1,176 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use lychee_lib::{Input, Response, ResponseBody};
+use serde::Serialize;
+
+/// Number of records per compressed frame.
+/// Smaller frames allow faster random access, larger ones compress better.
+const RECORDS_PER_FRAME: usize = 1000;
+
+/// A single archived response
+#[derive(Serialize)]
+struct Record<'a> {
+    source: &'a Input,
+    #[serde(flatten)]
+    response: &'a ResponseBody,
+}
+
+/// Location of a compressed frame within the archive
+#[derive(Debug, Serialize)]
+struct Frame {
+    /// Byte offset of the frame in the archive file
+    offset: u64,
+    /// Compressed size of the frame in bytes
+    length: u64,
+    /// Number of the first record in the frame, starting at 0
+    first_record: usize,
+    /// Number of records in the frame
+    records: usize,
+    /// Number of failed checks in the frame
+    failures: usize,
+}
+
+/// Index of an archive, written next to it as `<archive>.index.json`
+#[derive(Debug, Default, Serialize)]
+struct Index {
+    records: usize,
+    failures: usize,
+    frames: Vec<Frame>,
+}
+
+/// Zstandard-compressed archive of all responses of a run.
+///
+/// Records are stored as NDJSON in independent zstd frames of
+/// `RECORDS_PER_FRAME` records each. The whole archive can be unpacked
+/// with `zstd -d`, while the index allows decompressing single frames.
+#[derive(Debug)]
+pub(crate) struct Archive {
+    path: PathBuf,
+    file: BufWriter<File>,
+    /// Uncompressed records of the current frame
+    buffer: Vec<u8>,
+    records: usize,
+    failures: usize,
+    offset: u64,
+    index: Index,
+}
+
+impl Archive {
+    pub(crate) fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).context("Cannot create archive file")?;
+        Ok(Self {
+            path: path.to_owned(),
+            file: BufWriter::new(file),
+            buffer: Vec::new(),
+            records: 0,
+            failures: 0,
+            offset: 0,
+            index: Index::default(),
+        })
+    }
+
+    pub(crate) fn add(&mut self, response: &Response) -> Result<()> {
+        let record = Record {
+            source: &response.0,
+            response: &response.1,
+        };
+        serde_json::to_writer(&mut self.buffer, &record)?;
+        self.buffer.push(b'\n');
+        self.records += 1;
+        if response.status().is_failure() {
+            self.failures += 1;
+        }
+        if self.records == RECORDS_PER_FRAME {
+            self.write_frame()?;
+        }
+        Ok(())
+    }
+
+    /// Write the remaining records and the index
+    pub(crate) fn finish(mut self) -> Result<()> {
+        self.write_frame()?;
+        self.file.flush()?;
+
+        let mut index_path = OsString::from(self.path.as_os_str());
+        index_path.push(".index.json");
+        fs::write(index_path, serde_json::to_string_pretty(&self.index)?)
+            .context("Cannot write archive index")?;
+        Ok(())
+    }
+
+    fn write_frame(&mut self) -> Result<()> {
+        if self.records == 0 {
+            return Ok(());
+        }
+        let compressed = zstd::encode_all(&self.buffer[..], zstd::DEFAULT_COMPRESSION_LEVEL)?;
+        self.file.write_all(&compressed)?;
+
+        let length = compressed.len() as u64;
+        self.index.frames.push(Frame {
+            offset: self.offset,
+            length,
+            first_record: self.index.records,
+            records: self.records,
+            failures: self.failures,
+        });
+        self.index.records += self.records;
+        self.index.failures += self.failures;
+        self.offset += length;
+
+        self.buffer.clear();
+        self.records = 0;
+        self.failures = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{convert::TryFrom, fs};
+
+    use http::StatusCode;
+    use lychee_lib::{ErrorKind, Input, Response, Status, Uri};
+    use serde_json::Value;
+
+    use super::{Archive, RECORDS_PER_FRAME};
+
+    #[test]
+    fn test_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.zst");
+
+        let mut archive = Archive::create(&path).unwrap();
+        for i in 0..=RECORDS_PER_FRAME {
+            let uri = Uri::try_from(format!("https://example.org/{}", i).as_str()).unwrap();
+            let status = if i == 0 {
+                ErrorKind::TooManyRedirects(5).into()
+            } else {
+                Status::Ok(StatusCode::OK)
+            };
+            archive
+                .add(&Response::new(uri, status, Input::Stdin))
+                .unwrap();
+        }
+        archive.finish().unwrap();
+
+        let decompressed = zstd::decode_all(fs::File::open(&path).unwrap()).unwrap();
+        let lines: Vec<&[u8]> = decompressed.split(|b| *b == b'\n').collect();
+        assert_eq!(lines.len(), RECORDS_PER_FRAME + 2); // trailing newline
+        let first: Value = serde_json::from_slice(lines[0]).unwrap();
+        assert_eq!(first["url"], "https://example.org/0");
+        assert_eq!(first["source"], "stdin");
+
+        let index = fs::read_to_string(dir.path().join("results.zst.index.json")).unwrap();
+        let index: Value = serde_json::from_str(&index).unwrap();
+        assert_eq!(index["records"], RECORDS_PER_FRAME + 1);
+        assert_eq!(index["failures"], 1);
+        assert_eq!(index["frames"].as_array().unwrap().len(), 2);
+        assert_eq!(index["frames"][1]["first_record"], RECORDS_PER_FRAME);
+    }
+}
@@ -62,37 +62,81 @@
 use ring as _;
 
 use std::iter::FromIterator;
-use std::{collections::HashSet, fs, str::FromStr, time::Duration};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap, HashSet},
+    convert::TryFrom,
+    fs::{self, File},
+    hash::{Hash, Hasher},
+    io::BufWriter,
+    net::IpAddr,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::{anyhow, Context, Result};
+use console::style;
 use headers::{authorization::Basic, Authorization, HeaderMap, HeaderMapExt, HeaderName};
 use http::StatusCode;
 use indicatif::{ProgressBar, ProgressStyle};
+#[cfg(feature = "render")]
+use lychee_lib::Renderer;
+#[cfg(feature = "server")]
+use lychee_lib::Server;
 use lychee_lib::{
     collector::{Collector, Input},
-    ClientBuilder, ClientPool, Response,
+    comparison::{self, Comparison},
+    config::expand_env,
+    diff::ChangedLines,
+    extract::{HtmlExtractor, MarkdownExtractor, PlaintextExtractor, Selector},
+    fix::Fix,
+    graph::LinkGraph,
+    metrics::Metrics,
+    pipeline::{CacheStage, Pipeline, Remap, RemapStage},
+    post_process::{HeaderAssertion, Reclassification, StatusRule},
+    redact_headers,
+    sitemap::Sitemap,
+    AdaptiveTimeout, Checksums, ClientBuilder, ClientPool, CodeOwners, Connectivity, DnsCache,
+    Event, EventLog, GithubApp, IpNetwork, MailCheckMode, MailPolicy, Observer, Redirect, Request,
+    Response, SmtpConfig, TimelineEvent,
 };
 use openssl_sys as _; // required for vendored-openssl feature
-use regex::RegexSet;
+use regex::{Regex, RegexSet};
+use reqwest::Url;
 use ring as _; // required for apple silicon
 use structopt::StructOpt;
-use tokio::sync::mpsc;
+use tokio::{net::TcpListener, sync::mpsc};
 
+mod annotations;
+mod archive;
 mod options;
+mod report;
+mod sarif;
 mod stats;
+mod table;
+mod webhook;
 
 use crate::{
-    options::{Config, Format, LycheeOptions},
-    stats::{color_response, ResponseStats},
+    archive::Archive,
+    options::{Config, Format, LycheeOptions, NonFatal},
+    report::{Formatter, HtmlFormatter, MarkdownFormatter},
+    stats::{color_response, ResponseStats, SuccessPolicy},
+    table::{table, Row},
 };
 
+/// Default number of seconds to keep the resolved addresses of a host
+const DNS_CACHE_TTL: u64 = 300;
+
+/// Default number of seconds to connect to a host
+const CONNECT_TIMEOUT: u64 = 10;
+
 /// A C-like enum that can be cast to `i32` and used as process exit code.
 enum ExitCode {
     Success = 0,
     // NOTE: exit code 1 is used for any `Result::Err` bubbled up to `main()` using the `?` operator.
     // For now, 1 acts as a catch-all for everything non-link related (including config errors),
     // until we find a way to structure the error code handling better.
-    #[allow(unused)]
+    // It is also used when the run was interrupted before all links were checked.
     UnexpectedFailure = 1,
     LinkCheckFailure = 2,
 }
@@ -136,70 +180,380 @@ fn show_progress(progress_bar: &Option<ProgressBar>, response: &Response, verbos
         pb.inc(1);
         pb.set_message(&out);
         if verbose {
-            pb.println(out);
+            pb.println(&out);
+            for redirect in &response.1.redirects {
+                pb.println(format_redirect(redirect));
+            }
+            for suggestion in &response.1.suggestions {
+                pb.println(format_suggestion(suggestion));
+            }
+            for event in response.1.timeline.iter().flat_map(|t| &t.events) {
+                pb.println(format_timeline_event(event));
+            }
         }
     } else {
         if (response.status().is_success() || response.status().is_excluded()) && !verbose {
             return;
         }
         println!("{}", out);
+        if verbose {
+            for redirect in &response.1.redirects {
+                println!("{}", format_redirect(redirect));
+            }
+            for event in response.1.timeline.iter().flat_map(|t| &t.events) {
+                println!("{}", format_timeline_event(event));
+            }
+        }
+        for suggestion in &response.1.suggestions {
+            println!("{}", format_suggestion(suggestion));
+        }
     }
 }
 
-fn fmt(stats: &ResponseStats, format: &Format) -> Result<String> {
+/// Format a single redirect hop, indented below the response it belongs to
+fn format_redirect(redirect: &Redirect) -> String {
+    style(format!("  \u{21b3} {}", redirect)).dim().to_string() // ↳
+}
+
+/// Format a single event of a timeline, indented below the response it belongs to
+fn format_timeline_event(event: &TimelineEvent) -> String {
+    style(format!("  \u{23f1} {}", event)).dim().to_string() // ⏱
+}
+
+/// Format a suggestion, indented below the response it belongs to
+fn format_suggestion(suggestion: &str) -> String {
+    style(format!("  \u{1f4a1} {}", suggestion))
+        .yellow()
+        .to_string() // 💡
+}
+
+fn fmt(stats: &ResponseStats, rows: &[Row], format: &Format) -> Result<String> {
     Ok(match format {
         Format::String => stats.to_string(),
         Format::Json => serde_json::to_string_pretty(&stats)?,
+        Format::Sarif => serde_json::to_string_pretty(&sarif::sarif(stats))?,
+        Format::Github => annotations::annotations(stats),
+        Format::Markdown => MarkdownFormatter.format(stats),
+        Format::Html => HtmlFormatter.format(stats),
+        Format::Csv => table(rows, ','),
+        Format::Tsv => table(rows, '\t'),
     })
 }
 
-async fn run(cfg: &Config, inputs: Vec<Input>) -> Result<i32> {
+async fn run(cfg: &Config, inputs: Vec<(Input, Vec<String>)>) -> Result<i32> {
     let mut headers = parse_headers(&cfg.headers)?;
     if let Some(auth) = &cfg.basic_auth {
         let auth_header = parse_basic_auth(&auth)?;
         headers.typed_insert(auth_header);
     }
+    redact_headers(&mut headers);
 
     let accepted = cfg.accept.clone().and_then(|a| parse_statuscodes(&a).ok());
+    let status_rules = cfg
+        .status_rule
+        .iter()
+        .map(|rule| parse_status_rule(rule))
+        .collect::<Result<Vec<_>>>()?;
+    let header_assertions = cfg
+        .expect_header
+        .iter()
+        .map(|rule| parse_header_assertion(rule))
+        .collect::<Result<Vec<_>>>()?;
+    let record_headers = cfg
+        .record_header
+        .iter()
+        .map(|name| HeaderName::from_str(name))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let checksums = match &cfg.checksums {
+        Some(path) => {
+            let manifest = fs::read_to_string(path).context("Cannot read checksums")?;
+            Some(Checksums::parse(&manifest)?)
+        }
+        None => None,
+    };
+    // Read the baseline before checking, so a missing report fails fast
+    let baseline = match &cfg.baseline {
+        Some(path) => {
+            let report = fs::read_to_string(path).context("Cannot read baseline")?;
+            Some(comparison::failed_links(&report)?)
+        }
+        None => None,
+    };
+    let remaps = cfg
+        .remap
+        .iter()
+        .map(|remap| parse_remap(remap))
+        .collect::<Result<Vec<_>>>()?;
+    let mut pipeline = if remaps.is_empty() {
+        Pipeline::default()
+    } else {
+        Pipeline::default().insert_after("filter", RemapStage::new(remaps))
+    };
+    // The service gets asked for the same links over and over
+    if cfg.serve.is_some() {
+        pipeline = pipeline.insert_after("filter", CacheStage::default());
+    }
     let timeout = parse_timeout(cfg.timeout);
     let max_concurrency = cfg.max_concurrency;
     let method: reqwest::Method = reqwest::Method::from_str(&cfg.method.to_uppercase())?;
     let include = RegexSet::new(&cfg.include)?;
     let exclude = RegexSet::new(&cfg.exclude)?;
+    let template_patterns = if cfg.check_templates {
+        Some(RegexSet::empty())
+    } else if cfg.template_pattern.is_empty() {
+        None
+    } else {
+        Some(RegexSet::new(&cfg.template_pattern)?)
+    };
+    let soft_404 = if cfg.soft_404.is_empty() {
+        None
+    } else {
+        Some(RegexSet::new(&cfg.soft_404)?)
+    };
+    let expect = cfg.expect.as_deref().map(Regex::new).transpose()?;
+    let mail_policy = mail_policy(cfg);
+    let mail_check_mode = parse_mail_check_mode(&cfg.mail_check)?;
+    let smtp = SmtpConfig {
+        from_email: cfg.smtp_from.clone(),
+        hello_name: cfg.smtp_hello.clone(),
+        proxy: cfg.smtp_proxy.as_deref().map(parse_proxy).transpose()?,
+        port: None,
+        timeout: cfg.smtp_timeout.map(Duration::from_secs),
+    };
+    let host_headers = parse_host_headers(&cfg.host_header)?;
+    let github_token = cfg
+        .github_token
+        .as_deref()
+        .map(expand_env)
+        .transpose()
+        .map_err(|e| anyhow!(e))?;
+    let resolve = parse_resolve(&cfg.resolve)?;
+    let dns_cache = match cfg.dns_cache_ttl.unwrap_or(DNS_CACHE_TTL) {
+        0 => None,
+        ttl => Some(DnsCache::new(Duration::from_secs(ttl))),
+    };
+    let github_app = github_app(cfg)?;
+    let event_log = match &cfg.event_log {
+        Some(path) => {
+            let file = File::create(path).context("Cannot create event log file")?;
+            Some(Arc::new(EventLog::new(BufWriter::new(file))))
+        }
+        None => None,
+    };
+    let metrics = match cfg.metrics {
+        Some(address) => {
+            let listener = TcpListener::bind(address)
+                .await
+                .context("Cannot serve metrics")?;
+            let metrics = Arc::new(Metrics::default());
+            tokio::spawn(metrics.clone().serve(listener));
+            Some(metrics)
+        }
+        None => None,
+    };
+    let mut observers: Vec<Arc<dyn Observer>> = Vec::new();
+    if let Some(log) = &event_log {
+        observers.push(log.clone());
+    }
+    if let Some(metrics) = &metrics {
+        observers.push(metrics.clone());
+    }
 
-    let client = ClientBuilder::builder()
+    let client_builder = ClientBuilder::builder()
         .includes(include)
         .excludes(exclude)
+        .template_patterns(template_patterns)
         .exclude_all_private(cfg.exclude_all_private)
         .exclude_private_ips(cfg.exclude_private)
         .exclude_link_local_ips(cfg.exclude_link_local)
         .exclude_loopback_ips(cfg.exclude_loopback)
+        .exclude_networks(parse_networks(&cfg.exclude_network)?)
+        .include_networks(parse_networks(&cfg.include_network)?)
+        .resolve_hosts(cfg.resolve_hosts)
+        .intranet(cfg.intranet)
         .exclude_mail(cfg.exclude_mail)
+        .allowed_hosts(HashSet::from_iter(cfg.allowed_host.clone()))
+        .blocked_hosts(HashSet::from_iter(cfg.blocked_host.clone()))
+        .allowed_domains(HashSet::from_iter(cfg.allowed_domain.clone()))
+        .blocked_domains(HashSet::from_iter(cfg.blocked_domain.clone()))
         .max_redirects(cfg.max_redirects)
+        .soft_404_patterns(soft_404)
+        .soft_404_homepage(cfg.soft_404_homepage)
+        .expected_content(expect)
+        .detect_parked_domains(cfg.detect_parked)
+        .detect_sso(cfg.detect_sso)
+        .detect_https_upgrades(cfg.detect_https)
+        .strip_credentials(cfg.strip_credentials)
+        .strip_fragments(cfg.strip_fragments)
+        .validate_data_uris(cfg.validate_data_uris)
+        .suggest_replacements(cfg.fixes.is_some())
+        .max_response_body_size(cfg.max_body_size)
+        .mail_policy(mail_policy)
+        .mail_check_mode(mail_check_mode)
+        .smtp(smtp)
         .user_agent(cfg.user_agent.clone())
         .allow_insecure(cfg.insecure)
         .custom_headers(headers)
+        .host_headers(host_headers)
+        .resolve(resolve)
+        .dns_cache(dns_cache)
+        .connect_timeout(Duration::from_secs(
+            cfg.connect_timeout.unwrap_or(CONNECT_TIMEOUT),
+        ))
+        .timeline_uris(HashSet::from_iter(cfg.timeline.clone()))
+        .timeline_sample(cfg.timeline_sample)
+        .approved_hosts(
+            (!cfg.approved_host.is_empty()).then(|| HashSet::from_iter(cfg.approved_host.clone())),
+        )
         .method(method)
+        .range_fallback(cfg.range_fallback)
         .timeout(timeout)
-        .github_token(cfg.github_token.clone())
+        .adaptive_timeout(cfg.slow_host_multiplier.map(AdaptiveTimeout::new))
+        .proxy(cfg.proxy.clone())
+        .proxy_hosts(
+            (!cfg.proxy_host.is_empty()).then(|| HashSet::from_iter(cfg.proxy_host.clone())),
+        )
+        .github_token(github_token)
+        .github_app(github_app)
         .schemes(HashSet::from_iter(cfg.scheme.clone()))
+        .exclude_schemes(HashSet::from_iter(cfg.exclude_scheme.clone()))
         .accepted(accepted)
-        .build()
-        .client()
-        .map_err(|e| anyhow!(e))?;
+        .status_rules(status_rules)
+        .header_assertions(header_assertions)
+        .record_headers(record_headers)
+        .max_content_age(
+            cfg.stale_after
+                .map(|years| Duration::from_secs(years * 365 * 24 * 60 * 60)),
+        )
+        .checksums(checksums)
+        .pipeline(pipeline)
+        .observer((!observers.is_empty()).then(|| Arc::new(observers) as Arc<dyn Observer>))
+        .build();
+    // Report all configuration problems at once, before checking any link
+    client_builder.validate().await.map_err(|e| anyhow!(e))?;
+    let client = client_builder.client().map_err(|e| anyhow!(e))?;
 
-    let links = Collector::new(cfg.base_url.clone(), cfg.skip_missing, max_concurrency)
-        .collect_links(&inputs)
-        .await
-        .map_err(|e| anyhow!(e))?;
+    if let Some(address) = &cfg.serve {
+        #[cfg(feature = "server")]
+        {
+            println!("Serving link checks at http://{}/check", address);
+            Server::new(client)
+                .max_concurrency(max_concurrency)
+                .serve(address)
+                .await?;
+            return Ok(ExitCode::Success as i32);
+        }
+        #[cfg(not(feature = "server"))]
+        return Err(anyhow!(
+            "Cannot serve link checks at {}: lychee was built without the server feature",
+            address
+        ));
+    }
+
+    if !cfg.probe_network.is_empty() {
+        let urls = cfg
+            .probe_network
+            .iter()
+            .map(|url| Url::parse(url).with_context(|| format!("Invalid probe URL: {}", url)))
+            .collect::<Result<Vec<_>>>()?;
+        client.probe_network(&urls).await.map_err(|e| anyhow!(e))?;
+    }
+
+    let mut collector = Collector::new(cfg.base_url.clone(), cfg.skip_missing, max_concurrency);
+    if let Some(log) = &event_log {
+        collector = collector.event_log(log.clone());
+    }
+    let link_graph = cfg.graph.as_ref().map(|_| Arc::new(LinkGraph::default()));
+    if let Some(link_graph) = &link_graph {
+        collector = collector.link_graph(link_graph.clone());
+    }
+    if let Some(path) = &cfg.diff {
+        let diff = fs::read_to_string(path).context("Cannot read diff")?;
+        collector = collector.changed_lines(ChangedLines::parse(&diff));
+    }
+    if cfg.bare_domains || cfg.angle_brackets || cfg.keep_punctuation {
+        collector = collector.plaintext(PlaintextExtractor {
+            bare_domains: cfg.bare_domains,
+            angle_brackets: cfg.angle_brackets,
+            trim_punctuation: !cfg.keep_punctuation,
+        });
+    }
+    if cfg.include_code_blocks
+        || cfg.include_inline_code
+        || cfg.exclude_blockquotes
+        || !cfg.frontmatter_key.is_empty()
+    {
+        collector = collector.markdown(MarkdownExtractor {
+            skip_code_blocks: !cfg.include_code_blocks,
+            skip_inline_code: !cfg.include_inline_code,
+            skip_blockquotes: cfg.exclude_blockquotes,
+            frontmatter_keys: cfg.frontmatter_key.clone(),
+        });
+    }
+    if cfg.skip_nofollow || cfg.skip_selector.is_some() || cfg.include_selector.is_some() {
+        collector = collector.html(HtmlExtractor {
+            skip_nofollow: cfg.skip_nofollow,
+            skip: parse_selector(cfg.skip_selector.as_deref())?,
+            include: parse_selector(cfg.include_selector.as_deref())?,
+        });
+    }
+    if let Some(webdriver) = &cfg.render {
+        #[cfg(feature = "render")]
+        {
+            let webdriver = Url::parse(webdriver)
+                .with_context(|| format!("Invalid WebDriver URL: {}", webdriver))?;
+            collector = collector.renderer(Renderer::new(webdriver).hosts(cfg.render_host.clone()));
+        }
+        #[cfg(not(feature = "render"))]
+        return Err(anyhow!(
+            "Cannot render inputs with {}: lychee was built without the render feature",
+            webdriver
+        ));
+    }
+    if let Some(path) = &cfg.codeowners {
+        let codeowners = fs::read_to_string(path).context("Cannot read CODEOWNERS")?;
+        collector = collector.code_owners(CodeOwners::parse(&codeowners)?);
+    }
+    if let Some(max) = cfg.max_links {
+        collector = collector.max_links_total(max);
+    }
+    if let Some(max) = cfg.max_links_per_input {
+        collector = collector.max_links_per_input(max);
+    }
+    collector = collector.lenient_urls(cfg.lenient_urls);
+    let truncation = collector.truncation();
+    let metadata = cfg
+        .metadata
+        .iter()
+        .map(|pair| parse_metadata(pair))
+        .collect::<Result<BTreeMap<_, _>>>()?;
+    for (input, tags) in &inputs {
+        if !tags.is_empty() {
+            collector = collector.tag(input.clone(), tags.clone());
+        }
+        if !metadata.is_empty() {
+            collector = collector.metadata(input.clone(), metadata.clone());
+        }
+    }
+    let inputs: Vec<Input> = inputs.into_iter().map(|(input, _)| input).collect();
+    let mut sitemap = match &cfg.sitemap {
+        Some(_) => Some(Sitemap::new(&site(cfg.base_url.as_ref(), &inputs)?)),
+        None => None,
+    };
+    if cfg.dump {
+        let collector = collector.filter(client.filter().clone());
+        return dump(collector, &inputs, max_concurrency, cfg.verbose).await;
+    }
+    // Links are checked while the inputs are still being read
+    let (send_link, mut recv_link) = mpsc::channel(max_concurrency);
+    let collection = tokio::spawn(async move { collector.stream_links(&inputs, send_link).await });
 
     let pb = if cfg.no_progress {
         None
     } else {
-        let bar =
-            ProgressBar::new(links.len() as u64).with_style(ProgressStyle::default_bar().template(
-                "{spinner:.red.bright} {pos}/{len:.dim} [{elapsed_precise}] {bar:25} {wide_msg}",
-            ));
+        let bar = ProgressBar::new(0).with_style(ProgressStyle::default_bar().template(
+            "{spinner:.red.bright} {pos}/{len:.dim} [{elapsed_precise}] {bar:25} {wide_msg}",
+        ));
         bar.enable_steady_tick(100);
         Some(bar)
     };
@@ -207,29 +561,132 @@ async fn run(cfg: &Config, inputs: Vec<Input>) -> Result<i32> {
     let (send_req, recv_req) = mpsc::channel(max_concurrency);
     let (send_resp, mut recv_resp) = mpsc::channel(max_concurrency);
 
-    let mut stats = ResponseStats::new();
+    let mut stats = if cfg.group_by_input {
+        ResponseStats::grouped_by_input()
+    } else {
+        ResponseStats::new()
+    };
+    if cfg.compact {
+        stats = stats.compact();
+    }
+    let mut archive = cfg.archive.as_deref().map(Archive::create).transpose()?;
 
     let bar = pb.clone();
+    let queue_log = event_log.clone();
     tokio::spawn(async move {
-        for link in links {
+        // Different inputs may contain the same links. Only their fingerprints
+        // are kept, so runs with millions of links don't exhaust the memory.
+        let mut queued = HashSet::new();
+        while let Some(link) = recv_link.recv().await {
+            if !queued.insert(fingerprint(&link)) {
+                continue;
+            }
             if let Some(pb) = &bar {
+                pb.set_length(queued.len() as u64);
                 pb.set_message(&link.to_string());
             };
-            send_req.send(link).await.unwrap();
+            if let Some(log) = &queue_log {
+                log.emit(&Event::RequestQueued {
+                    uri: link.uri.as_str(),
+                    source: &link.source,
+                });
+            }
+            // The pool stops receiving requests once it was shut down
+            if send_req.send(link).await.is_err() {
+                break;
+            }
         }
     });
 
+    let retry_clients = if cfg.retry_failures {
+        let timeout = cfg.retry_timeout.map_or(timeout * 2, parse_timeout);
+        let client = client.with_method_and_timeout(reqwest::Method::GET, timeout);
+        Some(vec![client; max_concurrency])
+    } else {
+        None
+    };
+    let clients = vec![client; max_concurrency];
+    let mut clients = ClientPool::new(send_resp, recv_req, clients);
+    if let Some(retry_clients) = retry_clients {
+        clients = clients.retry_failures(retry_clients);
+    }
+    let cancel = clients.cancellation_token();
+
     // Start receiving requests
     tokio::spawn(async move {
-        let clients = vec![client; max_concurrency];
-        let mut clients = ClientPool::new(send_resp, recv_req, clients);
         clients.listen().await;
     });
 
+    // Stop checking on Ctrl-C, but still report the responses received so far
+    let interrupt = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            interrupt.cancel();
+        }
+    });
+
+    // Dead links to different hosts may fail before any working link
+    // succeeds, so only abort if asked to
+    let mut connectivity = cfg
+        .offline_threshold
+        .filter(|&threshold| threshold > 0)
+        .map(Connectivity::new);
+    let mut unavailable = None;
+    let mut fixes = Vec::new();
+    // Tables have a row for every link, not only the failures
+    let mut rows = Vec::new();
     while let Some(response) = recv_resp.recv().await {
         show_progress(&pb, &response, cfg.verbose);
+        if let Some(connectivity) = &mut connectivity {
+            if let Err(e) = connectivity.record(&response) {
+                unavailable.get_or_insert(e);
+                cancel.cancel();
+            }
+        }
+        if let Some(archive) = &mut archive {
+            archive.add(&response)?;
+        }
+        if cfg.fixes.is_some() {
+            fixes.extend(Fix::from_response(&response));
+        }
+        if let Some(sitemap) = &mut sitemap {
+            sitemap.add(&response);
+        }
+        if matches!(cfg.format, Format::Csv | Format::Tsv) {
+            rows.push(Row::from_response(&response));
+        }
         stats.add(response);
     }
+    // Reporting every link as broken wouldn't help anyone
+    if let Some(e) = unavailable {
+        if let Some(pb) = &pb {
+            pb.finish_and_clear();
+        }
+        return Err(anyhow!(e));
+    }
+    collection.await?.map_err(|e| anyhow!(e))?;
+    stats.truncated(truncation.dropped_links(), truncation.truncated_inputs());
+    if let Some(archive) = archive {
+        archive.finish()?;
+    }
+    if let Some(path) = &cfg.fixes {
+        fs::write(path, serde_json::to_string_pretty(&fixes)?)
+            .context("Cannot write fixes to file")?;
+    }
+    if let (Some(path), Some(sitemap)) = (&cfg.sitemap, &sitemap) {
+        fs::write(path, sitemap.to_xml()).context("Cannot write sitemap to file")?;
+    }
+    if let (Some(path), Some(link_graph)) = (&cfg.graph, &link_graph) {
+        let is_dot = path
+            .extension()
+            .map_or(false, |extension| extension == "dot" || extension == "gv");
+        let graph = if is_dot {
+            link_graph.to_dot()
+        } else {
+            serde_json::to_string_pretty(link_graph.as_ref())?
+        };
+        fs::write(path, graph).context("Cannot write link graph to file")?;
+    }
 
     // Note that print statements may interfere with the progress bar, so this
     // must go before printing the stats
@@ -237,7 +694,13 @@ async fn run(cfg: &Config, inputs: Vec<Input>) -> Result<i32> {
         pb.finish_and_clear();
     }
 
-    let stats_formatted = fmt(&stats, &cfg.format)?;
+    if let Some(log) = &event_log {
+        log.emit(&Event::Summary {
+            stats: serde_json::to_value(&stats)?,
+        });
+    }
+
+    let stats_formatted = fmt(&stats, &rows, &cfg.format)?;
     if let Some(output) = &cfg.output {
         fs::write(output, stats_formatted).context("Cannot write status output to file")?;
     } else {
@@ -249,13 +712,173 @@ async fn run(cfg: &Config, inputs: Vec<Input>) -> Result<i32> {
         println!("{}", stats_formatted);
     }
 
-    if stats.is_success() {
+    if let Some(webhook) = &cfg.webhook {
+        if stats.failure_count() >= cfg.webhook_threshold.unwrap_or(1) {
+            let payload = webhook::payload(&stats, cfg.webhook_format);
+            // A missed notification shouldn't hide the result of the checks
+            if let Err(e) = webhook::notify(webhook, &payload).await {
+                eprintln!("{:#}", e);
+            }
+        }
+    }
+
+    // Printed to stderr, so reports on stdout stay valid
+    let comparison = baseline.map(|previous| Comparison::new(previous, stats.failed_uris()));
+    if let Some(comparison) = &comparison {
+        print_comparison(comparison);
+    }
+
+    let success = match &comparison {
+        Some(comparison) => !comparison.is_regression(),
+        None => stats.is_success(success_policy(&cfg)),
+    };
+    if cancel.is_cancelled() {
+        Ok(ExitCode::UnexpectedFailure as i32)
+    } else if success {
         Ok(ExitCode::Success as i32)
     } else {
         Ok(ExitCode::LinkCheckFailure as i32)
     }
 }
 
+/// Print which links broke or were fixed since the baseline
+fn print_comparison(comparison: &Comparison) {
+    eprintln!(
+        "Compared with the baseline: {} newly broken, {} fixed, {} still broken",
+        comparison.newly_broken.len(),
+        comparison.fixed.len(),
+        comparison.still_broken.len()
+    );
+    for uri in &comparison.newly_broken {
+        eprintln!("  + {}", uri);
+    }
+    for uri in &comparison.fixed {
+        eprintln!("  - {}", uri);
+    }
+}
+
+/// Print the links which would be checked, one per line, without checking them.
+/// In verbose mode, their locations are printed as well.
+async fn dump(
+    collector: Collector,
+    inputs: &[Input],
+    max_concurrency: usize,
+    verbose: bool,
+) -> Result<i32> {
+    let (send_link, mut recv_link) = mpsc::channel(max_concurrency);
+    let (result, ()) = tokio::join!(collector.stream_links(inputs, send_link), async {
+        while let Some(link) = recv_link.recv().await {
+            if verbose {
+                println!("{} ({})", link.uri, locations(&link));
+            } else {
+                println!("{}", link.uri);
+            }
+        }
+    });
+    result.map_err(|e| anyhow!(e))?;
+    Ok(ExitCode::Success as i32)
+}
+
+/// A hash of a link to tell duplicates apart. Collisions are as unlikely as
+/// about one in 10^11 for a million links, which is good enough to skip one.
+fn fingerprint(link: &Request) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    link.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Locations of a link, e.g. `README.md:3:7, README.md:8:1`,
+/// or only its input if the link doesn't appear there verbatim
+fn locations(link: &Request) -> String {
+    if link.spans.is_empty() {
+        return link.source.to_string();
+    }
+    let spans: Vec<String> = link
+        .spans
+        .iter()
+        .map(|span| format!("{}:{}", link.source, span))
+        .collect();
+    spans.join(", ")
+}
+
+/// Build the mail policy from the config, if any of its options are set
+fn mail_policy(cfg: &Config) -> Option<MailPolicy> {
+    if cfg.mail_allow.is_empty()
+        && cfg.mail_deny.is_empty()
+        && !cfg.deny_free_mail
+        && !cfg.flag_role_mail
+    {
+        return None;
+    }
+    let domains = |domains: &[String]| -> HashSet<String> {
+        domains.iter().map(|d| d.to_lowercase()).collect()
+    };
+    Some(MailPolicy {
+        allowed_domains: domains(&cfg.mail_allow),
+        denied_domains: domains(&cfg.mail_deny),
+        deny_free_mail: cfg.deny_free_mail,
+        flag_role_accounts: cfg.flag_role_mail,
+        tags: cfg.mail_tag.clone(),
+    })
+}
+
+fn github_app(cfg: &Config) -> Result<Option<GithubApp>> {
+    match (
+        cfg.github_app_id,
+        cfg.github_app_installation,
+        &cfg.github_app_key,
+    ) {
+        (Some(app_id), Some(installation_id), Some(key)) => Ok(Some(GithubApp {
+            app_id,
+            installation_id,
+            private_key: fs::read_to_string(key)
+                .context("Cannot read private key of the GitHub App")?,
+        })),
+        (None, None, None) => Ok(None),
+        _ => Err(anyhow!(
+            "--github-app-id, --github-app-installation and --github-app-key must be used together"
+        )),
+    }
+}
+
+fn parse_mail_check_mode(mode: &str) -> Result<MailCheckMode> {
+    match mode.to_lowercase().as_str() {
+        "syntax" => Ok(MailCheckMode::Syntax),
+        "mx" => Ok(MailCheckMode::Mx),
+        "smtp" => Ok(MailCheckMode::Smtp),
+        _ => Err(anyhow!(
+            "Mail check mode should be one of syntax, mx or smtp, got {}",
+            mode
+        )),
+    }
+}
+
+fn parse_proxy(proxy: &str) -> Result<(String, u16)> {
+    let (host, port) = proxy
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("Proxy should be of the form host:port, got {}", proxy))?;
+    Ok((host.to_owned(), port.parse()?))
+}
+
+fn success_policy(cfg: &Config) -> SuccessPolicy {
+    let non_fatal = &cfg.non_fatal;
+    SuccessPolicy {
+        allow_redirects: non_fatal.contains(&NonFatal::Redirects),
+        allow_timeouts: non_fatal.contains(&NonFatal::Timeouts),
+        allow_unknown: non_fatal.contains(&NonFatal::Unknown),
+        allow_bot_checks: non_fatal.contains(&NonFatal::BotChecks),
+        max_failures: cfg.max_failures,
+        max_error_rate: cfg.max_error_rate,
+    }
+}
+
+fn parse_metadata(pair: &str) -> Result<(String, String)> {
+    let (key, value) = pair
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Metadata should be of the form key=value, got {}", pair))?;
+    Ok((key.trim().to_owned(), value.trim().to_owned()))
+}
+
 fn read_header(input: &str) -> Result<(String, String)> {
     let elements: Vec<_> = input.split('=').collect();
     if elements.len() != 2 {
@@ -264,7 +887,9 @@ fn read_header(input: &str) -> Result<(String, String)> {
             input
         ));
     }
-    Ok((elements[0].into(), elements[1].into()))
+    // Secrets can come from the environment instead of the config file
+    let value = expand_env(elements[1]).map_err(|e| anyhow!(e))?;
+    Ok((elements[0].into(), value))
 }
 
 const fn parse_timeout(timeout: usize) -> Duration {
@@ -275,14 +900,145 @@ fn parse_headers<T: AsRef<str>>(headers: &[T]) -> Result<HeaderMap> {
     let mut out = HeaderMap::new();
     for header in headers {
         let (key, val) = read_header(header.as_ref())?;
-        out.insert(
-            HeaderName::from_bytes(key.as_bytes())?,
-            val.parse().unwrap(),
-        );
+        out.insert(HeaderName::from_bytes(key.as_bytes())?, val.parse()?);
     }
     Ok(out)
 }
 
+/// Parse headers of the form `host:key=value` into headers per host pattern
+fn parse_host_headers<T: AsRef<str>>(headers: &[T]) -> Result<HashMap<String, HeaderMap>> {
+    let mut out: HashMap<String, HeaderMap> = HashMap::new();
+    for header in headers {
+        let header = header.as_ref();
+        let (host, header) = header.split_once(':').ok_or_else(|| {
+            anyhow!(
+                "Host header should be of the form host:key=value, got {}",
+                header
+            )
+        })?;
+        let (key, val) = read_header(header)?;
+        out.entry(host.to_owned())
+            .or_default()
+            .insert(HeaderName::from_bytes(key.as_bytes())?, val.parse()?);
+    }
+    out.values_mut().for_each(redact_headers);
+    Ok(out)
+}
+
+/// The site of a sitemap: the base URL, or else the first URL input
+fn site(base_url: Option<&Url>, inputs: &[Input]) -> Result<Url> {
+    base_url
+        .cloned()
+        .or_else(|| {
+            inputs.iter().find_map(|input| match input {
+                Input::RemoteUrl(url) => Some(url.as_ref().clone()),
+                _ => None,
+            })
+        })
+        .ok_or_else(|| anyhow!("A sitemap needs a base URL or a URL input to know the site"))
+}
+
+/// Parse fixed addresses of the form `host=address` for hosts
+fn parse_resolve<T: AsRef<str>>(entries: &[T]) -> Result<HashMap<String, IpAddr>> {
+    let mut out = HashMap::new();
+    for entry in entries {
+        let entry = entry.as_ref();
+        let (host, address) = entry.split_once('=').ok_or_else(|| {
+            anyhow!(
+                "Resolve entry should be of the form host=address, got {}",
+                entry
+            )
+        })?;
+        let address = address.trim_start_matches('[').trim_end_matches(']');
+        let address = address
+            .parse()
+            .map_err(|_| anyhow!("Invalid IP address for {}: {}", host, address))?;
+        out.insert(host.to_owned(), address);
+    }
+    Ok(out)
+}
+
+/// Parse a remapping of the form `from to`, where both are URL prefixes
+fn parse_remap(remap: &str) -> Result<Remap> {
+    let remap = &expand_env(remap).map_err(|e| anyhow!(e))?;
+    match remap.split_whitespace().collect::<Vec<_>>()[..] {
+        [from, to] => {
+            Url::parse(to).with_context(|| format!("Invalid URL to remap to: {}", to))?;
+            Ok(Remap::new(from, to))
+        }
+        _ => Err(anyhow!(
+            "Remap should be of the form 'from to', got {}",
+            remap
+        )),
+    }
+}
+
+/// Parse the networks of `--exclude-network` in CIDR notation
+fn parse_networks(networks: &[String]) -> Result<Vec<IpNetwork>> {
+    networks
+        .iter()
+        .map(|network| IpNetwork::try_from(network.as_str()).map_err(|e| anyhow!(e)))
+        .collect()
+}
+
+/// Parse an optional CSS selector or XPath expression for HTML elements
+fn parse_selector(selector: Option<&str>) -> Result<Option<Selector>> {
+    selector
+        .map(|selector| Selector::try_from(selector).map_err(|e| anyhow!(e)))
+        .transpose()
+}
+
+/// Parse a status rule of the form `[host:]code=status`,
+/// where status is `ok`, `warning` or `excluded`
+fn parse_status_rule(rule: &str) -> Result<StatusRule> {
+    let (matcher, status) = rule.split_once('=').ok_or_else(|| {
+        anyhow!(
+            "Status rule should be of the form [host:]code=status, got {}",
+            rule
+        )
+    })?;
+    let (host, code) = match matcher.rsplit_once(':') {
+        Some((host, code)) => (Some(host.trim_end_matches('.').to_lowercase()), code),
+        None => (None, matcher),
+    };
+    let status = match status {
+        "ok" => Reclassification::Ok,
+        "warning" => Reclassification::Warning,
+        "excluded" => Reclassification::Excluded,
+        _ => {
+            return Err(anyhow!(
+                "Status of a rule should be ok, warning or excluded, got {}",
+                status
+            ))
+        }
+    };
+    Ok(StatusRule {
+        host,
+        code: StatusCode::from_bytes(code.as_bytes())?,
+        status,
+    })
+}
+
+/// Parse a header assertion of the form `[links] header=value`, where links
+/// and value are regular expressions
+fn parse_header_assertion(rule: &str) -> Result<HeaderAssertion> {
+    let (links, assertion) = match rule.trim().split_once(char::is_whitespace) {
+        Some((links, assertion)) => (Some(Regex::new(links)?), assertion.trim_start()),
+        None => (None, rule.trim()),
+    };
+    let (header, value) = assertion.split_once('=').ok_or_else(|| {
+        anyhow!(
+            "Header assertion should be of the form [links] header=value, got {}",
+            rule
+        )
+    })?;
+    Ok(HeaderAssertion {
+        links,
+        header: HeaderName::from_str(header)?,
+        value: Regex::new(value)?,
+    })
+}
+
 fn parse_statuscodes<T: AsRef<str>>(accept: T) -> Result<HashSet<StatusCode>> {
     let mut statuscodes = HashSet::new();
     for code in accept.as_ref().split(',') {
@@ -293,6 +1049,7 @@ fn parse_statuscodes<T: AsRef<str>>(accept: T) -> Result<HashSet<StatusCode>> {
 }
 
 fn parse_basic_auth(auth: &str) -> Result<Authorization<Basic>> {
+    let auth = &expand_env(auth).map_err(|e| anyhow!(e))?;
     let params: Vec<_> = auth.split(':').collect();
     if params.len() != 2 {
         return Err(anyhow!(
@@ -305,14 +1062,23 @@ fn parse_basic_auth(auth: &str) -> Result<Authorization<Basic>> {
 
 #[cfg(test)]
 mod test {
-    use std::{array, collections::HashSet};
+    use std::{array, collections::HashSet, net::IpAddr};
 
     use headers::{HeaderMap, HeaderMapExt};
     use http::StatusCode;
     use pretty_assertions::assert_eq;
-    use reqwest::header;
+    use reqwest::{header, Url};
 
-    use super::{parse_basic_auth, parse_headers, parse_statuscodes};
+    use lychee_lib::{
+        collector::Input,
+        post_process::{Reclassification, StatusRule},
+    };
+
+    use super::{
+        parse_basic_auth, parse_header_assertion, parse_headers, parse_host_headers,
+        parse_networks, parse_proxy, parse_remap, parse_resolve, parse_selector, parse_status_rule,
+        parse_statuscodes, site,
+    };
 
     #[test]
     fn test_parse_custom_headers() {
@@ -321,6 +1087,141 @@ mod test {
         assert_eq!(parse_headers(&["accept=text/html"]).unwrap(), custom);
     }
 
+    #[test]
+    fn test_parse_headers_env() {
+        std::env::set_var("LYCHEE_TEST_HEADER_TOKEN", "secret");
+        let mut custom = HeaderMap::new();
+        custom.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(
+            parse_headers(&["authorization=Bearer ${LYCHEE_TEST_HEADER_TOKEN}"]).unwrap(),
+            custom
+        );
+        assert!(parse_headers(&["authorization=${LYCHEE_TEST_HEADER_MISSING}"]).is_err());
+        // e.g. a token which was read from a file with its trailing newline
+        std::env::set_var("LYCHEE_TEST_HEADER_NEWLINE", "secret\n");
+        assert!(parse_headers(&["authorization=${LYCHEE_TEST_HEADER_NEWLINE}"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_remap() {
+        let remap = parse_remap("https://docs.example.com/  http://localhost:8000/").unwrap();
+        assert_eq!(remap.from, "https://docs.example.com/");
+        assert_eq!(remap.to, "http://localhost:8000/");
+        assert!(parse_remap("https://docs.example.com/").is_err());
+        assert!(parse_remap("https://docs.example.com/ localhost").is_err());
+
+        std::env::set_var("LYCHEE_TEST_STAGING", "staging.example.com");
+        let remap =
+            parse_remap("https://docs.example.com/ https://${LYCHEE_TEST_STAGING}/").unwrap();
+        assert_eq!(remap.to, "https://staging.example.com/");
+    }
+
+    #[test]
+    fn test_site() {
+        let base_url = Url::parse("https://example.org/docs/").unwrap();
+        let inputs = [
+            Input::String("https://example.com".to_owned()),
+            Input::new("https://example.net/blog/", false),
+        ];
+        assert_eq!(site(Some(&base_url), &inputs).unwrap(), base_url);
+        assert_eq!(
+            site(None, &inputs).unwrap(),
+            Url::parse("https://example.net/blog/").unwrap()
+        );
+        assert!(site(None, &inputs[..1]).is_err());
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert!(parse_selector(None).unwrap().is_none());
+        assert!(parse_selector(Some("main, article")).unwrap().is_some());
+        assert!(parse_selector(Some("//div[@class='sidebar']"))
+            .unwrap()
+            .is_some());
+        assert!(parse_selector(Some("nav > a")).is_err());
+    }
+
+    #[test]
+    fn test_parse_resolve() {
+        let actual = parse_resolve(&["example.com=10.0.0.5", "example.org=[::1]"]).unwrap();
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual["example.com"], IpAddr::from([10, 0, 0, 5]));
+        assert_eq!(actual["example.org"], "::1".parse::<IpAddr>().unwrap());
+        assert!(parse_resolve(&["example.com"]).is_err());
+        assert!(parse_resolve(&["example.com=staging"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_networks() {
+        let networks =
+            parse_networks(&["10.42.0.0/16".to_owned(), "2001:db8::/32".to_owned()]).unwrap();
+        assert!(networks[0].contains(IpAddr::from([10, 42, 1, 2])));
+        assert!(networks[1].contains("2001:db8::1".parse().unwrap()));
+        assert!(parse_networks(&["10.42.0.0/33".to_owned()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_host_headers() {
+        let actual = parse_host_headers(&[
+            "crates.io:accept=text/html",
+            "*.example.com:authorization=Bearer token",
+        ])
+        .unwrap();
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual["crates.io"][header::ACCEPT], "text/html");
+        assert_eq!(
+            actual["*.example.com"][header::AUTHORIZATION],
+            "Bearer token"
+        );
+
+        assert!(parse_host_headers(&["accept=text/html"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_status_rule() {
+        assert_eq!(
+            parse_status_rule("LinkedIn.com:999=excluded").unwrap(),
+            StatusRule {
+                host: Some("linkedin.com".to_owned()),
+                code: StatusCode::from_u16(999).unwrap(),
+                status: Reclassification::Excluded,
+            }
+        );
+        assert_eq!(
+            parse_status_rule("429=warning").unwrap(),
+            StatusRule {
+                host: None,
+                code: StatusCode::TOO_MANY_REQUESTS,
+                status: Reclassification::Warning,
+            }
+        );
+        assert!(parse_status_rule("429").is_err());
+        assert!(parse_status_rule("429=fine").is_err());
+        assert!(parse_status_rule("example.com:abc=ok").is_err());
+    }
+
+    #[test]
+    fn test_parse_header_assertion() {
+        let assertion = parse_header_assertion(r"\.pdf$ Content-Type=^application/pdf").unwrap();
+        assert_eq!(assertion.links.unwrap().as_str(), r"\.pdf$");
+        assert_eq!(assertion.header, http::header::CONTENT_TYPE);
+        assert_eq!(assertion.value.as_str(), "^application/pdf");
+
+        let assertion = parse_header_assertion("x-robots-tag=^$").unwrap();
+        assert!(assertion.links.is_none());
+        assert!(parse_header_assertion(r"\.pdf$ content-type").is_err());
+        assert!(parse_header_assertion(r"\.pdf$ content/type=x").is_err());
+    }
+
+    #[test]
+    fn test_parse_proxy() {
+        assert_eq!(
+            parse_proxy("relay.example.com:1080").unwrap(),
+            ("relay.example.com".to_owned(), 1080)
+        );
+        assert!(parse_proxy("relay.example.com").is_err());
+    }
+
     #[test]
     fn test_parse_statuscodes() {
         let actual = parse_statuscodes("200,204,301").unwrap();
@@ -0,0 +1,168 @@
+use lychee_lib::{Input, ResponseBody};
+
+use crate::stats::{owners, ResponseStats};
+
+/// A human-readable report of a run, e.g. for a pull request comment
+/// or a published page
+pub(crate) trait Formatter {
+    /// Format the summary and the failures grouped by input
+    fn format(&self, stats: &ResponseStats) -> String;
+}
+
+/// Report in Markdown, e.g. for a comment on a pull request
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct MarkdownFormatter;
+
+/// Standalone HTML page, e.g. to publish as an artifact
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct HtmlFormatter;
+
+impl Formatter for MarkdownFormatter {
+    fn format(&self, stats: &ResponseStats) -> String {
+        let mut out = String::from("## Summary\n\n| Status | Count |\n|:-------|------:|\n");
+        for (title, count) in stats.summary() {
+            out.push_str(&format!("| {} | {} |\n", title, count));
+        }
+
+        for (input, responses) in stats.failures() {
+            out.push_str(&format!(
+                "\n## Errors in {}\n\n",
+                markdown_escape(&input.to_string())
+            ));
+            if let Some(owners) = owners(responses.iter().copied()) {
+                out.push_str(&format!("Owners: {}\n\n", markdown_escape(owners)));
+            }
+            for response in responses {
+                out.push_str(&format!(
+                    "* {} <{}> {}{}\n",
+                    response.status.icon(),
+                    response.uri,
+                    markdown_escape(&response.status.to_string()),
+                    markdown_escape(&locations(input, response))
+                ));
+            }
+        }
+        out
+    }
+}
+
+impl Formatter for HtmlFormatter {
+    fn format(&self, stats: &ResponseStats) -> String {
+        let mut out = String::from(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>lychee report</title>\n</head>\n<body>\n\
+             <h1>Summary</h1>\n<table>\n<tr><th>Status</th><th>Count</th></tr>\n",
+        );
+        for (title, count) in stats.summary() {
+            out.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>\n", title, count));
+        }
+        out.push_str("</table>\n");
+
+        for (input, responses) in stats.failures() {
+            out.push_str(&format!(
+                "<h2>Errors in {}</h2>\n",
+                html_escape(&input.to_string())
+            ));
+            if let Some(owners) = owners(responses.iter().copied()) {
+                out.push_str(&format!("<p>Owners: {}</p>\n", html_escape(owners)));
+            }
+            out.push_str("<ul>\n");
+            for response in responses {
+                let uri = html_escape(response.uri.as_str());
+                out.push_str(&format!(
+                    "<li>{} <a href=\"{}\">{}</a> {}{}</li>\n",
+                    response.status.icon(),
+                    uri,
+                    uri,
+                    html_escape(&response.status.to_string()),
+                    html_escape(&locations(input, response))
+                ));
+            }
+            out.push_str("</ul>\n");
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+/// Locations of a link in its input, e.g. ` at README.md:3:7, README.md:8:1`
+fn locations(input: &Input, response: &ResponseBody) -> String {
+    if response.spans.is_empty() {
+        return String::new();
+    }
+    let spans: Vec<String> = response
+        .spans
+        .iter()
+        .map(|span| format!("{}:{}", input, span))
+        .collect();
+    format!(" at {}", spans.join(", "))
+}
+
+/// Escape characters with a meaning in Markdown text
+fn markdown_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use lychee_lib::{ErrorKind, Input, Response, ResponseBody, Span, Uri};
+    use reqwest::Url;
+
+    use super::{Formatter, HtmlFormatter, MarkdownFormatter};
+    use crate::stats::ResponseStats;
+
+    fn stats() -> ResponseStats {
+        let mut body = ResponseBody::new(
+            Uri::from(Url::parse("https://example.org/a?b=<c>").unwrap()),
+            ErrorKind::MissingContent("example.org".to_owned()).into(),
+        );
+        body.spans = vec![Span {
+            start: 30,
+            end: 53,
+            line: 3,
+            column: 7,
+        }];
+        body.metadata
+            .insert("owners".to_owned(), "@docs-team".to_owned());
+        let mut stats = ResponseStats::new();
+        stats.add(Response(Input::FsPath(PathBuf::from("README.md")), body));
+        stats
+    }
+
+    #[test]
+    fn test_markdown() {
+        let report = MarkdownFormatter.format(&stats());
+        assert!(report.contains("| \u{1f6ab} Errors | 1 |"));
+        assert!(report.contains("## Errors in README.md\n\nOwners: @docs-team\n"));
+        assert!(report.contains("<https://example.org/a?b=%3Cc%3E>"));
+        assert!(report.contains(" at README.md:3:7\n"));
+    }
+
+    #[test]
+    fn test_html() {
+        let report = HtmlFormatter.format(&stats());
+        assert!(report.contains("<tr><td>\u{1f6ab} Errors</td><td>1</td></tr>"));
+        assert!(report.contains("<h2>Errors in README.md</h2>\n<p>Owners: @docs-team</p>"));
+        assert!(report.contains("href=\"https://example.org/a?b=%3Cc%3E\""));
+        assert!(report.ends_with("</html>\n"));
+    }
+}
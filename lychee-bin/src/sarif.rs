@@ -0,0 +1,191 @@
+use lychee_lib::{Input, ResponseBody, Status};
+use serde_json::{json, Value};
+
+use crate::stats::ResponseStats;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// A kind of reported link
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Rule {
+    pub(crate) id: &'static str,
+    /// `error` or `warning`
+    pub(crate) level: &'static str,
+    pub(crate) description: &'static str,
+}
+
+const BROKEN_LINK: Rule = Rule {
+    id: "broken-link",
+    level: "error",
+    description: "Link is broken",
+};
+const TIMEOUT: Rule = Rule {
+    id: "timeout",
+    level: "warning",
+    description: "Link timed out",
+};
+const REDIRECT: Rule = Rule {
+    id: "redirect",
+    level: "warning",
+    description: "Link redirects",
+};
+const BOT_CHECK: Rule = Rule {
+    id: "bot-check",
+    level: "warning",
+    description: "Link is protected by a bot check",
+};
+const WARNING: Rule = Rule {
+    id: "warning",
+    level: "warning",
+    description: "Link has a status which is configured as a warning",
+};
+const RULES: &[Rule] = &[BROKEN_LINK, TIMEOUT, REDIRECT, BOT_CHECK, WARNING];
+
+/// Convert the failures of a run into a log in the Static Analysis Results
+/// Interchange Format (SARIF) 2.1.0, which code scanning tools like
+/// GitHub Code Scanning use to annotate files.
+/// See <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html>
+pub(crate) fn sarif(stats: &ResponseStats) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.description },
+                "defaultConfiguration": { "level": rule.level },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = stats
+        .failures()
+        .into_iter()
+        .flat_map(|(input, responses)| {
+            responses
+                .into_iter()
+                .flat_map(move |response| results(input, response))
+        })
+        .collect();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "lychee",
+                    "informationUri": "https://github.com/lycheeverse/lychee",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// One result per location of the link, or a single one
+/// if the link can't be located in its input
+fn results(input: &Input, response: &ResponseBody) -> Vec<Value> {
+    let rule = rule(&response.status);
+    let result = |locations: Vec<Value>| {
+        json!({
+            "ruleId": rule.id,
+            "level": rule.level,
+            "message": { "text": format!("{} ({})", response.uri, response.status) },
+            "locations": locations,
+        })
+    };
+
+    let artifact = match artifact_uri(input) {
+        Some(artifact) => artifact,
+        None => return vec![result(vec![])],
+    };
+    if response.spans.is_empty() {
+        return vec![result(vec![json!({
+            "physicalLocation": { "artifactLocation": { "uri": artifact } }
+        })])];
+    }
+    response
+        .spans
+        .iter()
+        .map(|span| {
+            result(vec![json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": artifact },
+                    "region": { "startLine": span.line, "startColumn": span.column },
+                }
+            })])
+        })
+        .collect()
+}
+
+/// The rule a failed response is reported under
+pub(crate) const fn rule(status: &Status) -> Rule {
+    match status {
+        Status::Timeout(_) => TIMEOUT,
+        Status::Redirected(_) => REDIRECT,
+        Status::ProtectedByBotCheck(_) => BOT_CHECK,
+        Status::Warning(_) => WARNING,
+        _ => BROKEN_LINK,
+    }
+}
+
+/// URI of a file or website, relative for local files
+/// so they match the paths in the repository
+pub(crate) fn artifact_uri(input: &Input) -> Option<String> {
+    match input {
+        Input::FsPath(path) => Some(
+            path.strip_prefix("./")
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+        ),
+        Input::RemoteUrl(url) => Some(url.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use http::StatusCode;
+    use lychee_lib::{Input, Response, ResponseBody, Span, Status, Uri};
+    use reqwest::Url;
+    use serde_json::json;
+
+    use super::sarif;
+    use crate::stats::ResponseStats;
+
+    #[test]
+    fn test_sarif() {
+        let mut body = ResponseBody::new(
+            Uri::from(Url::parse("https://example.org/timeout").unwrap()),
+            Status::Timeout(Some(StatusCode::REQUEST_TIMEOUT)),
+        );
+        body.spans = vec![Span {
+            start: 30,
+            end: 56,
+            line: 3,
+            column: 7,
+        }];
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::FsPath(PathBuf::from("./docs/README.md")),
+            body,
+        ));
+
+        let log = sarif(&stats);
+        assert_eq!(log["version"], "2.1.0");
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "timeout");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "docs/README.md" },
+                "region": { "startLine": 3, "startColumn": 7 },
+            })
+        );
+    }
+}
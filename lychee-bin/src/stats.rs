@@ -4,24 +4,68 @@ use std::{
 };
 
 use console::style;
-use lychee_lib::{Input, Response, ResponseBody, Status};
+use lychee_lib::{ErrorKind, Input, Response, ResponseBody, SkipReason, Status, Uri};
 use pad::{Alignment, PadStr};
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 
 // Maximum padding for each entry in the final statistics output
 const MAX_PADDING: usize = 20;
 
+// Number of failed links listed in the ranking by the inputs referencing them
+const MAX_RANKED_FAILURES: usize = 10;
+
 pub(crate) fn color_response(response: &ResponseBody) -> String {
     let out = match response.status {
         Status::Ok(_) => style(response).green().bright(),
-        Status::Excluded | Status::Unsupported(_) => style(response).dim(),
+        Status::Excluded | Status::Unsupported(_) | Status::Skipped(_) => style(response).dim(),
         Status::Redirected(_) => style(response),
-        Status::Timeout(_) => style(response).yellow().bright(),
+        Status::Timeout(_) | Status::ProtectedByBotCheck(_) | Status::Warning(_) => {
+            style(response).yellow().bright()
+        }
         Status::Error(_) => style(response).red().bright(),
     };
     out.to_string()
 }
 
+/// Decides which result categories, besides failures, fail a run,
+/// and how many failures a run can have.
+/// By default, every failure, redirect, timeout, bot check and unknown result is fatal.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SuccessPolicy {
+    /// Don't fail the run because of redirects
+    pub(crate) allow_redirects: bool,
+    /// Don't fail the run because of timeouts
+    pub(crate) allow_timeouts: bool,
+    /// Don't fail the run because of results with an unknown outcome,
+    /// e.g. responses with a non-standard status code
+    pub(crate) allow_unknown: bool,
+    /// Don't fail the run because of bot protection challenges
+    pub(crate) allow_bot_checks: bool,
+    /// Don't fail the run if there are at most this many failed links
+    pub(crate) max_failures: Option<usize>,
+    /// Don't fail the run if at most this share (0.0 to 1.0) of the checked links failed
+    pub(crate) max_error_rate: Option<f64>,
+}
+
+impl SuccessPolicy {
+    /// Allow up to `max` failed links
+    pub(crate) const fn max_failures(self, max: usize) -> Self {
+        Self {
+            max_failures: Some(max),
+            ..self
+        }
+    }
+
+    /// Allow failed links as long as their share of the checked links is at most `rate`
+    pub(crate) const fn fail_if_error_rate_above(self, rate: f64) -> Self {
+        Self {
+            max_error_rate: Some(rate),
+            ..self
+        }
+    }
+}
+
 #[derive(Default, Serialize)]
 pub(crate) struct ResponseStats {
     total: usize,
@@ -30,8 +74,48 @@ pub(crate) struct ResponseStats {
     timeouts: usize,
     redirects: usize,
     excludes: usize,
+    /// Links which weren't checked, e.g. because the run was cancelled.
+    /// They don't fail the run.
+    skipped: usize,
+    /// Number of skipped links by the reason they weren't checked
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    skip_reasons: HashMap<SkipReason, usize>,
+    /// Links which weren't checked because of the limits on the number of links.
+    /// They don't fail the run.
+    #[serde(skip_serializing_if = "is_zero")]
+    truncated: usize,
+    /// Inputs which had more links than allowed per input
+    #[serde(skip_serializing_if = "is_zero")]
+    truncated_inputs: usize,
+    /// Links which were blocked by a bot protection service (e.g. Cloudflare)
+    bot_checks: usize,
+    /// Links whose status a rule marks as a warning. They don't fail the run.
+    warnings: usize,
+    /// Links with an unknown outcome, e.g. responses with a non-standard
+    /// status code like 999. They fail the run unless allowed by the policy.
     errors: usize,
+    /// Links which violate a policy, e.g. links to unapproved hosts
+    policy_violations: usize,
     fail_map: HashMap<Input, HashSet<ResponseBody>>,
+    /// Inputs referencing each failed link, serialized as their number,
+    /// to rank the failures by their impact
+    #[serde(
+        rename = "fail_references",
+        serialize_with = "serialize_reference_counts",
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    fail_sources: HashMap<Uri, HashSet<Input>>,
+    /// Statistics for each input tag (without failure details)
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    tag_map: HashMap<String, ResponseStats>,
+    /// Statistics for each input (without failure details),
+    /// only collected when grouping by input
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    input_map: HashMap<Input, ResponseStats>,
+    #[serde(skip)]
+    group_by_input: bool,
+    #[serde(skip)]
+    compact: bool,
 }
 
 impl ResponseStats {
@@ -40,36 +124,239 @@ impl ResponseStats {
         Self::default()
     }
 
+    /// Collect statistics for each input as well,
+    /// and list the failures grouped under their input
+    pub(crate) fn grouped_by_input() -> Self {
+        Self {
+            group_by_input: true,
+            ..Self::default()
+        }
+    }
+
+    /// Only list errors and timeouts, e.g. for runs with millions of links.
+    /// Redirects, warnings and bot checks are counted, but not listed.
+    pub(crate) fn compact(self) -> Self {
+        Self {
+            compact: true,
+            ..self
+        }
+    }
+
     pub(crate) fn add(&mut self, response: Response) {
-        let Response(source, ResponseBody { ref status, .. }) = response;
+        let Response(
+            source,
+            ResponseBody {
+                ref uri,
+                ref status,
+                ref tags,
+                ..
+            },
+        ) = response;
         if status.is_unsupported() {
             // Silently skip unsupported URIs
             return;
         }
 
+        self.count(status);
+        for tag in tags {
+            self.tag_map.entry(tag.clone()).or_default().count(status);
+        }
+        if self.group_by_input {
+            self.input_map
+                .entry(source.clone())
+                .or_default()
+                .count(status);
+        }
+        if status.is_failure() {
+            self.fail_sources
+                .entry(uri.clone())
+                .or_default()
+                .insert(source.clone());
+        }
+
+        let listed = match status {
+            Status::Error(_) | Status::Timeout(_) => true,
+            Status::Redirected(_) | Status::ProtectedByBotCheck(_) | Status::Warning(_) => {
+                !self.compact
+            }
+            _ => false,
+        };
+        if listed {
+            let fail = self.fail_map.entry(source).or_default();
+            fail.insert(response.1);
+        };
+    }
+
+    fn count(&mut self, status: &Status) {
         self.total += 1;
 
         match status {
             Status::Ok(_) => self.successful += 1,
+            Status::Error(e) if e.is_policy_violation() => self.policy_violations += 1,
+            Status::Error(e) if matches!(**e, ErrorKind::UnexpectedStatusCode(_)) => {
+                self.errors += 1;
+            }
             Status::Error(_) => self.failures += 1,
             Status::Timeout(_) => self.timeouts += 1,
             Status::Redirected(_) => self.redirects += 1,
             Status::Excluded => self.excludes += 1,
+            Status::ProtectedByBotCheck(_) => self.bot_checks += 1,
+            Status::Warning(_) => self.warnings += 1,
+            Status::Skipped(reason) => {
+                self.skipped += 1;
+                *self.skip_reasons.entry(*reason).or_default() += 1;
+            }
             Status::Unsupported(_) => (), // Just skip unsupported URI
         }
-
-        if matches!(
-            status,
-            Status::Error(_) | Status::Timeout(_) | Status::Redirected(_)
-        ) {
-            let fail = self.fail_map.entry(source).or_default();
-            fail.insert(response.1);
-        };
     }
 
     #[inline]
-    pub(crate) const fn is_success(&self) -> bool {
-        self.total == self.successful + self.excludes
+    pub(crate) fn is_success(&self, policy: SuccessPolicy) -> bool {
+        self.failures_within_budget(policy)
+            && self.policy_violations == 0
+            && (policy.allow_redirects || self.redirects == 0)
+            && (policy.allow_timeouts || self.timeouts == 0)
+            && (policy.allow_unknown || self.errors == 0)
+            && (policy.allow_bot_checks || self.bot_checks == 0)
+    }
+
+    /// Whether the failed links are within the budget of the policy.
+    /// Without any budget, a single failure fails the run.
+    fn failures_within_budget(&self, policy: SuccessPolicy) -> bool {
+        if self.failures == 0 {
+            return true;
+        }
+        if policy.max_failures.is_none() && policy.max_error_rate.is_none() {
+            return false;
+        }
+        policy.max_failures.map_or(true, |max| self.failures <= max)
+            && policy
+                .max_error_rate
+                .map_or(true, |max| self.error_rate() <= max)
+    }
+
+    /// Share of failed links among the checked (not excluded or skipped) links
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn error_rate(&self) -> f64 {
+        let checked = self.total - self.excludes - self.skipped;
+        if checked == 0 {
+            return 0.0;
+        }
+        self.failures as f64 / checked as f64
+    }
+
+    /// Responses which fail the run, or could, by input.
+    /// Sorted by input, then by the number of inputs referencing the link
+    /// (most referenced first) and URI, so reports are stable between runs.
+    pub(crate) fn failures(&self) -> Vec<(&Input, Vec<&ResponseBody>)> {
+        let mut failures: Vec<(&Input, Vec<&ResponseBody>)> = self
+            .fail_map
+            .iter()
+            .map(|(input, responses)| (input, self.ranked(responses)))
+            .collect();
+        failures.sort_by_cached_key(|(input, _)| input.to_string());
+        failures
+    }
+
+    /// The failed links over all inputs
+    pub(crate) fn failed_uris(&self) -> impl Iterator<Item = Uri> + '_ {
+        self.fail_map
+            .values()
+            .flatten()
+            .map(|response| response.uri.clone())
+    }
+
+    /// Number of inputs referencing a failed link
+    fn references(&self, uri: &Uri) -> usize {
+        self.fail_sources.get(uri).map_or(0, HashSet::len)
+    }
+
+    /// Failed links with the number of inputs referencing them,
+    /// most referenced first
+    pub(crate) fn impact(&self) -> Vec<(&Uri, usize)> {
+        let mut impact: Vec<(&Uri, usize)> = self
+            .fail_sources
+            .iter()
+            .map(|(uri, sources)| (uri, sources.len()))
+            .collect();
+        impact.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+        impact
+    }
+
+    /// Responses sorted by the number of inputs referencing their link,
+    /// then by URI
+    fn ranked<'a>(&self, responses: &'a HashSet<ResponseBody>) -> Vec<&'a ResponseBody> {
+        let mut responses: Vec<&ResponseBody> = responses.iter().collect();
+        responses.sort_by(|a, b| {
+            self.references(&b.uri)
+                .cmp(&self.references(&a.uri))
+                .then_with(|| a.uri.cmp(&b.uri))
+        });
+        responses
+    }
+
+    /// Write the failed links of an input with their locations
+    fn write_failures(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        input: &Input,
+        responses: &HashSet<ResponseBody>,
+    ) -> fmt::Result {
+        for response in self.ranked(responses) {
+            write!(f, "\n{}", color_response(response))?;
+            // Locations in the style of compilers, so editors can jump to them
+            for span in &response.spans {
+                write!(f, "\n  at {}:{}", input, span)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Titles (with icons) and counts of the result categories.
+    /// Skipped and truncated links, bot checks, warnings and policy violations are only
+    /// included if there are any.
+    pub(crate) fn summary(&self) -> Vec<(&'static str, usize)> {
+        let mut summary = vec![
+            ("\u{1f50d} Total", self.total),          // 🔍
+            ("\u{2705} Successful", self.successful), // ✅
+            ("\u{23f3} Timeouts", self.timeouts),     // ⏳
+            ("\u{1f500} Redirected", self.redirects), // 🔀
+            ("\u{1f47b} Excluded", self.excludes),    // 👻
+        ];
+        if self.skipped > 0 {
+            summary.push(("\u{23ed} Skipped", self.skipped)); // ⏭
+        }
+        if self.truncated > 0 {
+            summary.push(("\u{2702} Truncated", self.truncated)); // ✂
+        }
+        if self.bot_checks > 0 {
+            summary.push(("\u{1f916} Bot checks", self.bot_checks)); // 🤖
+        }
+        if self.warnings > 0 {
+            summary.push(("\u{2755} Warnings", self.warnings)); // ❕
+        }
+        summary.push(("\u{1f6ab} Errors", self.errors + self.failures)); // 🚫
+        if self.policy_violations > 0 {
+            summary.push(("\u{26d4} Violations", self.policy_violations)); // ⛔
+        }
+        summary
+    }
+
+    /// Record the links which weren't checked because of the limits
+    /// on the number of links
+    pub(crate) fn truncated(&mut self, links: usize, inputs: usize) {
+        self.truncated = links;
+        self.truncated_inputs = inputs;
+    }
+
+    /// Number of checked links
+    pub(crate) const fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Number of failed links, as counted against `--max-failures`
+    pub(crate) const fn failure_count(&self) -> usize {
+        self.failures
     }
 
     #[inline]
@@ -100,37 +387,112 @@ impl Display for ResponseStats {
 
         writeln!(f, "\u{1f4dd} Summary")?; // 📝
         writeln!(f, "{}", separator)?;
-        write_stat(f, "\u{1f50d} Total", self.total, true)?; // 🔍
-        write_stat(f, "\u{2705} Successful", self.successful, true)?; // ✅
-        write_stat(f, "\u{23f3} Timeouts", self.timeouts, true)?; // ⏳
-        write_stat(f, "\u{1f500} Redirected", self.redirects, true)?; // 🔀
-        write_stat(f, "\u{1f47b} Excluded", self.excludes, true)?; // 👻
-        write_stat(f, "\u{1f6ab} Errors", self.errors + self.failures, false)?; // 🚫
+        let summary = self.summary();
+        for (i, (title, stat)) in summary.iter().enumerate() {
+            write_stat(f, title, *stat, i + 1 < summary.len())?;
+        }
+
+        // Links referenced by many inputs are the most important to fix
+        let impact = self.impact();
+        if impact.first().map_or(false, |(_, count)| *count > 1) {
+            write!(f, "\n\n\u{1f525} Most referenced failures")?; // 🔥
+            for (uri, count) in impact.iter().take(MAX_RANKED_FAILURES) {
+                let inputs = if *count == 1 { "input" } else { "inputs" };
+                write!(f, "\n{} ({} {})", uri, count, inputs)?;
+            }
+        }
+
+        let mut tags: Vec<_> = self.tag_map.iter().collect();
+        tags.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (tag, stats) in tags {
+            write!(
+                f,
+                "\n\n\u{1f3f7} {}: {} total, {} successful, {} errors", // 🏷
+                tag,
+                stats.total,
+                stats.successful,
+                stats.errors + stats.failures
+            )?;
+        }
+
+        if self.group_by_input {
+            let mut inputs: Vec<_> = self.input_map.iter().collect();
+            inputs.sort_by_cached_key(|(input, _)| input.to_string());
+            for (input, stats) in inputs {
+                write!(
+                    f,
+                    "\n\n\u{1f4c4} {}: {} total, {} successful, {} errors", // 📄
+                    input,
+                    stats.total,
+                    stats.successful,
+                    stats.errors + stats.failures
+                )?;
+                if let Some(responses) = self.fail_map.get(input) {
+                    if let Some(owners) = owners(responses) {
+                        write!(f, " (owners: {})", owners)?;
+                    }
+                    self.write_failures(f, input, responses)?;
+                }
+            }
+            return Ok(());
+        }
 
         for (input, responses) in &self.fail_map {
             // Using leading newlines over trailing ones (e.g. `writeln!`)
             // lets us avoid extra newlines without any additional logic.
             write!(f, "\n\nErrors in {}", input)?;
-            for response in responses {
-                write!(f, "\n{}", color_response(response))?
+            if let Some(owners) = owners(responses) {
+                write!(f, " (owners: {})", owners)?;
             }
+            self.write_failures(f, input, responses)?;
         }
 
         Ok(())
     }
 }
 
+/// The owners of an input (from CODEOWNERS), which all of its links
+/// have in their metadata
+pub(crate) fn owners<'a>(responses: impl IntoIterator<Item = &'a ResponseBody>) -> Option<&'a str> {
+    responses
+        .into_iter()
+        .find_map(|response| response.metadata.get("owners"))
+        .map(String::as_str)
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // required by serde
+const fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+/// Serialize the inputs referencing each failed link as their number
+fn serialize_reference_counts<S: Serializer>(
+    sources: &HashMap<Uri, HashSet<Input>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        sources
+            .iter()
+            .map(|(uri, sources)| (uri.as_str(), sources.len())),
+    )
+}
+
 #[cfg(test)]
 mod test {
-    use std::collections::{HashMap, HashSet};
+    use std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+    };
 
     use http::StatusCode;
-    use lychee_lib::{ClientBuilder, Input, Response, ResponseBody, Status, Uri};
+    use lychee_lib::{
+        ClientBuilder, ErrorKind, Input, Response, ResponseBody, SkipReason, Span, Status, Uri,
+    };
     use pretty_assertions::assert_eq;
     use reqwest::Url;
     use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
 
-    use super::ResponseStats;
+    use super::{ResponseStats, SuccessPolicy};
 
     fn website(url: &str) -> Uri {
         Uri::from(Url::parse(url).expect("Expected valid Website URI"))
@@ -161,15 +523,325 @@ mod test {
         let mut stats = ResponseStats::new();
         assert!(stats.is_empty());
 
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(website("http://example.org/ok"), Status::Ok(StatusCode::OK)),
+        ));
+
+        assert!(!stats.is_empty());
+    }
+
+    #[test]
+    fn test_stats_success_policy() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(
+                website("http://example.org/redirect"),
+                Status::Redirected(StatusCode::PERMANENT_REDIRECT),
+            ),
+        ));
+
+        assert!(!stats.is_success(SuccessPolicy::default()));
+        assert!(!stats.is_success(SuccessPolicy {
+            allow_timeouts: true,
+            ..SuccessPolicy::default()
+        }));
+        assert!(stats.is_success(SuccessPolicy {
+            allow_redirects: true,
+            ..SuccessPolicy::default()
+        }));
+
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(website("http://example.org/timeout"), Status::Timeout(None)),
+        ));
+
+        assert!(!stats.is_success(SuccessPolicy {
+            allow_redirects: true,
+            ..SuccessPolicy::default()
+        }));
+        assert!(stats.is_success(SuccessPolicy {
+            allow_redirects: true,
+            allow_timeouts: true,
+            allow_unknown: false,
+            allow_bot_checks: false,
+            max_failures: None,
+            max_error_rate: None,
+        }));
+    }
+
+    #[test]
+    fn test_stats_unknown_policy() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(
+                website("http://example.org/linkedin"),
+                ErrorKind::UnexpectedStatusCode(StatusCode::from_u16(999).unwrap()).into(),
+            ),
+        ));
+
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.failures, 0);
+        assert!(!stats.is_success(SuccessPolicy::default()));
+        assert!(stats.is_success(SuccessPolicy {
+            allow_unknown: true,
+            ..SuccessPolicy::default()
+        }));
+    }
+
+    #[test]
+    fn test_stats_failure_budget() {
+        let mut stats = ResponseStats::new();
+        for i in 0..48 {
+            stats.add(Response(
+                Input::Stdin,
+                ResponseBody::new(
+                    website(&format!("http://example.org/{}", i)),
+                    Status::Ok(StatusCode::OK),
+                ),
+            ));
+        }
+        for i in 0..2 {
+            stats.add(Response(
+                Input::Stdin,
+                ResponseBody::new(
+                    website(&format!("http://example.org/broken/{}", i)),
+                    ErrorKind::MissingContent("example".to_owned()).into(),
+                ),
+            ));
+        }
+
+        assert!((stats.error_rate() - 0.04).abs() < f64::EPSILON);
+        assert!(!stats.is_success(SuccessPolicy::default()));
+        assert!(stats.is_success(SuccessPolicy::default().max_failures(2)));
+        assert!(!stats.is_success(SuccessPolicy::default().max_failures(1)));
+        assert!(stats.is_success(SuccessPolicy::default().fail_if_error_rate_above(0.05)));
+        assert!(!stats.is_success(SuccessPolicy::default().fail_if_error_rate_above(0.02)));
+        assert!(!stats.is_success(
+            SuccessPolicy::default()
+                .max_failures(1)
+                .fail_if_error_rate_above(0.05)
+        ));
+    }
+
+    #[test]
+    fn test_stats_compact() {
+        let mut stats = ResponseStats::new().compact();
+        let statuses: Vec<Status> = vec![
+            Status::Ok(StatusCode::OK),
+            Status::Redirected(StatusCode::PERMANENT_REDIRECT),
+            Status::Timeout(None),
+            ErrorKind::MissingContent("example".to_owned()).into(),
+        ];
+        for (i, status) in statuses.into_iter().enumerate() {
+            stats.add(Response(
+                Input::Stdin,
+                ResponseBody::new(website(&format!("http://example.org/{}", i)), status),
+            ));
+        }
+
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.redirects, 1);
+        let listed: HashSet<&str> = stats.fail_map[&Input::Stdin]
+            .iter()
+            .map(|response| response.uri.as_str())
+            .collect();
+        assert_eq!(
+            listed,
+            vec!["http://example.org/2", "http://example.org/3"]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_stats_bot_checks() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(
+                website("http://example.org/protected"),
+                Status::ProtectedByBotCheck(StatusCode::FORBIDDEN),
+            ),
+        ));
+
+        assert_eq!(stats.bot_checks, 1);
+        assert_eq!(stats.failures, 0);
+        assert!(!stats.is_success(SuccessPolicy::default()));
+        assert!(stats.is_success(SuccessPolicy {
+            allow_bot_checks: true,
+            ..SuccessPolicy::default()
+        }));
+    }
+
+    #[test]
+    fn test_stats_warnings() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(
+                website("https://www.linkedin.com/in/someone"),
+                Status::Warning(StatusCode::from_u16(999).unwrap()),
+            ),
+        ));
+
+        assert_eq!(stats.warnings, 1);
+        assert_eq!(stats.failures().len(), 1);
+        assert!(stats.is_success(SuccessPolicy::default()));
+        assert!(stats.to_string().contains("Warnings"));
+    }
+
+    #[test]
+    fn test_stats_skipped() {
+        let mut stats = ResponseStats::new();
+        for (url, reason) in &[
+            ("https://example.org", SkipReason::Cancelled),
+            ("https://example.com", SkipReason::Cancelled),
+            ("slack://channel?id=123", SkipReason::UnsupportedScheme),
+        ] {
+            stats.add(Response(
+                Input::Stdin,
+                ResponseBody::new(website(url), Status::Skipped(*reason)),
+            ));
+        }
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.skipped, 3);
+        assert_eq!(stats.excludes, 0);
+        assert_eq!(stats.skip_reasons[&SkipReason::Cancelled], 2);
+        assert!(stats.failures().is_empty());
+        assert!(stats.is_success(SuccessPolicy::default()));
+        assert!(stats.to_string().contains("Skipped"));
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["skip_reasons"]["unsupported_scheme"], 1);
+    }
+
+    #[test]
+    fn test_stats_policy_violations() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody::new(
+                website("http://evil.org"),
+                ErrorKind::HostNotAllowed("evil.org".to_owned()).into(),
+            ),
+        ));
+
+        assert_eq!(stats.policy_violations, 1);
+        assert_eq!(stats.failures, 0);
+        assert!(!stats.is_success(SuccessPolicy::default()));
+    }
+
+    #[test]
+    fn test_stats_tags() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::Stdin,
+            ResponseBody {
+                tags: vec!["docs".to_owned()],
+                ..ResponseBody::new(website("http://example.org/ok"), Status::Ok(StatusCode::OK))
+            },
+        ));
         stats.add(Response(
             Input::Stdin,
             ResponseBody {
-                uri: website("http://example.org/ok"),
-                status: Status::Ok(StatusCode::OK),
+                tags: vec!["blog".to_owned()],
+                ..ResponseBody::new(website("http://example.org/timeout"), Status::Timeout(None))
             },
         ));
 
-        assert!(!stats.is_empty());
+        assert_eq!(stats.total, 2);
+        assert_eq!(stats.tag_map["docs"].successful, 1);
+        assert_eq!(stats.tag_map["docs"].timeouts, 0);
+        assert_eq!(stats.tag_map["blog"].total, 1);
+        assert_eq!(stats.tag_map["blog"].timeouts, 1);
+    }
+
+    #[test]
+    fn test_stats_locations() {
+        let mut stats = ResponseStats::new();
+        stats.add(Response(
+            Input::FsPath(PathBuf::from("README.md")),
+            ResponseBody {
+                spans: vec![Span {
+                    start: 30,
+                    end: 56,
+                    line: 3,
+                    column: 7,
+                }],
+                ..ResponseBody::new(website("http://example.org/timeout"), Status::Timeout(None))
+            },
+        ));
+
+        assert!(stats.to_string().contains("\n  at README.md:3:7"));
+    }
+
+    #[test]
+    fn test_stats_grouped_by_input() {
+        let mut stats = ResponseStats::grouped_by_input();
+        let readme = Input::FsPath(PathBuf::from("README.md"));
+        stats.add(Response(
+            readme.clone(),
+            ResponseBody::new(website("http://example.org/ok"), Status::Ok(StatusCode::OK)),
+        ));
+        stats.add(Response(
+            readme.clone(),
+            ResponseBody::new(website("http://example.org/timeout"), Status::Timeout(None)),
+        ));
+        stats.add(Response(
+            Input::FsPath(PathBuf::from("CHANGELOG.md")),
+            ResponseBody::new(website("http://example.org/ok"), Status::Ok(StatusCode::OK)),
+        ));
+
+        assert_eq!(stats.input_map[&readme].total, 2);
+        assert_eq!(stats.input_map[&readme].timeouts, 1);
+        let output = stats.to_string();
+        let changelog = output.find("CHANGELOG.md: 1 total, 1 successful").unwrap();
+        let readme = output.find("README.md: 2 total, 1 successful").unwrap();
+        let timeout = output.find("http://example.org/timeout").unwrap();
+        assert!(changelog < readme && readme < timeout);
+    }
+
+    #[test]
+    fn test_stats_impact() {
+        let mut stats = ResponseStats::new();
+        let broken = || Status::Error(Box::new(ErrorKind::MissingContent("Example".into())));
+        for page in &["a.md", "b.md", "c.md"] {
+            stats.add(Response(
+                Input::FsPath(PathBuf::from(page)),
+                ResponseBody::new(website("http://example.org/moved"), broken()),
+            ));
+        }
+        for uri in &["http://example.org/moved", "http://example.org/typo"] {
+            stats.add(Response(
+                Input::FsPath(PathBuf::from("a.md")),
+                ResponseBody::new(website(uri), broken()),
+            ));
+        }
+        stats.add(Response(
+            Input::FsPath(PathBuf::from("b.md")),
+            ResponseBody::new(website("http://example.org/slow"), Status::Timeout(None)),
+        ));
+
+        assert_eq!(
+            stats.impact(),
+            vec![
+                (&website("http://example.org/moved"), 3),
+                (&website("http://example.org/typo"), 1)
+            ]
+        );
+        assert_eq!(stats.failures, 5);
+        let failures = stats.failures();
+        let (input, responses) = &failures[0];
+        assert_eq!(input.to_string(), "a.md");
+        assert_eq!(responses[0].uri, website("http://example.org/moved"));
+
+        let output = stats.to_string();
+        assert!(output.contains("Most referenced failures\nhttp://example.org/moved (3 inputs)"));
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["fail_references"]["http://example.org/typo"], 1);
     }
 
     #[tokio::test]
@@ -0,0 +1,123 @@
+use lychee_lib::{Response, Status};
+
+const HEADER: [&str; 6] = ["uri", "status", "code", "source", "duration_ms", "error"];
+
+/// A single checked link as a row of a CSV or TSV table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Row([String; 6]);
+
+impl Row {
+    pub(crate) fn from_response(response: &Response) -> Self {
+        let Response(source, body) = response;
+        let error = match &body.status {
+            Status::Error(e) | Status::Unsupported(e) => e.to_string(),
+            Status::Skipped(reason) => reason.to_string(),
+            _ => String::new(),
+        };
+        Self([
+            body.uri.to_string(),
+            status(&body.status).to_owned(),
+            body.status
+                .code()
+                .map(|code| code.as_u16().to_string())
+                .unwrap_or_default(),
+            source.to_string(),
+            body.duration
+                .map(|duration| duration.as_millis().to_string())
+                .unwrap_or_default(),
+            error,
+        ])
+    }
+}
+
+/// Category of the status, as in the summary
+const fn status(status: &Status) -> &'static str {
+    match status {
+        Status::Ok(_) => "ok",
+        Status::Error(_) => "error",
+        Status::Timeout(_) => "timeout",
+        Status::Redirected(_) => "redirected",
+        Status::Excluded => "excluded",
+        Status::Unsupported(_) => "unsupported",
+        Status::ProtectedByBotCheck(_) => "bot_check",
+        Status::Warning(_) => "warning",
+        Status::Skipped(_) => "skipped",
+    }
+}
+
+/// Format rows as a table with a header, separated by `delimiter`
+/// (`,` for CSV, `\t` for TSV)
+pub(crate) fn table(rows: &[Row], delimiter: char) -> String {
+    let mut out = line(HEADER.iter().copied(), delimiter);
+    for Row(fields) in rows {
+        out.push_str(&line(fields.iter().map(String::as_str), delimiter));
+    }
+    out
+}
+
+fn line<'a>(fields: impl Iterator<Item = &'a str>, delimiter: char) -> String {
+    let fields: Vec<String> = fields.map(|field| escape(field, delimiter)).collect();
+    let mut line = fields.join(&delimiter.to_string());
+    line.push_str("\r\n");
+    line
+}
+
+/// Quote a field if it contains the delimiter, quotes or line breaks,
+/// doubling the quotes inside (RFC 4180)
+fn escape(field: &str, delimiter: char) -> String {
+    if field.contains(|c| c == delimiter || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{path::PathBuf, time::Duration};
+
+    use http::StatusCode;
+    use lychee_lib::{ErrorKind, Input, Response, ResponseBody, Status, Uri};
+    use reqwest::Url;
+
+    use super::{escape, table, Row};
+
+    fn website(url: &str) -> Uri {
+        Uri::from(Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_table() {
+        let mut ok = ResponseBody::new(website("https://example.org/"), Status::Ok(StatusCode::OK));
+        ok.duration = Some(Duration::from_millis(120));
+        let failed = ResponseBody::new(
+            website("https://example.org/a,b"),
+            ErrorKind::MissingContent("example.org, \"docs\"".to_owned()).into(),
+        );
+        let source = Input::FsPath(PathBuf::from("README.md"));
+        let rows = vec![
+            Row::from_response(&Response(source.clone(), ok)),
+            Row::from_response(&Response(source, failed)),
+        ];
+
+        let csv = table(&rows, ',');
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[0], "uri,status,code,source,duration_ms,error");
+        assert_eq!(lines[1], "https://example.org/,ok,200,README.md,120,");
+        assert!(lines[2].starts_with("\"https://example.org/a,b\",error,,README.md,,\""));
+        assert!(lines[2].contains("\"\"docs\"\""));
+
+        let tsv = table(&rows, '\t');
+        assert!(tsv.starts_with("uri\tstatus\tcode\tsource\tduration_ms\terror\r\n"));
+        assert!(tsv.contains("https://example.org/a,b\terror"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain", ','), "plain");
+        assert_eq!(escape("a,b", ','), "\"a,b\"");
+        assert_eq!(escape("a,b", '\t'), "a,b");
+        assert_eq!(escape("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("two\nlines", '\t'), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use reqwest::{header, Url};
+use serde_json::{json, Value};
+
+use crate::{options::WebhookFormat, stats::ResponseStats};
+
+/// Maximum number of failed links listed in a notification
+const MAX_LISTED: usize = 20;
+
+/// The payload of a notification about the failures of a run.
+/// Slack and Teams show the text, other services get the statistics
+/// in the format of the JSON report as well.
+pub(crate) fn payload(stats: &ResponseStats, format: WebhookFormat) -> Value {
+    let title = format!(
+        "lychee found {} broken links out of {}",
+        stats.failure_count(),
+        stats.total()
+    );
+    match format {
+        WebhookFormat::Json => json!({
+            "text": format!("{}\n{}", title, failures(stats, "\n")),
+            "stats": stats,
+        }),
+        WebhookFormat::Slack => json!({
+            "text": format!("*{}*\n{}", title, failures(stats, "\n")),
+        }),
+        // Teams needs empty lines between paragraphs
+        WebhookFormat::Teams => json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": title,
+            "title": title,
+            "text": failures(stats, "\n\n"),
+        }),
+    }
+}
+
+/// The most referenced failed links with their status and input
+fn failures(stats: &ResponseStats, separator: &str) -> String {
+    let failures: Vec<String> = stats
+        .failures()
+        .into_iter()
+        .flat_map(|(input, responses)| {
+            responses.into_iter().map(move |response| {
+                format!("- {} ({}) in {}", response.uri, response.status, input)
+            })
+        })
+        .collect();
+    let mut lines: Vec<String> = failures.iter().take(MAX_LISTED).cloned().collect();
+    if failures.len() > MAX_LISTED {
+        lines.push(format!("… and {} more", failures.len() - MAX_LISTED));
+    }
+    lines.join(separator)
+}
+
+/// Send a notification to a webhook
+pub(crate) async fn notify(url: &Url, payload: &Value) -> Result<()> {
+    reqwest::Client::new()
+        .post(url.clone())
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(payload.to_string())
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("Cannot notify webhook {}", url))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use lychee_lib::{ErrorKind, Input, Response, ResponseBody, Uri};
+    use pretty_assertions::assert_eq;
+    use reqwest::Url;
+
+    use super::payload;
+    use crate::{options::WebhookFormat, stats::ResponseStats};
+
+    fn stats(failures: usize) -> ResponseStats {
+        let mut stats = ResponseStats::new();
+        for i in 0..failures {
+            let uri = Uri::from(Url::parse(&format!("https://example.org/{}", i)).unwrap());
+            let status = ErrorKind::MissingContent("Welcome".to_owned()).into();
+            stats.add(Response(
+                Input::FsPath(PathBuf::from("docs/README.md")),
+                ResponseBody::new(uri, status),
+            ));
+        }
+        stats
+    }
+
+    #[test]
+    fn test_payload() {
+        let slack = payload(&stats(1), WebhookFormat::Slack);
+        assert_eq!(
+            slack["text"],
+            "*lychee found 1 broken links out of 1*\n- https://example.org/0 \
+             (Failed: Expected content not found (pattern: Welcome)) in docs/README.md"
+        );
+
+        let json = payload(&stats(1), WebhookFormat::Json);
+        assert_eq!(json["stats"]["failures"], 1);
+
+        let teams = payload(&stats(25), WebhookFormat::Teams);
+        assert_eq!(teams["title"], "lychee found 25 broken links out of 25");
+        let text = teams["text"].as_str().unwrap();
+        assert_eq!(text.split("\n\n").count(), 21);
+        assert!(text.ends_with("… and 5 more"));
+    }
+}
@@ -43,11 +43,26 @@ mod cli {
         timeouts: usize,
         redirects: usize,
         excludes: usize,
+        skipped: usize,
+        skip_reasons: &'static [(&'static str, usize)],
+        bot_checks: usize,
+        warnings: usize,
         errors: usize,
+        policy_violations: usize,
     }
 
     impl MockResponseStats {
         fn to_json_str(&self) -> String {
+            let skip_reasons = if self.skip_reasons.is_empty() {
+                String::new()
+            } else {
+                let reasons: Vec<String> = self
+                    .skip_reasons
+                    .iter()
+                    .map(|(reason, count)| format!("    \"{}\": {}", reason, count))
+                    .collect();
+                format!("\n  \"skip_reasons\": {{\n{}\n  }},", reasons.join(",\n"))
+            };
             format!(
                 r#"{{
   "total": {},
@@ -56,7 +71,11 @@ mod cli {
   "timeouts": {},
   "redirects": {},
   "excludes": {},
+  "skipped": {},{}
+  "bot_checks": {},
+  "warnings": {},
   "errors": {},
+  "policy_violations": {},
   "fail_map": {{}}
 }}"#,
                 self.total,
@@ -65,7 +84,12 @@ mod cli {
                 self.timeouts,
                 self.redirects,
                 self.excludes,
-                self.errors
+                self.skipped,
+                skip_reasons,
+                self.bot_checks,
+                self.warnings,
+                self.errors,
+                self.policy_violations
             )
         }
     }
@@ -134,8 +158,10 @@ mod cli {
         test_json_output!(
             "TEST_SCHEMES.txt",
             MockResponseStats {
-                total: 1,
+                total: 3,
                 successful: 1,
+                skipped: 2,
+                skip_reasons: &[("unsupported_scheme", 2)],
                 ..MockResponseStats::default()
             }
         )
@@ -228,6 +254,21 @@ mod cli {
             .success();
     }
 
+    #[test]
+    fn test_dump() {
+        let mut cmd = main_command();
+
+        cmd.arg("--dump")
+            .arg("--exclude")
+            .arg("excluded")
+            .arg("--verbose")
+            .arg("-")
+            .write_stdin("https://example.org/dumped\nhttps://excluded.org")
+            .assert()
+            .success()
+            .stdout("https://example.org/dumped (stdin:1:1)\n");
+    }
+
     #[tokio::test]
     async fn test_stdin_input_failure() {
         let mut cmd = main_command();
@@ -370,4 +411,303 @@ mod cli {
         fs::remove_file(outfile)?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_link_graph() -> Result<()> {
+        let mut cmd = main_command();
+        let dir = tempfile::tempdir()?;
+        let mock_server = mock_server!(StatusCode::OK);
+        let page = dir.path().join("page.md");
+        let mut file = File::create(&page)?;
+        writeln!(file, "See {}", mock_server.uri())?;
+        let graph = dir.path().join("links.dot");
+
+        cmd.arg(&page).arg("--graph").arg(&graph).assert().success();
+
+        let expected = format!(
+            "  {:?} -> {:?};\n",
+            page.display().to_string(),
+            format!("{}/", mock_server.uri())
+        );
+        assert!(fs::read_to_string(&graph)?.contains(&expected));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_checksums() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK, set_body_string("hello"));
+        let dir = tempfile::tempdir()?;
+        let page = dir.path().join("release.md");
+        writeln!(File::create(&page)?, "Download {}/", mock_server.uri())?;
+        let checksums = dir.path().join("SHA256SUMS");
+        let mut manifest = File::create(&checksums)?;
+        writeln!(manifest, "{}  {}/", "0".repeat(64), mock_server.uri())?;
+
+        main_command()
+            .arg(&page)
+            .arg("--checksums")
+            .arg(&checksums)
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains("Checksum mismatch"));
+
+        let mut manifest = File::create(&checksums)?;
+        writeln!(
+            manifest,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  {}/",
+            mock_server.uri()
+        )?;
+        main_command()
+            .arg(&page)
+            .arg("--checksums")
+            .arg(&checksums)
+            .assert()
+            .success();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_baseline() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::NOT_FOUND);
+        let dir = tempfile::tempdir()?;
+        let page = dir.path().join("test.md");
+        writeln!(File::create(&page)?, "{}", mock_server.uri())?;
+        let baseline = dir.path().join("baseline.json");
+        writeln!(
+            File::create(&baseline)?,
+            r#"{{"total": 0, "fail_map": {{}}}}"#
+        )?;
+
+        main_command()
+            .arg(&page)
+            .arg("--baseline")
+            .arg(&baseline)
+            .assert()
+            .failure()
+            .code(2)
+            .stderr(contains("1 newly broken, 0 fixed, 0 still broken"));
+
+        writeln!(
+            File::create(&baseline)?,
+            r#"{{"fail_map": {{"test.md": [{{"url": "{}/", "status": "Failed: Network error"}}]}}}}"#,
+            mock_server.uri()
+        )?;
+        main_command()
+            .arg(&page)
+            .arg("--baseline")
+            .arg(&baseline)
+            .assert()
+            .success()
+            .stderr(contains("0 newly broken, 0 fixed, 1 still broken"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::NOT_FOUND);
+        main_command()
+            .arg("--format")
+            .arg("json")
+            .arg("--metadata")
+            .arg("team=docs")
+            .arg("-")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains(r#""team": "docs""#));
+
+        main_command()
+            .arg("--metadata")
+            .arg("team")
+            .arg("-")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(contains(
+                "Metadata should be of the form key=value, got team",
+            ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_codeowners() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::NOT_FOUND);
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("docs"))?;
+        writeln!(
+            File::create(dir.path().join("docs/guide.md"))?,
+            "{}",
+            mock_server.uri()
+        )?;
+        writeln!(
+            File::create(dir.path().join("CODEOWNERS"))?,
+            "* @maintainers\n/docs/ @docs-team @alice"
+        )?;
+
+        main_command()
+            .current_dir(dir.path())
+            .arg("docs/guide.md")
+            .arg("--codeowners")
+            .arg("CODEOWNERS")
+            .assert()
+            .failure()
+            .code(2)
+            .stdout(contains(
+                "Errors in docs/guide.md (owners: @docs-team @alice)",
+            ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_links() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK);
+        let links = format!("{uri}/a {uri}/b {uri}/c", uri = mock_server.uri());
+        main_command()
+            .arg("--max-links")
+            .arg("2")
+            .arg("--format")
+            .arg("json")
+            .arg("-")
+            .write_stdin(links)
+            .assert()
+            .success()
+            .stdout(contains(r#""total": 2"#))
+            .stdout(contains(r#""truncated": 1"#));
+        Ok(())
+    }
+
+    #[test]
+    fn test_probe_network() -> Result<()> {
+        // Nothing listens on port 1
+        main_command()
+            .arg("--probe-network")
+            .arg("http://127.0.0.1:1/")
+            .arg("-")
+            .write_stdin("https://example.org")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(contains("The network seems to be unavailable"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_config() -> Result<()> {
+        main_command()
+            .arg("--exclude")
+            .arg("example.org|")
+            .arg("--blocked-host")
+            .arg("https://example.com")
+            .arg("-")
+            .write_stdin("https://example.org")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(contains("An exclude pattern matches every link"))
+            .stderr(contains("Invalid host pattern: https://example.com"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_templates() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK);
+        let links = format!(
+            "{uri}/releases/$VERSION/app.zip {uri}/",
+            uri = mock_server.uri()
+        );
+        main_command()
+            .arg("--format")
+            .arg("json")
+            .arg("-")
+            .write_stdin(links.clone())
+            .assert()
+            .success()
+            .stdout(contains(r#""excludes": 1"#))
+            .stdout(contains(r#""successful": 1"#));
+
+        main_command()
+            .arg("--check-templates")
+            .arg("--format")
+            .arg("json")
+            .arg("-")
+            .write_stdin(links)
+            .assert()
+            .success()
+            .stdout(contains(r#""excludes": 0"#));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_domain_rules() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK);
+        // Domain rules don't affect IP addresses like the one of the mock server
+        let links = format!(
+            "https://www.beijing.gov.cn/ https://docs.example.co.uk/ {}",
+            mock_server.uri()
+        );
+        main_command()
+            .arg("--blocked-domain")
+            .arg("*.gov.cn")
+            .arg("--allowed-domain")
+            .arg("example.com")
+            .arg("--format")
+            .arg("json")
+            .arg("-")
+            .write_stdin(links)
+            .assert()
+            .success()
+            .stdout(contains(r#""excludes": 2"#))
+            .stdout(contains(r#""successful": 1"#));
+
+        main_command()
+            .arg("--blocked-domain")
+            .arg("github.io")
+            .arg("-")
+            .write_stdin("https://example.com")
+            .assert()
+            .failure()
+            .code(1)
+            .stderr(contains("Invalid domain rule: github.io"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_networks() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK);
+        main_command()
+            .arg("--exclude-network")
+            .arg("127.0.0.0/8")
+            .arg("--format")
+            .arg("json")
+            .arg("-")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .success()
+            .stdout(contains(r#""excludes": 1"#));
+
+        main_command()
+            .arg("--exclude-loopback")
+            .arg("--include-network")
+            .arg("127.0.0.1")
+            .arg("--format")
+            .arg("json")
+            .arg("-")
+            .write_stdin(mock_server.uri())
+            .assert()
+            .success()
+            .stdout(contains(r#""successful": 1"#));
+
+        main_command()
+            .arg("--exclude-network")
+            .arg("10.42.0.0/33")
+            .arg("-")
+            .write_stdin("https://example.com")
+            .assert()
+            .failure()
+            .stderr(contains("Invalid IP network: 10.42.0.0/33"));
+        Ok(())
+    }
 }
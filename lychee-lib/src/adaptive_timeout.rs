@@ -0,0 +1,127 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+/// Allow requests this many times the average latency of their host
+const HEADROOM: u32 = 4;
+
+/// The weight of a new latency in the moving average of its host, i.e. 1/4
+const WEIGHT: u32 = 4;
+
+/// Timeouts per host which adapt to the latency observed for each host,
+/// so a single slow host doesn't force a long timeout for all others.
+///
+/// Requests to a host get the default timeout of the client until its
+/// moving average latency comes close to it. Then the timeout of that host
+/// grows with its latency, up to the default timeout times the slow host
+/// multiplier. Requests which time out count with the full timeout.
+///
+/// Clones share the observed latencies, like the clients of a pool.
+///
+/// ```
+/// use lychee_lib::{AdaptiveTimeout, ClientBuilder};
+/// use std::time::Duration;
+///
+/// // Allow up to 60s for slow hosts, but 10s for all others
+/// let client = ClientBuilder::builder()
+///     .timeout(Duration::from_secs(10))
+///     .adaptive_timeout(AdaptiveTimeout::new(6))
+///     .build()
+///     .client();
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdaptiveTimeout {
+    slow_host_multiplier: u32,
+    latencies: Arc<Mutex<HashMap<String, Duration>>>,
+}
+
+impl AdaptiveTimeout {
+    /// Adapt timeouts of slow hosts up to `slow_host_multiplier` times the
+    /// default timeout
+    #[must_use]
+    pub fn new(slow_host_multiplier: u32) -> Self {
+        Self {
+            slow_host_multiplier: slow_host_multiplier.max(1),
+            latencies: Arc::default(),
+        }
+    }
+
+    /// The latencies, which are still consistent if a thread panicked while
+    /// holding the lock, as every update is a single insert
+    fn latencies(&self) -> MutexGuard<'_, HashMap<String, Duration>> {
+        self.latencies
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The moving average latency of the requests to a host so far
+    #[must_use]
+    pub fn latency(&self, host: &str) -> Option<Duration> {
+        self.latencies().get(host).copied()
+    }
+
+    /// The timeout of the next request to a host
+    pub(crate) fn timeout(&self, host: &str, default: Duration) -> Duration {
+        self.latency(host).map_or(default, |latency| {
+            (latency * HEADROOM).clamp(default, default * self.slow_host_multiplier)
+        })
+    }
+
+    /// Record the latency of a request to a host
+    pub(crate) fn record(&self, host: &str, latency: Duration) {
+        let mut latencies = self.latencies();
+        let average = latencies.get(host).map_or(latency, |average| {
+            (*average * (WEIGHT - 1) + latency) / WEIGHT
+        });
+        latencies.insert(host.to_owned(), average);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::AdaptiveTimeout;
+
+    #[test]
+    fn test_timeout() {
+        let timeouts = AdaptiveTimeout::new(3);
+        let default = Duration::from_secs(10);
+        assert_eq!(timeouts.timeout("example.org", default), default);
+
+        timeouts.record("example.org", Duration::from_millis(200));
+        assert_eq!(timeouts.timeout("example.org", default), default);
+
+        timeouts.record("slow.example.org", Duration::from_secs(4));
+        assert_eq!(
+            timeouts.timeout("slow.example.org", default),
+            Duration::from_secs(16)
+        );
+        timeouts.record("slow.example.org", Duration::from_secs(16));
+        assert_eq!(
+            timeouts.latency("slow.example.org"),
+            Some(Duration::from_secs(7))
+        );
+        timeouts.record("slow.example.org", Duration::from_secs(28));
+        assert_eq!(
+            timeouts.timeout("slow.example.org", default),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_shared_between_clones() {
+        let timeouts = AdaptiveTimeout::new(2);
+        timeouts
+            .clone()
+            .record("example.org", Duration::from_secs(1));
+        assert_eq!(
+            timeouts.latency("example.org"),
+            Some(Duration::from_secs(1))
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::{collections::HashMap, fmt::Write};
+
+use reqwest::Url;
+use ring::digest::{digest, SHA256};
+
+use crate::{ErrorKind, Result};
+
+/// Expected SHA-256 checksums of download links, e.g. of the binaries
+/// linked from release notes. Links with a checksum get downloaded
+/// completely and fail if their content doesn't match.
+///
+/// The manifest uses the format of `sha256sum`, with URLs instead of paths.
+/// Empty lines and lines starting with `#` are ignored.
+///
+/// ```
+/// use lychee_lib::Checksums;
+/// use reqwest::Url;
+///
+/// let checksums = Checksums::parse(
+///     "# v1.0.0\n\
+///      e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  https://example.org/lychee.tar.gz\n",
+/// )
+/// .unwrap();
+/// assert!(checksums.get(&Url::parse("https://example.org/lychee.tar.gz").unwrap()).is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checksums(HashMap<Url, String>);
+
+impl Checksums {
+    /// Parse a manifest with a checksum and a URL per line
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidChecksum`] for the first line
+    /// which isn't a hexadecimal SHA-256 checksum followed by a URL
+    pub fn parse(manifest: &str) -> Result<Self> {
+        let mut checksums = HashMap::new();
+        for line in manifest.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || ErrorKind::InvalidChecksum(line.to_owned());
+            let (checksum, url) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            // `sha256sum` marks files read in binary mode with `*`
+            let url = url.trim_start().trim_start_matches('*');
+            if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            let url = Url::parse(url).map_err(|_| invalid())?;
+            checksums.insert(url, checksum.to_ascii_lowercase());
+        }
+        Ok(Self(checksums))
+    }
+
+    /// The expected checksum of a link, in lowercase hexadecimal
+    #[must_use]
+    pub fn get(&self, url: &Url) -> Option<&str> {
+        self.0.get(url).map(String::as_str)
+    }
+
+    /// Verify the content of a link against its expected checksum
+    pub(crate) fn verify(&self, url: &Url, content: &[u8]) -> Result<()> {
+        let expected = match self.get(url) {
+            Some(expected) => expected,
+            None => return Ok(()),
+        };
+        let actual = sha256(content);
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(ErrorKind::ChecksumMismatch(expected.to_owned(), actual))
+        }
+    }
+}
+
+/// The SHA-256 checksum of `content`, in lowercase hexadecimal
+fn sha256(content: &[u8]) -> String {
+    digest(&SHA256, content)
+        .as_ref()
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use reqwest::Url;
+
+    use super::{sha256, Checksums};
+    use crate::ErrorKind;
+
+    const HELLO: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(sha256(b"hello"), HELLO);
+    }
+
+    #[test]
+    fn test_parse() {
+        let checksums = Checksums::parse(&format!(
+            "\n# Release 1.0\n{}  https://example.org/a.tar.gz\n{} *https://example.org/b.zip\n",
+            HELLO.to_uppercase(),
+            HELLO
+        ))
+        .unwrap();
+        let url = |url| Url::parse(url).unwrap();
+        assert_eq!(
+            checksums.get(&url("https://example.org/a.tar.gz")),
+            Some(HELLO)
+        );
+        assert_eq!(
+            checksums.get(&url("https://example.org/b.zip")),
+            Some(HELLO)
+        );
+        assert_eq!(checksums.get(&url("https://example.org/c.zip")), None);
+
+        for line in &[
+            "abc https://example.org/",
+            HELLO,
+            format!("{} not-a-url", HELLO).as_str(),
+        ] {
+            assert_eq!(
+                Checksums::parse(line),
+                Err(ErrorKind::InvalidChecksum((*line).to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let checksums = Checksums::parse(&format!("{}  https://example.org/hello", HELLO)).unwrap();
+        let url = Url::parse("https://example.org/hello").unwrap();
+        assert_eq!(checksums.verify(&url, b"hello"), Ok(()));
+        assert_eq!(
+            checksums.verify(&url, b"hello\n"),
+            Err(ErrorKind::ChecksumMismatch(
+                HELLO.to_owned(),
+                sha256(b"hello\n")
+            ))
+        );
+        let other = Url::parse("https://example.org/other").unwrap();
+        assert_eq!(checksums.verify(&other, b"anything"), Ok(()));
+    }
+}
@@ -1,8 +1,9 @@
 use client::Client;
 use deadpool::unmanaged::Pool;
 use tokio::sync::mpsc;
+pub use tokio_util::sync::CancellationToken;
 
-use crate::{client, types};
+use crate::{client, types, Response, SkipReason, Status};
 
 #[allow(missing_debug_implementations)]
 /// Manages a channel for incoming requests
@@ -11,6 +12,8 @@ pub struct ClientPool {
     tx: mpsc::Sender<types::Response>,
     rx: mpsc::Receiver<types::Request>,
     pool: deadpool::unmanaged::Pool<client::Client>,
+    retry_pool: Option<deadpool::unmanaged::Pool<client::Client>>,
+    cancel: CancellationToken,
 }
 
 impl ClientPool {
@@ -22,19 +25,113 @@ impl ClientPool {
         clients: Vec<Client>,
     ) -> Self {
         let pool = Pool::from(clients);
-        ClientPool { tx, rx, pool }
+        ClientPool {
+            tx,
+            rx,
+            pool,
+            retry_pool: None,
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    #[must_use]
+    /// Hold back failed checks (errors and timeouts) and check their links
+    /// once more with the given clients after all other requests were sent,
+    /// e.g. with a longer timeout. Only the response of the second check is
+    /// sent, so transient failures like network blips aren't reported.
+    pub fn retry_failures(mut self, clients: Vec<Client>) -> Self {
+        self.retry_pool = Some(Pool::from(clients));
+        self
+    }
+
+    #[must_use]
+    /// Returns a token to shut down the pool from another task.
+    /// See [`ClientPool::shutdown`] for the semantics of cancelling it.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Stop accepting new requests.
+    ///
+    /// Requests which are already in flight are awaited and their responses
+    /// are still sent, so the response channel closes once they are done
+    /// and all results collected so far can be reported.
+    /// Requests which were queued, but not sent yet, are answered as
+    /// [skipped](SkipReason::Cancelled).
+    pub fn shutdown(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Answer the requests which are still queued as cancelled
+    async fn skip_queued(&mut self) {
+        self.rx.close();
+        while let Some(req) = self.rx.recv().await {
+            let resp = Response::new(req.uri, Status::Skipped(SkipReason::Cancelled), req.source);
+            self.tx
+                .send(resp)
+                .await
+                .expect("Cannot send response to channel");
+        }
     }
 
     #[allow(clippy::missing_panics_doc)]
     /// Start listening for incoming requests and send each of them
-    /// asynchronously to a client from the pool
+    /// asynchronously to a client from the pool.
+    /// Returns once the request channel is closed or the pool was shut down,
+    /// and failures which are checked once more were sent to the retry clients.
     pub async fn listen(&mut self) {
-        while let Some(req) = self.rx.recv().await {
+        // Failed requests and their responses, to check them once more at the end
+        let (failed_tx, mut failed_rx) = mpsc::unbounded_channel();
+        loop {
+            let req = tokio::select! {
+                biased;
+                _ = self.cancel.cancelled() => {
+                    self.skip_queued().await;
+                    break;
+                }
+                req = self.rx.recv() => match req {
+                    Some(req) => req,
+                    None => break,
+                },
+            };
             let client = self.pool.get().await;
             let tx = self.tx.clone();
+            let failed_tx = self.retry_pool.as_ref().map(|_| failed_tx.clone());
             tokio::spawn(async move {
+                let retry = failed_tx.as_ref().map(|_| req.clone());
                 // Client::check() may fail only because Request::try_from() may fail
                 // here request is already Request, so it never fails
+                let mut resp = client.check(req).await.unwrap();
+                if let (Some(failed_tx), Some(req)) = (failed_tx, retry) {
+                    if resp.status().is_transient_failure() {
+                        match failed_tx.send((req, resp)) {
+                            Ok(()) => return,
+                            Err(mpsc::error::SendError((_, failed))) => resp = failed,
+                        }
+                    }
+                }
+                tx.send(resp)
+                    .await
+                    .expect("Cannot send response to channel");
+            });
+        }
+
+        // The channel closes once all checks of the first pass are done
+        drop(failed_tx);
+        let retry_pool = match &self.retry_pool {
+            Some(retry_pool) => retry_pool,
+            None => return,
+        };
+        while let Some((req, resp)) = failed_rx.recv().await {
+            let tx = self.tx.clone();
+            if self.cancel.is_cancelled() {
+                tx.send(resp)
+                    .await
+                    .expect("Cannot send response to channel");
+                continue;
+            }
+            let client = retry_pool.get().await;
+            tokio::spawn(async move {
                 let resp = client.check(req).await.unwrap();
                 tx.send(resp)
                     .await
@@ -43,3 +140,97 @@ impl ClientPool {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{convert::TryFrom, time::Duration};
+
+    use tokio::sync::mpsc;
+    use wiremock::{matchers::method, Mock, MockServer, ResponseTemplate};
+
+    use super::ClientPool;
+    use crate::{ClientBuilder, Request, Response, SkipReason, Status};
+
+    #[tokio::test]
+    async fn test_shutdown() {
+        let (_send_req, recv_req) = mpsc::channel::<Request>(1);
+        let (send_resp, mut recv_resp) = mpsc::channel::<Response>(1);
+        let client = ClientBuilder::default().client().unwrap();
+
+        let mut pool = ClientPool::new(send_resp, recv_req, vec![client]);
+        pool.shutdown();
+
+        // Returns even though the request channel is still open
+        pool.listen().await;
+        drop(pool);
+
+        assert!(recv_resp.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_skips_queued_requests() {
+        let (send_req, recv_req) = mpsc::channel::<Request>(2);
+        let (send_resp, mut recv_resp) = mpsc::channel::<Response>(2);
+        let client = ClientBuilder::default().client().unwrap();
+        for uri in &["https://example.org", "https://example.com"] {
+            send_req
+                .send(Request::try_from(*uri).unwrap())
+                .await
+                .unwrap();
+        }
+
+        let mut pool = ClientPool::new(send_resp, recv_req, vec![client]);
+        pool.shutdown();
+        pool.listen().await;
+        drop(pool);
+
+        for _ in 0..2 {
+            let resp = recv_resp.recv().await.unwrap();
+            assert_eq!(resp.status(), &Status::Skipped(SkipReason::Cancelled));
+        }
+        assert!(recv_resp.recv().await.is_none());
+        // The pool doesn't accept any more requests
+        assert!(send_req
+            .send(Request::try_from("https://example.net").unwrap())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_failures() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("HEAD"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let (send_req, recv_req) = mpsc::channel::<Request>(1);
+        let (send_resp, mut recv_resp) = mpsc::channel::<Response>(1);
+        let client = ClientBuilder::builder()
+            .method(reqwest::Method::HEAD)
+            .build()
+            .client()
+            .unwrap();
+        let retry_client =
+            client.with_method_and_timeout(reqwest::Method::GET, Duration::from_secs(5));
+
+        let mut pool =
+            ClientPool::new(send_resp, recv_req, vec![client]).retry_failures(vec![retry_client]);
+        let listener = tokio::spawn(async move { pool.listen().await });
+        send_req
+            .send(Request::try_from(mock_server.uri()).unwrap())
+            .await
+            .unwrap();
+        drop(send_req);
+
+        // Only the response of the second check is sent
+        let resp = recv_resp.recv().await.unwrap();
+        assert!(resp.status().is_success());
+        listener.await.unwrap();
+        assert!(recv_resp.recv().await.is_none());
+    }
+}
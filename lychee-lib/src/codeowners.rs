@@ -0,0 +1,141 @@
+use std::path::{Component, Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+use crate::Result;
+
+/// Owners of the files of a repository from a `CODEOWNERS` file,
+/// to route broken links to the teams which can fix them.
+///
+/// Patterns follow the rules of GitHub: a pattern with a slash at the start
+/// or in the middle is relative to the root of the repository, others match
+/// at any depth. A pattern matching a directory matches everything in it.
+/// The last matching pattern wins, even if it has no owners.
+///
+/// ```
+/// use lychee_lib::CodeOwners;
+/// use std::path::Path;
+///
+/// let owners = CodeOwners::parse("* @lycheeverse/maintainers\n/docs/ @lycheeverse/docs\n").unwrap();
+/// assert_eq!(owners.owners(Path::new("docs/usage.md")), ["@lycheeverse/docs"]);
+/// assert_eq!(owners.owners(Path::new("README.md")), ["@lycheeverse/maintainers"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    /// The pattern itself and the pattern of everything below it
+    patterns: [Pattern; 2],
+    owners: Vec<String>,
+}
+
+impl CodeOwners {
+    /// Parse the content of a `CODEOWNERS` file
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidGlobPattern`](crate::ErrorKind::InvalidGlobPattern)
+    /// for the first invalid pattern
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for line in content.lines() {
+            // Comments can also follow the owners
+            let line = line.split('#').next().unwrap_or_default();
+            let mut parts = line.split_whitespace();
+            let pattern = match parts.next() {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+            let pattern = glob_pattern(pattern);
+            rules.push(Rule {
+                patterns: [
+                    Pattern::new(&pattern)?,
+                    Pattern::new(&format!("{}/**", pattern.trim_end_matches("/**")))?,
+                ],
+                owners: parts.map(str::to_owned).collect(),
+            });
+        }
+        Ok(CodeOwners { rules })
+    }
+
+    /// The owners of the file at `path`, which is relative to the root of
+    /// the repository
+    #[must_use]
+    pub fn owners(&self, path: &Path) -> &[String] {
+        let path: PathBuf = path
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect();
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                rule.patterns
+                    .iter()
+                    .any(|pattern| pattern.matches_path_with(&path, options))
+            })
+            .map_or(&[][..], |rule| rule.owners.as_slice())
+    }
+}
+
+/// The glob pattern of a `CODEOWNERS` pattern, relative to the root
+fn glob_pattern(pattern: &str) -> String {
+    let anchored = pattern.trim_end_matches('/').contains('/');
+    let mut glob = pattern.trim_start_matches('/').to_owned();
+    if glob.ends_with('/') {
+        glob.push_str("**");
+    }
+    if !anchored {
+        glob.insert_str(0, "**/");
+    }
+    glob
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::CodeOwners;
+
+    const CODEOWNERS: &str = "
+# Default owners
+*                @maintainers
+*.md             @docs-team # all documentation
+/docs/api/       @api-team @alice
+build/           @infra
+/scripts/*.sh    @infra
+CHANGELOG.md
+";
+
+    fn owners(path: &str) -> Vec<String> {
+        CodeOwners::parse(CODEOWNERS)
+            .unwrap()
+            .owners(Path::new(path))
+            .to_vec()
+    }
+
+    #[test]
+    fn test_owners() {
+        assert_eq!(owners("src/lib.rs"), vec!["@maintainers"]);
+        assert_eq!(owners("README.md"), vec!["@docs-team"]);
+        assert_eq!(owners("./docs/guide/intro.md"), vec!["@docs-team"]);
+        assert_eq!(owners("docs/api/client.md"), vec!["@api-team", "@alice"]);
+        assert_eq!(owners("docs/api/v2/server.md"), vec!["@api-team", "@alice"]);
+        assert_eq!(owners("tools/build/index.html"), vec!["@infra"]);
+        assert_eq!(owners("scripts/release.sh"), vec!["@infra"]);
+        assert_eq!(owners("scripts/ci/release.sh"), vec!["@maintainers"]);
+        assert!(owners("CHANGELOG.md").is_empty());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(CodeOwners::parse("docs/[ @docs-team").is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use std::{
-    collections::HashSet,
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use glob::glob_with;
@@ -11,12 +15,20 @@ use shellexpand::tilde;
 use tokio::{
     fs::read_to_string,
     io::{stdin, AsyncReadExt},
+    sync::mpsc,
 };
 
 use crate::{
-    extract::{extract_links, FileType},
-    uri::Uri,
-    Request, Result,
+    codeowners::CodeOwners,
+    concurrency::{ConcurrencyLimit, DEFAULT_MAX_CONCURRENCY},
+    config::Config,
+    diff::ChangedLines,
+    extract::{
+        extract_requests, Extractor, FileType, HtmlExtractor, MarkdownExtractor, PlaintextExtractor,
+    },
+    filter::Filter,
+    graph::LinkGraph,
+    mailbox, Event, EventLog, Request, Result,
 };
 
 const STDIN: &str = "-";
@@ -39,6 +51,9 @@ pub enum Input {
     Stdin,
     /// Raw string input.
     String(String),
+    /// In-memory document of the given file type, e.g. one generated on
+    /// the fly. Invalid UTF-8 sequences are replaced.
+    Buffer(Vec<u8>, FileType),
 }
 
 impl Serialize for Input {
@@ -58,6 +73,7 @@ impl Display for Input {
             Input::FsPath(path) => path.to_str().unwrap_or_default(),
             Input::Stdin => "stdin",
             Input::String(_) => "raw input string",
+            Input::Buffer(..) => "in-memory buffer",
         })
     }
 }
@@ -132,15 +148,16 @@ impl Input {
                 ignore_case,
             } => Ok(Self::glob_contents(pattern, ignore_case).await?),
             Input::FsPath(ref path) => {
-                let content = Self::path_content(path).await;
-                match content {
-                    Ok(input_content) => Ok(vec![input_content]),
+                let contents = Self::path_contents(path).await;
+                match contents {
+                    Ok(contents) => Ok(contents),
                     Err(_) if skip_missing => Ok(vec![]),
                     Err(e) => Err(e),
                 }
             }
             Input::Stdin => Ok(vec![Self::stdin_content(file_type_hint).await?]),
             Input::String(ref s) => Ok(vec![Self::string_content(s, file_type_hint)]),
+            Input::Buffer(ref bytes, file_type) => Ok(vec![Self::buffer_content(bytes, file_type)]),
         }
     }
 
@@ -153,6 +170,10 @@ impl Input {
         };
 
         let res = reqwest::get(url.clone()).await?;
+        #[cfg(feature = "pdf")]
+        if crate::pdf::is_pdf(Path::new(url.path())) {
+            return crate::pdf::url_content(url, &res.bytes().await?);
+        }
         let input_content = InputContent {
             input: Input::RemoteUrl(Box::new(url.clone())),
             file_type,
@@ -164,6 +185,16 @@ impl Input {
 
     async fn glob_contents(path_glob: &str, ignore_case: bool) -> Result<Vec<InputContent>> {
         let mut contents = vec![];
+        for path in Self::glob_paths(path_glob, ignore_case)? {
+            contents.extend(Self::path_contents(&path).await?);
+        }
+
+        Ok(contents)
+    }
+
+    /// Paths of all files matching a glob pattern
+    fn glob_paths(path_glob: &str, ignore_case: bool) -> Result<Vec<PathBuf>> {
+        let mut paths = vec![];
         let glob_expanded = tilde(&path_glob);
         let mut match_opts = glob::MatchOptions::new();
 
@@ -171,18 +202,25 @@ impl Input {
 
         for entry in glob_with(&glob_expanded, match_opts)? {
             match entry {
-                Ok(path) => {
-                    let content = Self::path_content(&path).await?;
-                    contents.push(content);
-                }
+                Ok(path) => paths.push(path),
                 Err(e) => println!("{:?}", e),
             }
         }
 
-        Ok(contents)
+        Ok(paths)
     }
 
-    async fn path_content<P: Into<PathBuf> + AsRef<Path> + Clone>(path: P) -> Result<InputContent> {
+    /// The contents of a file. Mail files have a content per message body.
+    async fn path_contents<P: Into<PathBuf> + AsRef<Path> + Clone>(
+        path: P,
+    ) -> Result<Vec<InputContent>> {
+        if mailbox::is_mailbox(path.as_ref()) {
+            return mailbox::path_contents(path.as_ref()).await;
+        }
+        #[cfg(feature = "pdf")]
+        if crate::pdf::is_pdf(path.as_ref()) {
+            return Ok(vec![crate::pdf::path_content(path.into()).await?]);
+        }
         let content = read_to_string(&path)
             .await
             .map_err(|e| (path.clone().into(), e))?;
@@ -192,7 +230,7 @@ impl Input {
             input: Input::FsPath(path.into()),
         };
 
-        Ok(input_content)
+        Ok(vec![input_content])
     }
 
     async fn stdin_content(file_type_hint: Option<FileType>) -> Result<InputContent> {
@@ -212,26 +250,323 @@ impl Input {
     fn string_content(s: &str, file_type_hint: Option<FileType>) -> InputContent {
         InputContent::from_string(s, file_type_hint.unwrap_or_default())
     }
+
+    fn buffer_content(bytes: &[u8], file_type: FileType) -> InputContent {
+        InputContent {
+            input: Input::Buffer(bytes.to_vec(), file_type),
+            file_type,
+            content: String::from_utf8_lossy(bytes).into_owned(),
+        }
+    }
 }
 
-/// Collector keeps the state of link collection
+/// Collector keeps the state of link collection.
+///
+/// It reads inputs (files, globs, URLs, stdin or strings) concurrently,
+/// detects their file type and extracts all unique links from them.
+///
+/// ```
+/// use lychee_lib::{Collector, Input, Result};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let links = Collector::default()
+///         .skip_missing_inputs(true)
+///         .collect_links(&[Input::String("See https://example.org".to_owned())])
+///         .await?;
+///     assert_eq!(links.len(), 1);
+///     Ok(())
+/// }
+/// ```
 #[derive(Debug, Clone)]
 pub struct Collector {
     base_url: Option<Url>,
     skip_missing_inputs: bool,
     max_concurrency: usize,
-    cache: HashSet<Uri>,
+    tags: HashMap<Input, Vec<String>>,
+    metadata: HashMap<Input, BTreeMap<String, String>>,
+    code_owners: Option<Arc<CodeOwners>>,
+    event_log: Option<Arc<EventLog>>,
+    link_graph: Option<Arc<LinkGraph>>,
+    extractors: HashMap<String, Arc<dyn Extractor>>,
+    plaintext: Option<PlaintextExtractor>,
+    markdown: Option<MarkdownExtractor>,
+    html: Option<HtmlExtractor>,
+    #[cfg(feature = "render")]
+    renderer: Option<crate::Renderer>,
+    changes: Option<Arc<ChangedLines>>,
+    filter: Option<Arc<Filter>>,
+    max_links_total: Option<usize>,
+    max_links_per_input: Option<usize>,
+    lenient_urls: bool,
+    truncation: Truncation,
+}
+
+/// The links a [`Collector`] dropped in its last collection because of its
+/// limits on the number of links. Clones share their counts,
+/// so they can be read after the collection.
+#[derive(Debug, Clone, Default)]
+pub struct Truncation {
+    sent: Arc<AtomicUsize>,
+    dropped_links: Arc<AtomicUsize>,
+    truncated_inputs: Arc<AtomicUsize>,
+}
+
+impl Truncation {
+    /// Number of links which were dropped
+    #[must_use]
+    pub fn dropped_links(&self) -> usize {
+        self.dropped_links.load(Ordering::Relaxed)
+    }
+
+    /// Number of inputs which had more links than allowed per input
+    #[must_use]
+    pub fn truncated_inputs(&self) -> usize {
+        self.truncated_inputs.load(Ordering::Relaxed)
+    }
 }
 
 impl Collector {
-    /// Create a new collector with an empty cache
+    /// Create a new collector
     #[must_use]
     pub fn new(base_url: Option<Url>, skip_missing_inputs: bool, max_concurrency: usize) -> Self {
         Collector {
             base_url,
             skip_missing_inputs,
             max_concurrency,
-            cache: HashSet::new(),
+            tags: HashMap::new(),
+            metadata: HashMap::new(),
+            code_owners: None,
+            event_log: None,
+            link_graph: None,
+            extractors: HashMap::new(),
+            plaintext: None,
+            markdown: None,
+            html: None,
+            #[cfg(feature = "render")]
+            renderer: None,
+            changes: None,
+            filter: None,
+            max_links_total: None,
+            max_links_per_input: None,
+            lenient_urls: false,
+            truncation: Truncation::default(),
+        }
+    }
+
+    /// Prefix relative links (e.g. `/docs`) with this URL.
+    /// Without it, relative links are skipped.
+    #[must_use]
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Skip inputs which don't exist instead of failing the collection
+    #[must_use]
+    pub fn skip_missing_inputs(mut self, skip_missing_inputs: bool) -> Self {
+        self.skip_missing_inputs = skip_missing_inputs;
+        self
+    }
+
+    /// Read at most this many inputs at the same time
+    #[must_use]
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    /// Label all links found in the given input with `tags`
+    /// (e.g. `docs` or `generated`). The tags are carried through to the
+    /// responses, so results can be grouped by them.
+    #[must_use]
+    pub fn tag(mut self, input: Input, tags: Vec<String>) -> Self {
+        self.tags.insert(input, tags);
+        self
+    }
+
+    /// Attach `metadata` to all links found in the given input
+    /// (e.g. the owning team or the section of the documentation).
+    /// The metadata is carried through to the responses and their reports,
+    /// so broken links can be routed to their owners.
+    #[must_use]
+    pub fn metadata(mut self, input: Input, metadata: BTreeMap<String, String>) -> Self {
+        self.metadata.insert(input, metadata);
+        self
+    }
+
+    /// Add the owners of each file to the metadata of its links (as `owners`),
+    /// so reports tell which team can fix a broken link.
+    /// Paths are relative to the current directory, which should be the root
+    /// of the repository.
+    #[must_use]
+    pub fn code_owners(mut self, code_owners: CodeOwners) -> Self {
+        self.code_owners = Some(Arc::new(code_owners));
+        self
+    }
+
+    /// A collector with the collector options of a [`Config`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::UrlParseError`](crate::ErrorKind::UrlParseError)
+    /// if the base URL is invalid
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let options = &config.collector;
+        let base_url = match &options.base_url {
+            Some(base_url) => Some(Url::parse(base_url).map_err(|e| (base_url.clone(), e))?),
+            None => None,
+        };
+        let mut collector = Collector::new(
+            base_url,
+            options.skip_missing,
+            options.max_concurrency.unwrap_or(DEFAULT_MAX_CONCURRENCY),
+        );
+        if let Some(max) = options.max_links {
+            collector = collector.max_links_total(max);
+        }
+        if let Some(max) = options.max_links_per_input {
+            collector = collector.max_links_per_input(max);
+        }
+        Ok(collector.lenient_urls(options.lenient_urls))
+    }
+
+    /// Collect at most this many links over all inputs, e.g. to protect CI
+    /// from generated pages with millions of links. Further links are dropped
+    /// and counted in the [`truncation`](Collector::truncation).
+    #[must_use]
+    pub const fn max_links_total(mut self, max: usize) -> Self {
+        self.max_links_total = Some(max);
+        self
+    }
+
+    /// Collect at most this many links of each input (e.g. each file).
+    /// Further links of the input are dropped and counted in the
+    /// [`truncation`](Collector::truncation).
+    #[must_use]
+    pub const fn max_links_per_input(mut self, max: usize) -> Self {
+        self.max_links_per_input = Some(max);
+        self
+    }
+
+    /// Percent-encode characters which aren't valid in URLs (e.g. spaces,
+    /// braces or a stray `%`) in the links of documents. The responses of
+    /// such links suggest encoding them (see [`Uri::parse_lenient`](crate::Uri::parse_lenient)).
+    #[must_use]
+    pub const fn lenient_urls(mut self, lenient_urls: bool) -> Self {
+        self.lenient_urls = lenient_urls;
+        self
+    }
+
+    /// The counts of the links which were dropped because of the limits,
+    /// to read them once the collection is done
+    #[must_use]
+    pub fn truncation(&self) -> Truncation {
+        self.truncation.clone()
+    }
+
+    /// Only collect links on lines which were added or modified by a diff,
+    /// e.g. to check just the links a pull request touched
+    #[must_use]
+    pub fn changed_lines(mut self, changes: ChangedLines) -> Self {
+        self.changes = Some(Arc::new(changes));
+        self
+    }
+
+    /// Skip links which are excluded by `filter`, e.g. the one of a client.
+    /// Together with [`Collector::stream_links`], this lists the links a client
+    /// would check without making any requests.
+    #[must_use]
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Record the progress of the link collection in the given event log
+    #[must_use]
+    pub fn event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.event_log = Some(event_log);
+        self
+    }
+
+    /// Record which input links to which URIs in the given graph
+    #[must_use]
+    pub fn link_graph(mut self, link_graph: Arc<LinkGraph>) -> Self {
+        self.link_graph = Some(link_graph);
+        self
+    }
+
+    /// Extract links from inputs with the given file extension (e.g. `wiki`)
+    /// with a custom extractor instead of the built-in ones
+    #[must_use]
+    pub fn extractor(mut self, extension: &str, extractor: Arc<dyn Extractor>) -> Self {
+        self.extractors.insert(extension.to_lowercase(), extractor);
+        self
+    }
+
+    /// Detect links in plaintext inputs (e.g. `.txt` files or stdin)
+    /// with the given settings, e.g. to find links without a scheme
+    #[must_use]
+    pub const fn plaintext(mut self, extractor: PlaintextExtractor) -> Self {
+        self.plaintext = Some(extractor);
+        self
+    }
+
+    /// Extract links from Markdown and MDX inputs with the given settings,
+    /// e.g. to check links in code blocks or front matter as well
+    #[must_use]
+    pub fn markdown(mut self, extractor: MarkdownExtractor) -> Self {
+        self.markdown = Some(extractor);
+        self
+    }
+
+    /// Extract links from HTML inputs with the given settings,
+    /// e.g. to skip `rel="nofollow"` links or elements with a class
+    #[must_use]
+    pub fn html(mut self, extractor: HtmlExtractor) -> Self {
+        self.html = Some(extractor);
+        self
+    }
+
+    /// Render remote inputs in a headless browser before extracting their
+    /// links, e.g. for single-page applications
+    #[cfg(feature = "render")]
+    #[must_use]
+    pub fn renderer(mut self, renderer: crate::Renderer) -> Self {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// The custom extractor for the file extension of the input, if any,
+    /// or the configured one for its file type
+    fn extractor_for(&self, input_content: &InputContent) -> Option<Arc<dyn Extractor>> {
+        self.custom_extractor(&input_content.input)
+            .or_else(|| match input_content.file_type {
+                FileType::Plaintext => Some(Arc::new(self.plaintext?) as Arc<dyn Extractor>),
+                FileType::Markdown | FileType::Mdx => {
+                    Some(Arc::new(self.markdown.clone()?) as Arc<dyn Extractor>)
+                }
+                FileType::Html => Some(Arc::new(self.html.clone()?) as Arc<dyn Extractor>),
+                _ => None,
+            })
+    }
+
+    /// The custom extractor for the file extension of the input, if any
+    fn custom_extractor(&self, input: &Input) -> Option<Arc<dyn Extractor>> {
+        if self.extractors.is_empty() {
+            return None;
+        }
+        let extension = match input {
+            Input::FsPath(path) => path.extension()?.to_str()?.to_lowercase(),
+            Input::RemoteUrl(url) => Path::new(url.path()).extension()?.to_str()?.to_lowercase(),
+            _ => return None,
+        };
+        self.extractors.get(&extension).cloned()
+    }
+
+    fn emit(&self, event: &Event<'_>) {
+        if let Some(event_log) = &self.event_log {
+            event_log.emit(event);
         }
     }
 
@@ -241,62 +576,206 @@ impl Collector {
     /// # Errors
     ///
     /// Will return `Err` if links cannot be extracted from an input
-    pub async fn collect_links(mut self, inputs: &[Input]) -> Result<HashSet<Request>> {
-        let (contents_tx, mut contents_rx) = tokio::sync::mpsc::channel(self.max_concurrency);
-
-        // extract input contents
-        for input in inputs.iter().cloned() {
-            let sender = contents_tx.clone();
+    pub async fn collect_links(self, inputs: &[Input]) -> Result<HashSet<Request>> {
+        let (sender, mut receiver) = mpsc::channel(self.max_concurrency.max(1));
+        let receiving = async {
+            let mut links = HashSet::new();
+            while let Some(link) = receiver.recv().await {
+                links.insert(link);
+            }
+            links
+        };
+        let (result, links) = tokio::join!(self.stream_links(inputs, sender), receiving);
+        result.map(|()| links)
+    }
 
-            let skip_missing_inputs = self.skip_missing_inputs;
-            tokio::spawn(async move {
-                let contents = input.get_contents(None, skip_missing_inputs).await;
-                sender.send(contents).await
-            });
+    /// Send the links of all inputs to `sender` as soon as they are extracted,
+    /// so they can be checked while the collection is still running.
+    /// Files, including the ones matching glob patterns, are read and parsed
+    /// concurrently, up to `max_concurrency` at a time.
+    /// Returns once all inputs are done, or the receiver was dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if links cannot be extracted from an input.
+    /// The links of the other inputs are sent nonetheless.
+    pub async fn stream_links(self, inputs: &[Input], sender: mpsc::Sender<Request>) -> Result<()> {
+        self.emit(&Event::CollectionStarted {
+            inputs: inputs.len(),
+        });
+        for count in &[
+            &self.truncation.sent,
+            &self.truncation.dropped_links,
+            &self.truncation.truncated_inputs,
+        ] {
+            count.store(0, Ordering::Relaxed);
         }
+        let inputs = self.expand_globs(inputs).await?;
 
-        // receiver will get None once all tasks are done
-        drop(contents_tx);
+        let collector = Arc::new(self);
+        let limit = ConcurrencyLimit::new(collector.max_concurrency);
+        let mut handles = Vec::with_capacity(inputs.len());
+        for (input, origin) in inputs {
+            let collector = collector.clone();
+            let sender = sender.clone();
+            handles.push(
+                limit.spawn(async move { collector.collect_input(input, &origin, &sender).await }),
+            );
+        }
+        drop(sender);
 
-        // extract links from input contents
-        let mut extract_links_handles = vec![];
+        let mut result = Ok(());
+        for handle in handles {
+            if let Err(e) = handle.await? {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
 
-        while let Some(result) = contents_rx.recv().await {
-            for input_content in result? {
-                let base_url = self.base_url.clone();
-                let handle =
-                    tokio::task::spawn_blocking(move || extract_links(&input_content, &base_url));
-                extract_links_handles.push(handle);
+    /// Replace glob patterns by the files they match, so every file
+    /// is read and parsed on its own. Every file comes with the input it
+    /// was given by, whose tags and metadata it gets.
+    async fn expand_globs(&self, inputs: &[Input]) -> Result<Vec<(Input, Input)>> {
+        let mut expanded = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            if let Input::FsGlob {
+                pattern,
+                ignore_case,
+            } = input
+            {
+                let (pattern, ignore_case) = (pattern.clone(), *ignore_case);
+                let paths =
+                    tokio::task::spawn_blocking(move || Input::glob_paths(&pattern, ignore_case))
+                        .await??;
+                expanded.extend(
+                    paths
+                        .into_iter()
+                        .map(|path| (Input::FsPath(path), input.clone())),
+                );
+            } else {
+                expanded.push((input.clone(), input.clone()));
             }
         }
+        Ok(expanded)
+    }
 
-        // Note: we could dispatch links to be checked as soon as we get them,
-        //       instead of building a HashSet with all links.
-        //       This optimization would speed up cases where there's
-        //       a lot of inputs and/or the inputs are large (e.g. big files).
-        let mut links: HashSet<Request> = HashSet::new();
+    /// Count a link of an input against the limits and return whether it can
+    /// be sent. The first dropped link of an input marks it as truncated.
+    fn within_limits(&self, collected: &mut usize, truncated: &mut bool) -> bool {
+        let truncation = &self.truncation;
+        if self
+            .max_links_per_input
+            .map_or(false, |max| *collected >= max)
+        {
+            if !*truncated {
+                *truncated = true;
+                truncation.truncated_inputs.fetch_add(1, Ordering::Relaxed);
+            }
+        } else if self.max_links_total.map_or(true, |max| {
+            truncation.sent.fetch_add(1, Ordering::Relaxed) < max
+        }) {
+            *collected += 1;
+            return true;
+        }
+        truncation.dropped_links.fetch_add(1, Ordering::Relaxed);
+        false
+    }
 
-        for handle in extract_links_handles {
-            let new_links = handle.await?;
-            links.extend(new_links);
+    /// The contents of an input. Remote inputs are rendered in a browser
+    /// if a renderer is configured for them.
+    async fn contents(&self, input: &Input) -> Result<Vec<InputContent>> {
+        #[cfg(feature = "render")]
+        if let (Input::RemoteUrl(url), Some(renderer)) = (input, &self.renderer) {
+            if renderer.renders(url) {
+                return Ok(vec![renderer.render(url).await?]);
+            }
         }
+        input.get_contents(None, self.skip_missing_inputs).await
+    }
 
-        // Filter out already cached links (duplicates)
-        links.retain(|l| !self.cache.contains(&l.uri));
+    /// Read an input, extract its links and send them
+    /// with the tags and metadata of the input they were given by
+    async fn collect_input(
+        &self,
+        input: Input,
+        origin: &Input,
+        sender: &mpsc::Sender<Request>,
+    ) -> Result<()> {
+        let tags = self.tags.get(origin).cloned().unwrap_or_default();
+        let mut metadata = self.metadata.get(origin).cloned().unwrap_or_default();
+        if let (Some(code_owners), Input::FsPath(path)) = (&self.code_owners, &input) {
+            let owners = code_owners.owners(&relative(path));
+            if !owners.is_empty() {
+                metadata.insert("owners".to_owned(), owners.join(" "));
+            }
+        }
+        // Links of the input which were sent, and whether some were dropped
+        let (mut collected, mut truncated) = (0, false);
+        for input_content in self.contents(&input).await? {
+            let base_url = self.base_url.clone();
+            let extractor = self.extractor_for(&input_content);
+            let lenient = self.lenient_urls;
+            let (input, links) = tokio::task::spawn_blocking(move || {
+                let links =
+                    extract_requests(&input_content, &base_url, extractor.as_deref(), lenient);
+                (input_content.input, links)
+            })
+            .await?;
+            self.emit(&Event::InputParsed {
+                input: &input,
+                links: links.len(),
+            });
+            if let Some(link_graph) = &self.link_graph {
+                link_graph.add(&input, links.iter().map(|link| &link.uri));
+            }
 
-        self.update_cache(&links);
-        Ok(links)
+            for link in links {
+                if let Some(changes) = &self.changes {
+                    if !changes.touches(&link) {
+                        continue;
+                    }
+                }
+                if let Some(filter) = &self.filter {
+                    if filter.is_excluded(&link.uri) {
+                        continue;
+                    }
+                }
+                if !self.within_limits(&mut collected, &mut truncated) {
+                    continue;
+                }
+                let link = Request {
+                    tags: tags.clone(),
+                    metadata: metadata.clone(),
+                    ..link
+                };
+                if sender.send(link).await.is_err() {
+                    // Nobody is interested in the links anymore
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
     }
+}
+
+/// A path relative to the current directory, if it is below it
+fn relative(path: &Path) -> PathBuf {
+    std::env::current_dir()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+}
 
-    /// Update internal link cache
-    fn update_cache(&mut self, links: &HashSet<Request>) {
-        self.cache.extend(links.iter().cloned().map(|l| l.uri));
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new(None, false, DEFAULT_MAX_CONCURRENCY)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use std::{fs::File, io::Write};
+    use std::{array, convert::TryFrom, fs::File, io::Write};
 
     use http::StatusCode;
     use pretty_assertions::assert_eq;
@@ -304,7 +783,7 @@ mod test {
 
     use super::*;
     use crate::{
-        extract::FileType,
+        extract::{FileType, RawLink, Selector},
         mock_server,
         test_utils::{mail, website},
         Result, Uri,
@@ -341,6 +820,24 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_buffer_input() -> Result<()> {
+        let input = Input::Buffer(
+            b"[docs](https://example.org/docs) \xff".to_vec(),
+            FileType::Markdown,
+        );
+        let contents = input.get_contents(Some(FileType::Html), false).await?;
+
+        assert_eq!(contents[0].file_type, FileType::Markdown);
+        assert_eq!(contents[0].input, input);
+        assert!(contents[0].content.ends_with('\u{fffd}'));
+
+        let links = Collector::default().collect_links(&[input]).await?;
+        let links: Vec<Uri> = links.into_iter().map(|request| request.uri).collect();
+        assert_eq!(links, vec![website("https://example.org/docs")]);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_collect_links() -> Result<()> {
         let temp_dir = tempfile::tempdir()?;
@@ -391,4 +888,251 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_collect_changed_lines() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("README.md");
+        let mut file = File::create(&file_path)?;
+        writeln!(file, "{}\n{}", TEST_URL, TEST_FILE)?;
+        let changes = ChangedLines::parse("+++ b/README.md\n@@ -2 +2 @@\n-old\n+new\n");
+
+        let links = Collector::default()
+            .changed_lines(changes)
+            .collect_links(&[
+                Input::FsPath(file_path),
+                Input::String(TEST_STRING.to_owned()),
+            ])
+            .await?;
+
+        let links: Vec<Uri> = links.into_iter().map(|request| request.uri).collect();
+        assert_eq!(links, vec![website(TEST_FILE)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collect_filtered_links() -> Result<()> {
+        let filter = Filter {
+            exclude_mail: true,
+            ..Filter::default()
+        };
+        let input = Input::String(format!("{} {}", TEST_URL, TEST_GLOB_2_MAIL));
+
+        let links = Collector::default()
+            .filter(filter)
+            .collect_links(&[input])
+            .await?;
+
+        let links: Vec<Uri> = links.into_iter().map(|request| request.uri).collect();
+        assert_eq!(links, vec![website(TEST_URL)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collect_links_tags() -> Result<()> {
+        let tagged = Input::String(TEST_STRING.to_owned());
+        let untagged = Input::String(TEST_URL.to_owned());
+
+        let requests = Collector::new(None, false, 8)
+            .tag(tagged.clone(), vec!["docs".to_owned()])
+            .collect_links(&[tagged, untagged])
+            .await?;
+
+        for request in requests {
+            if request.uri == website(TEST_STRING) {
+                assert_eq!(request.tags, vec!["docs".to_owned()]);
+            } else {
+                assert!(request.tags.is_empty());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collect_links_metadata() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("api.md");
+        File::create(&file_path)?.write_all(TEST_URL.as_bytes())?;
+        let pattern = Input::FsGlob {
+            pattern: temp_dir.path().join("*.md").to_str().unwrap().to_owned(),
+            ignore_case: false,
+        };
+        let metadata: BTreeMap<String, String> = vec![("team".to_owned(), "api".to_owned())]
+            .into_iter()
+            .collect();
+
+        let requests = Collector::new(None, false, 8)
+            .metadata(pattern.clone(), metadata.clone())
+            .collect_links(&[pattern])
+            .await?;
+
+        let requests: Vec<Request> = requests.into_iter().collect();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].metadata, metadata);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collect_links_limits() -> Result<()> {
+        let inputs = [
+            Input::String("https://a.org/1 https://a.org/2 https://a.org/3".to_owned()),
+            Input::String("https://b.org/1 https://b.org/2".to_owned()),
+        ];
+
+        let collector = Collector::default().max_links_per_input(2);
+        let truncation = collector.truncation();
+        assert_eq!(collector.collect_links(&inputs).await?.len(), 4);
+        assert_eq!(truncation.dropped_links(), 1);
+        assert_eq!(truncation.truncated_inputs(), 1);
+
+        let collector = Collector::default().max_links_total(3);
+        let truncation = collector.truncation();
+        assert_eq!(collector.collect_links(&inputs).await?.len(), 3);
+        assert_eq!(truncation.dropped_links(), 2);
+        assert_eq!(truncation.truncated_inputs(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_collect_lenient_urls() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("index.html");
+        File::create(&file_path)?
+            .write_all(br#"<a href="https://example.com/100%/{id}">Docs</a>"#)?;
+        let input = Input::FsPath(file_path);
+
+        let requests = Collector::default().collect_links(&[input.clone()]).await?;
+        let requests: Vec<Request> = requests.into_iter().collect();
+        assert!(requests[0].warnings.is_empty());
+
+        let requests = Collector::default()
+            .lenient_urls(true)
+            .collect_links(&[input])
+            .await?;
+        let requests: Vec<Request> = requests.into_iter().collect();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].uri,
+            website("https://example.com/100%25/%7Bid%7D")
+        );
+        assert_eq!(requests[0].warnings.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_links() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        for i in 0..10 {
+            let mut file = File::create(temp_dir.path().join(format!("{}.md", i)))?;
+            writeln!(file, "[link](https://example.org/{})", i)?;
+        }
+        let inputs = vec![
+            Input::FsGlob {
+                pattern: temp_dir.path().join("*.md").to_str().unwrap().to_owned(),
+                ignore_case: false,
+            },
+            Input::FsPath(temp_dir.path().join("missing.md")),
+        ];
+
+        let (sender, mut receiver) = mpsc::channel(1);
+        let (result, links) = tokio::join!(
+            Collector::new(None, false, 2).stream_links(&inputs, sender),
+            async {
+                let mut links = vec![];
+                while let Some(link) = receiver.recv().await {
+                    links.push(link.uri);
+                }
+                links
+            }
+        );
+
+        // The missing file fails, but all other links arrive nonetheless
+        assert!(result.is_err());
+        assert_eq!(links.len(), 10);
+        for i in 0..10 {
+            assert!(links.contains(&website(&format!("https://example.org/{}", i))));
+        }
+        Ok(())
+    }
+
+    /// Links in double angle brackets, e.g. `<<https://example.org>>`
+    #[derive(Debug)]
+    struct AngleBrackets;
+
+    impl Extractor for AngleBrackets {
+        fn extract(&self, content: &[u8], _: FileType) -> Vec<RawLink> {
+            let content = String::from_utf8_lossy(content);
+            content
+                .match_indices("<<")
+                .filter_map(|(start, _)| {
+                    let start = start + 2;
+                    let end = start + content[start..].find(">>")?;
+                    Some(RawLink::at(&content[start..end], start..end))
+                })
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_extractor() -> Result<()> {
+        let input = Input::String("See www.example.org/docs. Or <https://example.org/a.>".into());
+        let requests = Collector::default()
+            .plaintext(PlaintextExtractor {
+                bare_domains: true,
+                angle_brackets: true,
+                ..PlaintextExtractor::default()
+            })
+            .collect_links(&[input])
+            .await?;
+
+        let uris: HashSet<Uri> = requests.into_iter().map(|request| request.uri).collect();
+        let expected = array::IntoIter::new([
+            website("https://www.example.org/docs"),
+            website("https://example.org/a."),
+        ])
+        .collect::<HashSet<Uri>>();
+        assert_eq!(uris, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_html_extractor() -> Result<()> {
+        let html = r#"<a href="https://example.org/sponsor" rel="nofollow">Sponsor</a>
+            <pre class="no-check">curl https://example.org/api</pre>
+            <a href="https://example.org/docs">Docs</a>"#;
+        let input = Input::Buffer(html.as_bytes().to_vec(), FileType::Html);
+        let requests = Collector::default()
+            .html(HtmlExtractor {
+                skip_nofollow: true,
+                skip: Some(Selector::try_from(".no-check")?),
+                ..HtmlExtractor::default()
+            })
+            .collect_links(&[input])
+            .await?;
+
+        let uris: Vec<Uri> = requests.into_iter().map(|request| request.uri).collect();
+        assert_eq!(uris, vec![website("https://example.org/docs")]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_custom_extractor() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("page.wiki");
+        let mut file = File::create(&file_path)?;
+        writeln!(file, "Intro\nSee <<https://example.org/wiki>> for more")?;
+
+        let requests = Collector::default()
+            .extractor("wiki", Arc::new(AngleBrackets))
+            .collect_links(&[Input::FsPath(file_path)])
+            .await?;
+
+        let requests: Vec<Request> = requests.into_iter().collect();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].uri, website("https://example.org/wiki"));
+        assert_eq!(requests[0].spans[0].line, 2);
+        assert_eq!(requests[0].spans[0].column, 7);
+        Ok(())
+    }
 }
@@ -0,0 +1,136 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{redact, ErrorKind, Result, Uri};
+
+/// The changes of the failed links between two runs, e.g. of a pull request
+/// against the last run on the main branch, to fail only on regressions.
+///
+/// ```
+/// use lychee_lib::comparison::Comparison;
+///
+/// let previous = r#"{"fail_map": {"README.md": [{"url": "https://example.org/old", "status": "Failed: Network error"}]}}"#;
+/// let current = r#"{"fail_map": {"README.md": [{"url": "https://example.org/new", "status": "Failed: Network error"}]}}"#;
+/// let comparison = Comparison::from_reports(previous, current).unwrap();
+/// assert_eq!(comparison.newly_broken[0].as_str(), "https://example.org/new");
+/// assert_eq!(comparison.fixed[0].as_str(), "https://example.org/old");
+/// assert!(comparison.is_regression());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Comparison {
+    /// Links which failed in the current run, but not in the previous one
+    pub newly_broken: Vec<Uri>,
+    /// Links which failed in the previous run, but not in the current one
+    pub fixed: Vec<Uri>,
+    /// Links which failed in both runs
+    pub still_broken: Vec<Uri>,
+}
+
+/// The part of a JSON report which lists the failed links by input
+#[derive(Deserialize)]
+struct Report {
+    #[serde(default)]
+    fail_map: HashMap<String, Vec<Uri>>,
+}
+
+impl Comparison {
+    /// Compare the failed links of two runs, which are sorted in the result
+    #[must_use]
+    pub fn new(
+        previous: impl IntoIterator<Item = Uri>,
+        current: impl IntoIterator<Item = Uri>,
+    ) -> Self {
+        // Reports contain redacted URLs, so credentials must not tell
+        // links apart
+        let previous: BTreeSet<Uri> = previous.into_iter().map(redact).collect();
+        let current: BTreeSet<Uri> = current.into_iter().map(redact).collect();
+        Comparison {
+            newly_broken: current.difference(&previous).cloned().collect(),
+            fixed: previous.difference(&current).cloned().collect(),
+            still_broken: current.intersection(&previous).cloned().collect(),
+        }
+    }
+
+    /// Compare the failed links of two JSON reports (`--format json`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidReport`] if one of the reports
+    /// isn't a JSON report
+    pub fn from_reports(previous: &str, current: &str) -> Result<Self> {
+        Ok(Self::new(failed_links(previous)?, failed_links(current)?))
+    }
+
+    /// Whether a link failed which didn't fail before
+    #[must_use]
+    pub fn is_regression(&self) -> bool {
+        !self.newly_broken.is_empty()
+    }
+}
+
+/// The failed links of a JSON report (`--format json`), over all inputs
+///
+/// # Errors
+///
+/// Returns an [`ErrorKind::InvalidReport`] if `report` isn't a JSON report
+pub fn failed_links(report: &str) -> Result<BTreeSet<Uri>> {
+    let report: Report =
+        serde_json::from_str(report).map_err(|e| ErrorKind::InvalidReport(e.to_string()))?;
+    Ok(report
+        .fail_map
+        .into_iter()
+        .flat_map(|(_, uris)| uris)
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{failed_links, Comparison};
+    use crate::test_utils::website;
+
+    #[test]
+    fn test_compare() {
+        let comparison = Comparison::new(
+            vec![website("https://a.org/"), website("https://b.org/")],
+            vec![website("https://b.org/"), website("https://c.org/")],
+        );
+        assert_eq!(
+            comparison,
+            Comparison {
+                newly_broken: vec![website("https://c.org/")],
+                fixed: vec![website("https://a.org/")],
+                still_broken: vec![website("https://b.org/")],
+            }
+        );
+        assert!(comparison.is_regression());
+        assert!(!Comparison::new(vec![website("https://a.org/")], vec![]).is_regression());
+    }
+
+    #[test]
+    fn test_failed_links() {
+        let report = r#"{
+            "total": 3,
+            "fail_map": {
+                "README.md": [
+                    {"url": "https://a.org/", "status": "Failed: Network error", "error_code": "ERR_NETWORK"}
+                ],
+                "docs/index.md": [
+                    {"url": "https://a.org/", "status": "Timeout"},
+                    {"url": "https://b.org/", "status": "Failed: Network error"}
+                ]
+            }
+        }"#;
+        assert_eq!(
+            failed_links(report)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![website("https://a.org/"), website("https://b.org/")]
+        );
+        assert!(failed_links(r#"{"total": 0}"#).unwrap().is_empty());
+        assert!(failed_links("lychee").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::{future::Future, sync::Arc};
+
+use tokio::{sync::Semaphore, task::JoinHandle};
+
+/// Default number of inputs which are read or links which are checked
+/// at the same time
+pub(crate) const DEFAULT_MAX_CONCURRENCY: usize = 128;
+
+/// Limits the number of tasks which run at the same time.
+/// Clones share the limit.
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrencyLimit {
+    permits: Arc<Semaphore>,
+}
+
+impl ConcurrencyLimit {
+    /// Run at most `max_concurrency` tasks at the same time
+    #[must_use]
+    pub(crate) fn new(max_concurrency: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrency.max(1))),
+        }
+    }
+
+    /// Spawn a task, which waits for one of the running tasks to finish
+    /// if the limit is reached
+    pub(crate) fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let permits = self.permits.clone();
+        tokio::spawn(async move {
+            // The semaphore is never closed
+            let _permit = permits.acquire_owned().await.ok();
+            future.await
+        })
+    }
+}
+
+impl Default for ConcurrencyLimit {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENCY)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use super::ConcurrencyLimit;
+
+    #[tokio::test]
+    async fn test_limit() {
+        let limit = ConcurrencyLimit::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..6)
+            .map(|_| {
+                let running = running.clone();
+                limit.spawn(async move {
+                    let before = running.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    before
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.await.unwrap() < 2);
+        }
+    }
+}
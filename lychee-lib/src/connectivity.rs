@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use crate::{ErrorKind, Response, Result, Status};
+
+/// Tells an unavailable network (e.g. a CI runner without egress) from
+/// broken links, so a run can be aborted instead of reporting every link.
+///
+/// The network counts as unavailable once a number of different hosts
+/// failed with DNS, connection or timeout errors before any host answered.
+/// A single answer, even with an error status, proves that the network works.
+///
+/// ```
+/// use lychee_lib::{Connectivity, ErrorKind, Input, Response, Status, Uri};
+/// use std::convert::TryFrom;
+///
+/// let mut connectivity = Connectivity::new(2);
+/// for url in &["https://example.org/a", "https://example.org/b", "https://example.com/"] {
+///     let uri = Uri::try_from(*url).unwrap();
+///     let response = Response::new(uri, Status::Timeout(None), Input::Stdin);
+///     if let Err(e) = connectivity.record(&response) {
+///         assert_eq!(e, ErrorKind::NetworkUnavailable(2));
+///     }
+/// }
+/// assert!(connectivity.is_unavailable());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Connectivity {
+    threshold: usize,
+    unreachable_hosts: HashSet<String>,
+    reachable: bool,
+}
+
+impl Connectivity {
+    /// Consider the network unavailable once `threshold` hosts couldn't be
+    /// reached before any host could
+    #[must_use]
+    pub fn new(threshold: usize) -> Self {
+        Connectivity {
+            threshold: threshold.max(1),
+            unreachable_hosts: HashSet::new(),
+            reachable: false,
+        }
+    }
+
+    /// Record the response of a check. Mail addresses, local files and
+    /// links which weren't checked don't tell anything about the network.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::NetworkUnavailable`] if the network is
+    /// considered unavailable
+    pub fn record(&mut self, response: &Response) -> Result<()> {
+        let Response(_, body) = response;
+        let host = match body.uri.host() {
+            Some(host) if !body.uri.is_mail() => host,
+            _ => return Ok(()),
+        };
+        match &body.status {
+            Status::Excluded | Status::Unsupported(_) | Status::Skipped(_) => {}
+            status
+                if status
+                    .network_error()
+                    .map_or(false, |e| e.is_connectivity()) =>
+            {
+                self.unreachable_hosts.insert(host.to_owned());
+            }
+            _ => self.reachable = true,
+        }
+        if self.is_unavailable() {
+            return Err(ErrorKind::NetworkUnavailable(self.unreachable_hosts.len()));
+        }
+        Ok(())
+    }
+
+    /// Whether no host could be reached, but at least `threshold` hosts
+    /// were tried
+    #[must_use]
+    pub fn is_unavailable(&self) -> bool {
+        !self.reachable && self.unreachable_hosts.len() >= self.threshold
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::StatusCode;
+
+    use super::Connectivity;
+    use crate::{test_utils::response, ErrorKind, Status};
+
+    #[test]
+    fn test_unavailable() {
+        let mut connectivity = Connectivity::new(3);
+        let timeout = || Status::Timeout(None);
+        assert!(connectivity
+            .record(&response("https://a.org/1", timeout()))
+            .is_ok());
+        // The same host only counts once
+        assert!(connectivity
+            .record(&response("https://a.org/2", timeout()))
+            .is_ok());
+        assert!(connectivity
+            .record(&response("https://b.org/", timeout()))
+            .is_ok());
+        assert!(connectivity
+            .record(&response("https://c.org/", Status::Excluded))
+            .is_ok());
+        assert_eq!(
+            connectivity.record(&response("https://d.org/", timeout())),
+            Err(ErrorKind::NetworkUnavailable(3))
+        );
+        assert!(connectivity.is_unavailable());
+    }
+
+    #[test]
+    fn test_available() {
+        let mut connectivity = Connectivity::new(2);
+        // An unexpected status is still an answer of the host
+        let unexpected = ErrorKind::UnexpectedStatusCode(StatusCode::from_u16(999).unwrap());
+        connectivity
+            .record(&response("https://a.org/", unexpected.into()))
+            .unwrap();
+        for url in &["https://b.org/", "https://c.org/", "https://d.org/"] {
+            connectivity
+                .record(&response(url, Status::Timeout(None)))
+                .unwrap();
+        }
+        assert!(!connectivity.is_unavailable());
+    }
+}
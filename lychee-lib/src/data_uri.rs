@@ -0,0 +1,139 @@
+use crate::{ErrorKind, Result};
+
+/// Top-level media types, to tell plausible ones from typos
+const MEDIA_TYPES: &[&str] = &[
+    "application",
+    "audio",
+    "font",
+    "image",
+    "message",
+    "model",
+    "multipart",
+    "text",
+    "video",
+];
+
+/// Validate a `data:[<media type>][;base64],<data>` URI (RFC 2397):
+/// the media type has to be plausible and base64 content has to decode
+pub(crate) fn validate(uri: &str) -> Result<()> {
+    let rest = uri
+        .strip_prefix("data:")
+        .ok_or_else(|| invalid("missing data: scheme"))?;
+    let (meta, data) = rest
+        .split_once(',')
+        .ok_or_else(|| invalid("missing comma before the data"))?;
+    let mut parameters = meta.split(';');
+    let media_type = parameters.next().unwrap_or_default();
+    if !media_type.is_empty() && !is_media_type(media_type) {
+        return Err(invalid(&format!("implausible media type {}", media_type)));
+    }
+    let mut base64 = false;
+    for parameter in parameters {
+        if parameter.eq_ignore_ascii_case("base64") {
+            base64 = true;
+        } else if base64 || !parameter.contains('=') {
+            return Err(invalid(&format!("invalid parameter {}", parameter)));
+        }
+    }
+    if base64 && !is_base64(&percent_decode(data)) {
+        return Err(invalid("invalid base64 content"));
+    }
+    Ok(())
+}
+
+fn invalid(problem: &str) -> ErrorKind {
+    ErrorKind::InvalidDataUri(problem.to_owned())
+}
+
+/// Whether `media_type` looks like `type/subtype` with a known type
+fn is_media_type(media_type: &str) -> bool {
+    let is_token = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "!#$&^_.+-".contains(c))
+    };
+    match media_type.split_once('/') {
+        Some((main, sub)) => {
+            MEDIA_TYPES.contains(&main.to_ascii_lowercase().as_str()) && is_token(sub)
+        }
+        None => false,
+    }
+}
+
+/// Decode the percent-encoded bytes of the data, which URLs may contain
+fn percent_decode(data: &str) -> Vec<u8> {
+    let bytes = data.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 3).and_then(|hex| {
+            std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        });
+        match (bytes[i], escape) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// Whether `data` is valid base64, ignoring whitespace and
+/// missing padding like browsers do
+fn is_base64(data: &[u8]) -> bool {
+    let data: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let padding = data.iter().rev().take_while(|&&b| b == b'=').count();
+    let content = &data[..data.len() - padding];
+    padding <= 2
+        && (padding == 0 || data.len() % 4 == 0)
+        && content.len() % 4 != 1
+        && content
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/')
+}
+
+#[cfg(test)]
+mod test {
+    use super::validate;
+    use crate::ErrorKind;
+
+    #[test]
+    fn test_validate() {
+        for uri in &[
+            "data:,Hello%2C%20World%21",
+            "data:text/plain;charset=UTF-8,Hello",
+            "data:image/png;base64,iVBORw0KGgo=",
+            "data:image/svg+xml;base64,PHN2Zz4=",
+            "data:;base64,SGVsbG8",
+            "data:application/octet-stream;base64,SGVs%0AbG8=",
+        ] {
+            assert!(validate(uri).is_ok(), "{}", uri);
+        }
+        assert_eq!(
+            validate("data:image/png;base64,iVBOR*w0KGgo="),
+            Err(ErrorKind::InvalidDataUri(
+                "invalid base64 content".to_owned()
+            ))
+        );
+        assert_eq!(
+            validate("data:imgae/png;base64,iVBORw0KGgo="),
+            Err(ErrorKind::InvalidDataUri(
+                "implausible media type imgae/png".to_owned()
+            ))
+        );
+        assert!(validate("data:text/plain").is_err());
+        assert!(validate("data:image/png;base64,iVBORw0KG").is_err());
+        assert!(validate("data:image/png;base64,iVBORw0KGgo===").is_err());
+    }
+}
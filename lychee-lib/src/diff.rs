@@ -0,0 +1,216 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{Input, Request};
+
+/// Lines which were added or modified by a diff, per file
+///
+/// ```
+/// use std::path::Path;
+/// use lychee_lib::diff::ChangedLines;
+///
+/// let diff = "\
+/// --- a/README.md
+/// +++ b/README.md
+/// @@ -3,1 +3,2 @@
+///  Intro
+/// +See https://example.org
+/// ";
+/// let changes = ChangedLines::parse(diff);
+/// assert!(changes.contains(Path::new("README.md"), 4));
+/// assert!(!changes.contains(Path::new("README.md"), 3));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    files: HashMap<PathBuf, HashSet<usize>>,
+}
+
+impl ChangedLines {
+    /// Parse a unified diff, e.g. the output of `git diff main...`.
+    /// Lines which are no valid part of a diff are ignored.
+    #[must_use]
+    pub fn parse(diff: &str) -> Self {
+        let mut files: HashMap<PathBuf, HashSet<usize>> = HashMap::new();
+        let mut file: Option<PathBuf> = None;
+        // Line number in the new file and lines left in the current hunk
+        let (mut line, mut old_left, mut new_left) = (0, 0, 0);
+
+        for text in diff.lines() {
+            if old_left > 0 || new_left > 0 {
+                match text.chars().next() {
+                    Some('+') => {
+                        if let Some(file) = &file {
+                            files.entry(file.clone()).or_default().insert(line);
+                        }
+                        line += 1;
+                        new_left -= 1;
+                    }
+                    Some('-') => old_left -= 1,
+                    // "\ No newline at end of file"
+                    Some('\\') => {}
+                    _ => {
+                        line += 1;
+                        old_left -= 1;
+                        new_left -= 1;
+                    }
+                }
+                // Malformed hunks must not underflow
+                old_left = old_left.max(0);
+                new_left = new_left.max(0);
+            } else if let Some(path) = text.strip_prefix("+++ ") {
+                file = new_path(path);
+            } else if let Some(header) = text.strip_prefix("@@ ") {
+                if let Some((start, old, new)) = hunk_header(header) {
+                    line = start;
+                    old_left = old;
+                    new_left = new;
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Whether the diff changed nothing
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Whether `line` (starting at 1) of the file at `path` was added or
+    /// modified. The path may be absolute or relative to any directory above
+    /// the repository root, e.g. `./docs/README.md` matches `docs/README.md`.
+    #[must_use]
+    pub fn contains(&self, path: &Path, line: usize) -> bool {
+        let path = normalize(path);
+        self.files
+            .iter()
+            .any(|(file, lines)| path.ends_with(file) && lines.contains(&line))
+    }
+
+    /// Whether the link of a request appears on a changed line of its input.
+    /// Links from inputs which are no files, or without a known location,
+    /// were not touched by the diff.
+    #[must_use]
+    pub fn touches(&self, request: &Request) -> bool {
+        match &request.source {
+            Input::FsPath(path) => request
+                .spans
+                .iter()
+                .any(|span| self.contains(path, span.line)),
+            _ => false,
+        }
+    }
+}
+
+/// Path of the file after the change, without the `b/` prefix of git.
+/// Deleted files have no path.
+fn new_path(path: &str) -> Option<PathBuf> {
+    // Other tools append a timestamp after a tab
+    let path = path.split('\t').next()?.trim_end().trim_matches('"');
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.strip_prefix("b/").unwrap_or(path);
+    Some(normalize(Path::new(path)))
+}
+
+/// Start line in the new file and the number of old and new lines of a hunk
+/// from its header, e.g. `-3,1 +3,2 @@`
+fn hunk_header(header: &str) -> Option<(usize, isize, isize)> {
+    let mut ranges = header.split_whitespace();
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    let count = |range: &str| -> Option<(usize, isize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_count) = count(old)?;
+    let (start, new_count) = count(new)?;
+    Some((start, old_count, new_count))
+}
+
+/// Drop `.` components, so relative paths can be compared
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::ChangedLines;
+    use crate::{test_utils::website, Input, Request, Span};
+
+    const DIFF: &str = "\
+diff --git a/docs/README.md b/docs/README.md
+index 3b18e51..a9c3b2f 100644
+--- a/docs/README.md
++++ b/docs/README.md
+@@ -1,4 +1,5 @@
+ # Docs
+-See https://example.org/old
++See https://example.org/new
++++ and https://example.org/plus
+ Unchanged https://example.org/kept
+ End
+@@ -10 +11,0 @@
+-Removed
+diff --git a/gone.md b/gone.md
+deleted file mode 100644
+--- a/gone.md
++++ /dev/null
+@@ -1 +0,0 @@
+-https://example.org/gone
+";
+
+    fn request(path: &str, line: usize) -> Request {
+        let mut request = Request::new(
+            website("https://example.org/"),
+            Input::FsPath(PathBuf::from(path)),
+        );
+        request.spans = vec![Span {
+            start: 0,
+            end: 20,
+            line,
+            column: 1,
+        }];
+        request
+    }
+
+    #[test]
+    fn test_parse() {
+        let changes = ChangedLines::parse(DIFF);
+        let readme = Path::new("docs/README.md");
+        assert!(!changes.contains(readme, 1));
+        assert!(changes.contains(readme, 2));
+        assert!(changes.contains(readme, 3));
+        assert!(!changes.contains(readme, 4));
+        assert!(!changes.contains(readme, 11));
+        assert!(!changes.contains(Path::new("gone.md"), 1));
+    }
+
+    #[test]
+    fn test_touches() {
+        let changes = ChangedLines::parse(DIFF);
+        assert!(changes.touches(&request("./docs/README.md", 2)));
+        assert!(changes.touches(&request("/home/user/project/docs/README.md", 3)));
+        assert!(!changes.touches(&request("docs/README.md", 4)));
+        assert!(!changes.touches(&request("README.md", 2)));
+        assert!(!changes.touches(&Request::new(
+            website("https://example.org/"),
+            Input::String("https://example.org/".to_owned())
+        )));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(ChangedLines::parse("").is_empty());
+        assert!(ChangedLines::parse("not a diff\n@@ nonsense @@\n").is_empty());
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// A DNS cache shared by all clients built with it, so every host is only
+/// resolved once per `ttl` instead of for every link, which saves a lot of
+/// time in runs with many links to the same hosts.
+/// Register it with [`ClientBuilder::dns_cache`](crate::ClientBuilder).
+///
+/// Hosts are resolved by the system resolver, which doesn't tell the TTLs
+/// of its records, so all entries expire after the same `ttl`.
+/// The addresses of [`ClientBuilder::resolve`](crate::ClientBuilder) still
+/// take precedence.
+///
+/// The addresses of a host alternate between IPv6 and IPv4, as recommended
+/// by [RFC 8305](https://datatracker.ietf.org/doc/html/rfc8305#section-4),
+/// so if the first address can't be connected to, the other family gets
+/// tried right away instead of after all addresses of the same family.
+///
+/// ```
+/// use lychee_lib::{ClientBuilder, DnsCache};
+/// use std::time::Duration;
+///
+/// let client = ClientBuilder::builder()
+///     .dns_cache(DnsCache::new(Duration::from_secs(300)))
+///     .build()
+///     .client();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DnsCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    addresses: Vec<SocketAddr>,
+    resolved_at: Instant,
+}
+
+impl DnsCache {
+    /// A cache which keeps the addresses of a host for `ttl`
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        DnsCache {
+            ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    /// The entries, which are still consistent if a thread panicked while
+    /// holding the lock, as every update is a single insert
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The cached addresses of `host`, unless they expired
+    fn get(&self, host: &str) -> Option<Vec<SocketAddr>> {
+        self.entries()
+            .get(host)
+            .filter(|entry| entry.resolved_at.elapsed() < self.ttl)
+            .map(|entry| entry.addresses.clone())
+    }
+
+    pub(crate) fn insert(&self, host: String, addresses: Vec<SocketAddr>) {
+        let entry = Entry {
+            addresses: interleave(addresses),
+            resolved_at: Instant::now(),
+        };
+        self.entries().insert(host, entry);
+    }
+
+    /// The addresses of `host`, from the cache or resolved and cached
+    pub(crate) async fn lookup(&self, host: &str) -> io::Result<Vec<SocketAddr>> {
+        if let Some(addresses) = self.get(host) {
+            return Ok(addresses);
+        }
+        // The port is replaced by the one of the URL
+        let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+        self.insert(host.to_owned(), addresses.clone());
+        Ok(self.get(host).unwrap_or(addresses))
+    }
+}
+
+/// Alternate between the address families, starting with the family of the
+/// first address and keeping the order within each family
+fn interleave(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let first_is_ipv6 = match addresses.first() {
+        Some(address) => address.is_ipv6(),
+        None => return addresses,
+    };
+    let (preferred, fallback): (Vec<_>, Vec<_>) = addresses
+        .into_iter()
+        .partition(|address| address.is_ipv6() == first_is_ipv6);
+    let mut interleaved = Vec::with_capacity(preferred.len() + fallback.len());
+    let mut fallback = fallback.into_iter();
+    for address in preferred {
+        interleaved.push(address);
+        interleaved.extend(fallback.next());
+    }
+    interleaved.extend(fallback);
+    interleaved
+}
+
+impl Resolve for DnsCache {
+    fn resolve(&self, name: Name) -> Resolving {
+        let cache = self.clone();
+        Box::pin(async move {
+            let addresses = cache.lookup(name.as_str()).await?;
+            Ok::<_, Box<dyn Error + Send + Sync>>(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+        str::FromStr,
+        time::Duration,
+    };
+
+    use reqwest::dns::{Name, Resolve};
+
+    use super::{interleave, DnsCache};
+
+    #[tokio::test]
+    async fn test_cached() {
+        let cache = DnsCache::new(Duration::from_secs(60));
+        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 5)), 0);
+        // A host which can't be resolved is answered from the cache
+        cache.insert("staging.invalid".to_owned(), vec![address]);
+        let resolved: Vec<SocketAddr> = cache
+            .resolve(Name::from_str("staging.invalid").unwrap())
+            .await
+            .unwrap()
+            .collect();
+        assert_eq!(resolved, vec![address]);
+
+        let expired = DnsCache::new(Duration::from_secs(0));
+        expired.insert("staging.invalid".to_owned(), vec![address]);
+        assert!(expired.get("staging.invalid").is_none());
+        assert!(expired
+            .resolve(Name::from_str("staging.invalid").unwrap())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let cache = DnsCache::new(Duration::from_secs(60));
+        let resolved: Vec<SocketAddr> = cache
+            .resolve(Name::from_str("localhost").unwrap())
+            .await
+            .unwrap()
+            .collect();
+        assert!(resolved.iter().any(|address| address.ip().is_loopback()));
+        assert!(cache.get("localhost").is_some());
+    }
+
+    #[test]
+    fn test_interleave() {
+        let v4 = |last| SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)), 0);
+        let v6 =
+            |last| SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, last)), 0);
+        assert_eq!(
+            interleave(vec![v6(1), v6(2), v6(3), v4(1), v4(2)]),
+            vec![v6(1), v4(1), v6(2), v4(2), v6(3)]
+        );
+        assert_eq!(
+            interleave(vec![v4(1), v4(2), v4(3), v6(1)]),
+            vec![v4(1), v6(1), v4(2), v4(3)]
+        );
+        assert!(interleave(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+use http::{header, StatusCode};
+use serde::Deserialize;
+
+use crate::{ErrorKind, Result, Status, Uri};
+
+/// Registry of Docker Hub, which hosts images without a registry in their name
+const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+
+/// Manifest formats to accept, so registries don't reject the request
+/// for images which only exist as multi-platform or OCI images
+const MANIFEST_TYPES: &str = "application/vnd.docker.distribution.manifest.v2+json, \
+                              application/vnd.docker.distribution.manifest.list.v2+json, \
+                              application/vnd.oci.image.manifest.v1+json, \
+                              application/vnd.oci.image.index.v1+json";
+
+/// An image in a registry implementing the Docker Registry HTTP API v2
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DockerImage {
+    /// Host (and port) of the registry
+    registry: String,
+    /// Name of the repository, including its namespace (e.g. `library/ubuntu`)
+    repository: String,
+    /// Tag or digest, if the link points to a specific version
+    reference: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RegistryToken {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+impl DockerImage {
+    /// Parse a link to an image:
+    ///
+    /// - `docker://[registry/]repository[:tag|@digest]`
+    ///   (e.g. `docker://ghcr.io/lycheeverse/lychee:latest`)
+    /// - pages on Docker Hub (`hub.docker.com/_/ubuntu`, `hub.docker.com/r/owner/image`)
+    /// - images on GHCR (`ghcr.io/owner/image`), also via their page on GitHub
+    ///   (`github.com/owner/repo/pkgs/container/image`)
+    ///
+    /// Returns `None` for any other URI.
+    pub(crate) fn parse(uri: &Uri) -> Option<Self> {
+        let host = uri.host()?;
+        let segments: Vec<&str> = uri.path_segments()?.filter(|s| !s.is_empty()).collect();
+        if uri.scheme() == "docker" {
+            // Like Docker, treat the first component as a registry
+            // if it looks like a host name
+            return match uri.url.port() {
+                Some(port) => Self::from_name(&format!("{}:{}", host, port), &segments),
+                None if host.contains('.') || host == "localhost" => {
+                    Self::from_name(host, &segments)
+                }
+                None => {
+                    let mut name = vec![host];
+                    name.extend(segments);
+                    Self::from_name(DOCKER_HUB_REGISTRY, &name)
+                }
+            };
+        }
+
+        let on_github = matches!(host, "github.com" | "www.github.com");
+        match *segments.as_slice() {
+            ["_", name, ..] if host == "hub.docker.com" => {
+                Self::from_name(DOCKER_HUB_REGISTRY, &[name])
+            }
+            ["r", owner, name, ..] if host == "hub.docker.com" => {
+                Self::from_name(DOCKER_HUB_REGISTRY, &[owner, name])
+            }
+            [_, _, ..] if host == "ghcr.io" => Self::from_name(host, &segments),
+            [owner, _, "pkgs", "container", name] if on_github => {
+                Self::from_name("ghcr.io", &[owner, name])
+            }
+            _ => None,
+        }
+    }
+
+    /// Split the name of an image into repository and reference
+    fn from_name(registry: &str, name: &[&str]) -> Option<Self> {
+        let (last, namespace) = name.split_last()?;
+        let (last, reference) = match last.split_once('@') {
+            Some((last, digest)) => (last, Some(digest.to_string())),
+            None => match last.split_once(':') {
+                Some((last, tag)) => (last, Some(tag.to_string())),
+                None => (*last, None),
+            },
+        };
+
+        let registry = match registry {
+            "docker.io" | "index.docker.io" => DOCKER_HUB_REGISTRY,
+            registry => registry,
+        };
+        let mut repository: Vec<&str> = namespace.to_vec();
+        // Official images on Docker Hub live in the `library` namespace
+        if registry == DOCKER_HUB_REGISTRY && repository.is_empty() {
+            repository.push("library");
+        }
+        repository.push(last);
+
+        Some(Self {
+            registry: registry.to_string(),
+            repository: repository.join("/"),
+            reference,
+        })
+    }
+
+    /// Endpoint which exists if the image does. Without a tag or digest,
+    /// only the repository is checked, as not every image has a `latest` tag.
+    fn api_url(&self) -> String {
+        match &self.reference {
+            Some(reference) => format!(
+                "https://{}/v2/{}/manifests/{}",
+                self.registry, self.repository, reference
+            ),
+            None => format!("https://{}/v2/{}/tags/list", self.registry, self.repository),
+        }
+    }
+
+    /// Check the image via the registry API. Registries require a token even
+    /// for public images, which is requested anonymously from the
+    /// authorization service the registry points to.
+    pub(crate) async fn check(&self, client: &reqwest::Client) -> Status {
+        let url = self.api_url();
+        let mut response = match request(client, &url, None).await {
+            Ok(response) => response,
+            Err(e) => return e.into(),
+        };
+        if response.status() == StatusCode::UNAUTHORIZED {
+            let challenge = response
+                .headers()
+                .get(header::WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_challenge);
+            let token = match challenge {
+                Some(challenge) => match token(client, &challenge).await {
+                    Ok(token) => token,
+                    Err(e) => return e.into(),
+                },
+                None => return Status::new(&response, None),
+            };
+            response = match request(client, &url, Some(&token)).await {
+                Ok(response) => response,
+                Err(e) => return e.into(),
+            };
+        }
+        Status::new(&response, None)
+    }
+}
+
+async fn request(
+    client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+) -> reqwest::Result<reqwest::Response> {
+    let mut request = client.head(url).header(header::ACCEPT, MANIFEST_TYPES);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    request.send().await
+}
+
+/// Request an anonymous pull token from the authorization service
+async fn token(client: &reqwest::Client, challenge: &[(String, String)]) -> Result<String> {
+    let param = |key: &str| challenge.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+    let realm = param("realm").ok_or(ErrorKind::MissingRegistryToken)?;
+    let query: Vec<(&str, &str)> = challenge
+        .iter()
+        .filter(|(k, _)| k != "realm")
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+
+    let response = client
+        .get(realm.as_str())
+        .query(&query)
+        .send()
+        .await?
+        .error_for_status()?;
+    let token: RegistryToken = serde_json::from_str(&response.text().await?)
+        .map_err(|_| ErrorKind::MissingRegistryToken)?;
+    token
+        .token
+        .or(token.access_token)
+        .ok_or(ErrorKind::MissingRegistryToken)
+}
+
+/// Parse the parameters of a bearer challenge, like
+/// `Bearer realm="https://auth.docker.io/token",service="registry.docker.io"`
+fn parse_challenge(challenge: &str) -> Option<Vec<(String, String)>> {
+    let params = challenge.strip_prefix("Bearer ")?;
+    // Values are quoted and may contain commas (e.g. `scope="repository:x:pull,push"`),
+    // so keys and values alternate when splitting at the quotes
+    let parts: Vec<&str> = params.split('"').collect();
+    let params: Vec<(String, String)> = parts
+        .chunks_exact(2)
+        .map(|pair| {
+            let key = pair[0].trim_matches(|c: char| c == ',' || c == '=' || c.is_whitespace());
+            (key.to_string(), pair[1].to_string())
+        })
+        .collect();
+    if params.is_empty() {
+        None
+    } else {
+        Some(params)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_challenge, DockerImage, DOCKER_HUB_REGISTRY};
+    use crate::test_utils::website;
+
+    fn image(registry: &str, repository: &str, reference: Option<&str>) -> Option<DockerImage> {
+        Some(DockerImage {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            reference: reference.map(ToString::to_string),
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            (
+                "docker://ghcr.io/lycheeverse/lychee:0.7.0",
+                image("ghcr.io", "lycheeverse/lychee", Some("0.7.0")),
+            ),
+            (
+                "docker://docker.io/ubuntu:20.04",
+                image(DOCKER_HUB_REGISTRY, "library/ubuntu", Some("20.04")),
+            ),
+            (
+                "docker://bitnami/redis@sha256:abc",
+                image(DOCKER_HUB_REGISTRY, "bitnami/redis", Some("sha256:abc")),
+            ),
+            (
+                "docker://localhost:5000/app",
+                image("localhost:5000", "app", None),
+            ),
+            (
+                "https://hub.docker.com/_/ubuntu",
+                image(DOCKER_HUB_REGISTRY, "library/ubuntu", None),
+            ),
+            (
+                "https://hub.docker.com/r/lycheeverse/lychee/tags",
+                image(DOCKER_HUB_REGISTRY, "lycheeverse/lychee", None),
+            ),
+            (
+                "https://github.com/lycheeverse/lychee/pkgs/container/lychee",
+                image("ghcr.io", "lycheeverse/lychee", None),
+            ),
+            ("https://hub.docker.com/search", None),
+            ("https://github.com/lycheeverse/lychee", None),
+        ];
+        for (uri, expected) in &cases {
+            assert_eq!(&DockerImage::parse(&website(uri)), expected, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn test_api_url() {
+        let image = DockerImage::parse(&website("docker://docker.io/ubuntu:20.04")).unwrap();
+        assert_eq!(
+            image.api_url(),
+            "https://registry-1.docker.io/v2/library/ubuntu/manifests/20.04"
+        );
+        let image = DockerImage::parse(&website("https://hub.docker.com/_/ubuntu")).unwrap();
+        assert_eq!(
+            image.api_url(),
+            "https://registry-1.docker.io/v2/library/ubuntu/tags/list"
+        );
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let challenge = r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/ubuntu:pull,push""#;
+        assert_eq!(
+            parse_challenge(challenge),
+            Some(vec![
+                (
+                    "realm".to_string(),
+                    "https://auth.docker.io/token".to_string()
+                ),
+                ("service".to_string(), "registry.docker.io".to_string()),
+                (
+                    "scope".to_string(),
+                    "repository:library/ubuntu:pull,push".to_string()
+                ),
+            ])
+        );
+        assert_eq!(parse_challenge(r#"Basic realm="registry""#), None);
+    }
+}
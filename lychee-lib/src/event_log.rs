@@ -0,0 +1,174 @@
+use std::{
+    fmt::Debug,
+    io::Write,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{Input, Observer, Request, Response, Status, Uri};
+
+/// Events which happen during a run, from collecting links to the summary
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum Event<'a> {
+    /// Started collecting links from the given inputs
+    CollectionStarted {
+        /// Number of inputs
+        inputs: usize,
+    },
+    /// Extracted the links of an input
+    InputParsed {
+        /// The parsed input
+        input: &'a Input,
+        /// Number of links found in the input
+        links: usize,
+    },
+    /// Sent a request to the client pool
+    RequestQueued {
+        /// The URI to check
+        uri: &'a str,
+        /// The input which contained the URI
+        source: &'a Input,
+    },
+    /// Started checking a request
+    RequestStarted {
+        /// The URI to check
+        uri: &'a str,
+        /// The input which contained the URI
+        source: &'a Input,
+    },
+    /// Retrying an unsuccessful check
+    Retry {
+        /// The URI to check
+        uri: &'a str,
+        /// Number of the upcoming retry, starting at 1
+        attempt: usize,
+        /// Status of the previous attempt
+        status: &'a Status,
+    },
+    /// Finished checking a request
+    Response {
+        /// The checked URI
+        uri: &'a str,
+        /// The input which contained the URI
+        source: &'a Input,
+        /// The final status of the check
+        status: &'a Status,
+    },
+    /// The run is over
+    Summary {
+        /// Statistics of the run
+        stats: serde_json::Value,
+    },
+}
+
+/// Append-only log of [`Event`]s, written as newline-delimited JSON (NDJSON).
+///
+/// Every event is written as a single line with a `timestamp` in
+/// milliseconds since the UNIX epoch and is flushed right away,
+/// so the log is complete up to the last event even if the run crashes.
+pub struct EventLog {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl EventLog {
+    /// Create a new event log, which writes to the given writer
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Append an event to the log.
+    ///
+    /// The log is a best-effort audit trail, so errors while writing
+    /// are ignored instead of aborting the run.
+    pub fn emit(&self, event: &Event<'_>) {
+        #[derive(Serialize)]
+        struct Record<'a, 'b> {
+            timestamp: u128,
+            #[serde(flatten)]
+            event: &'b Event<'a>,
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let line = match serde_json::to_string(&Record { timestamp, event }) {
+            Ok(line) => line,
+            Err(_) => return,
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", line).and_then(|_| writer.flush());
+        }
+    }
+}
+
+impl Debug for EventLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventLog").finish()
+    }
+}
+
+impl Observer for EventLog {
+    fn on_request_started(&self, request: &Request) {
+        self.emit(&Event::RequestStarted {
+            uri: request.uri.as_str(),
+            source: &request.source,
+        });
+    }
+
+    fn on_retry(&self, uri: &Uri, attempt: usize, status: &Status) {
+        self.emit(&Event::Retry {
+            uri: uri.as_str(),
+            attempt,
+            status,
+        });
+    }
+
+    fn on_response(&self, response: &Response) {
+        self.emit(&Event::Response {
+            uri: response.1.uri.as_str(),
+            source: &response.0,
+            status: &response.1.status,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Event, EventLog};
+    use crate::Input;
+
+    #[test]
+    fn test_emit_ndjson() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let log = EventLog::new(file.reopen().unwrap());
+
+        log.emit(&Event::CollectionStarted { inputs: 2 });
+        log.emit(&Event::RequestQueued {
+            uri: "https://example.org/",
+            source: &Input::Stdin,
+        });
+
+        let contents = fs::read_to_string(file.path()).unwrap();
+        let events: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "collection_started");
+        assert_eq!(events[0]["inputs"], 2);
+        assert!(events[0]["timestamp"].is_number());
+        assert_eq!(events[1]["event"], "request_queued");
+        assert_eq!(events[1]["uri"], "https://example.org/");
+    }
+}
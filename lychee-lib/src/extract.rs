@@ -1,4 +1,11 @@
-use std::{collections::HashSet, convert::TryFrom, path::Path};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    fmt::Debug,
+    ops::Range,
+    path::Path,
+};
 
 use html5ever::{
     parse_document,
@@ -9,17 +16,38 @@ use markup5ever_rcdom::{Handle, NodeData, RcDom};
 use pulldown_cmark::{Event as MDEvent, Parser, Tag};
 use url::Url;
 
-use crate::{collector::InputContent, Request, Uri};
+use crate::{collector::InputContent, Request, Span, Uri};
+
+mod frontmatter;
+mod selector;
+mod translation;
+
+use frontmatter::{frontmatter_links, split_frontmatter};
+pub use selector::Selector;
+use translation::{
+    extract_links_from_gettext, extract_links_from_json, extract_links_from_xliff,
+    extract_links_from_yaml,
+};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 /// `FileType` defines which file types lychee can handle
 pub enum FileType {
     /// File in HTML format
     Html,
     /// File in Markdown format
     Markdown,
+    /// File in MDX format, i.e. Markdown with JSX and ES modules
+    Mdx,
     /// Generic text file without syntax-specific parsing
     Plaintext,
+    /// Translations in the gettext PO format
+    Gettext,
+    /// Translations in the XLIFF format
+    Xliff,
+    /// JSON file, e.g. a bundle of translations
+    Json,
+    /// YAML file, e.g. a bundle of translations
+    Yaml,
 }
 
 impl Default for FileType {
@@ -41,84 +69,569 @@ impl<P: AsRef<Path>> From<P> for FileType {
         // `From<Url> for FileType` is not allowed.
         match path.extension().and_then(std::ffi::OsStr::to_str) {
             Some("md") | Some("markdown") => FileType::Markdown,
+            Some("mdx") => FileType::Mdx,
             Some("htm") | Some("html") | None => FileType::Html,
+            Some("po") | Some("pot") => FileType::Gettext,
+            Some("xlf") | Some("xliff") => FileType::Xliff,
+            Some("json") => FileType::Json,
+            Some("yml") | Some("yaml") => FileType::Yaml,
             Some(_) => FileType::Plaintext,
         }
     }
 }
 
-// Use LinkFinder here to offload the actual link searching in plaintext.
-fn find_links(input: &str) -> Vec<linkify::Link> {
-    let finder = LinkFinder::new();
-    finder.links(input).collect()
+/// A link found by an [`Extractor`], as it appears in the content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawLink {
+    /// The link as written, which may be relative
+    pub text: String,
+    /// Byte range of the link in the content, if known.
+    /// Without it, the locations of the link are found by searching the content.
+    pub range: Option<Range<usize>>,
 }
 
-/// Extract unparsed URL strings from a markdown string.
-fn extract_links_from_markdown(input: &str) -> Vec<String> {
-    let parser = Parser::new(input);
-    parser
-        .flat_map(|event| match event {
-            MDEvent::Start(Tag::Link(_, url, _)) | MDEvent::Start(Tag::Image(_, url, _)) => {
-                vec![url.to_string()]
+impl RawLink {
+    /// A link without a known location
+    #[must_use]
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            range: None,
+        }
+    }
+
+    /// A link at the given byte range of the content
+    #[must_use]
+    pub fn at(text: impl Into<String>, range: Range<usize>) -> Self {
+        Self {
+            text: text.into(),
+            range: Some(range),
+        }
+    }
+}
+
+/// Extracts links from inputs in formats lychee doesn't support natively,
+/// e.g. a custom wiki markup. Register it for a file extension with
+/// [`Collector::extractor`](crate::Collector::extractor).
+pub trait Extractor: Debug + Send + Sync {
+    /// Find all links in the content of an input.
+    /// `file_type` is the type lychee detected, which is
+    /// [`FileType::Plaintext`] for unknown file extensions.
+    fn extract(&self, content: &[u8], file_type: FileType) -> Vec<RawLink>;
+}
+
+/// Configurable link detection for plaintext inputs. The defaults match the
+/// built-in detection, other settings trade precision for recall.
+/// Use it for plaintext inputs with
+/// [`Collector::plaintext`](crate::Collector::plaintext).
+///
+/// ```
+/// use lychee_lib::extract::{Extractor, FileType, PlaintextExtractor};
+///
+/// let extractor = PlaintextExtractor {
+///     bare_domains: true,
+///     angle_brackets: true,
+///     ..PlaintextExtractor::default()
+/// };
+/// let text = "Visit www.example.com or <https://example.org/a_(b).>";
+/// let links: Vec<_> = extractor
+///     .extract(text.as_bytes(), FileType::Plaintext)
+///     .into_iter()
+///     .map(|link| link.text)
+///     .collect();
+/// assert_eq!(links, ["https://example.org/a_(b).", "https://www.example.com"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaintextExtractor {
+    /// Detect links without a scheme which start with `www.`
+    /// (e.g. `www.example.com`). They are checked over HTTPS.
+    pub bare_domains: bool,
+    /// Take everything between angle brackets as the link if it starts with
+    /// a scheme (e.g. `<https://example.com/a.>`), as recommended by RFC 3986
+    pub angle_brackets: bool,
+    /// Drop punctuation at the end of links (e.g. the full stop of
+    /// `See https://example.com.`), which is most likely part of the sentence
+    pub trim_punctuation: bool,
+}
+
+impl Default for PlaintextExtractor {
+    fn default() -> Self {
+        Self {
+            bare_domains: false,
+            angle_brackets: false,
+            trim_punctuation: true,
+        }
+    }
+}
+
+impl Extractor for PlaintextExtractor {
+    fn extract(&self, content: &[u8], _: FileType) -> Vec<RawLink> {
+        let content = String::from_utf8_lossy(content);
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        let mut links = Vec::new();
+        let mut add = |text: &str, range: Range<usize>| {
+            if !ranges
+                .iter()
+                .any(|other| other.start < range.end && range.start < other.end)
+            {
+                links.push(RawLink::at(text, range.clone()));
+                ranges.push(range);
+            }
+        };
+
+        if self.angle_brackets {
+            for range in autolinks(&content) {
+                add(&content[range.clone()], range);
+            }
+        }
+        for link in find_links(&content) {
+            let mut end = link.end;
+            if !self.trim_punctuation {
+                end += content[end..]
+                    .find(|c: char| !is_punctuation(c))
+                    .unwrap_or(content.len() - end);
+            }
+            add(&content[link.start..end], link.start..end);
+        }
+        if self.bare_domains {
+            for range in bare_domains(&content, self.trim_punctuation) {
+                add(&format!("https://{}", &content[range.clone()]), range);
             }
-            MDEvent::Text(txt) => extract_links_from_plaintext(&txt.to_string()),
-            MDEvent::Html(html) => extract_links_from_html(&html.to_string()),
-            _ => vec![],
+        }
+        links
+    }
+}
+
+/// Punctuation which usually ends a sentence rather than a link
+fn is_punctuation(c: char) -> bool {
+    ".,:;!?".contains(c)
+}
+
+/// Characters which can't be part of a link written in a text, even though
+/// they aren't whitespace: CJK and fullwidth punctuation, typographic quotes,
+/// dashes, ellipses, symbols and emoji. URLs would have them percent-encoded,
+/// while letters of any script are fine in links (IRIs).
+const fn ends_link(c: char) -> bool {
+    matches!(c,
+        // No-break space and guillemets
+        '\u{a0}' | '\u{ab}' | '\u{bb}'
+        // General punctuation: spaces, dashes, quotes, ellipsis
+        | '\u{2000}'..='\u{206f}'
+        // Arrows, mathematical and technical symbols, shapes and dingbats
+        | '\u{2190}'..='\u{2bff}'
+        // CJK symbols and punctuation
+        | '\u{3000}'..='\u{303f}'
+        // Variation selectors, vertical and CJK compatibility forms
+        | '\u{fe00}'..='\u{fe1f}'
+        | '\u{fe30}'..='\u{fe4f}'
+        // Fullwidth and halfwidth punctuation
+        | '\u{ff01}'..='\u{ff0f}'
+        | '\u{ff1a}'..='\u{ff20}'
+        | '\u{ff3b}'..='\u{ff40}'
+        | '\u{ff5b}'..='\u{ff65}'
+        // Emoji and pictographs
+        | '\u{1f000}'..='\u{1faff}'
+    )
+}
+
+/// Byte ranges of the links within angle brackets, e.g. `<https://example.com>`
+fn autolinks(content: &str) -> Vec<Range<usize>> {
+    content
+        .match_indices('<')
+        .filter_map(|(open, _)| {
+            let start = open + 1;
+            let end = start
+                + content[start..].find(|c: char| c == '>' || c == '<' || c.is_whitespace())?;
+            let inner = &content[start..end];
+            let (scheme, rest) = inner.split_once(':')?;
+            let is_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+            (content[end..].starts_with('>') && is_scheme && !rest.is_empty()).then(|| start..end)
         })
         .collect()
 }
 
-/// Extract unparsed URL strings from a HTML string.
-fn extract_links_from_html(input: &str) -> Vec<String> {
-    let tendril = StrTendril::from(input);
-    let rc_dom = parse_document(RcDom::default(), html5ever::ParseOpts::default()).one(tendril);
+/// Byte ranges of links without a scheme which start with `www.`
+fn bare_domains(content: &str, trim_punctuation: bool) -> Vec<Range<usize>> {
+    content
+        .match_indices("www.")
+        .filter(|(start, _)| {
+            // Skip URLs with a scheme, mail addresses and longer host names,
+            // but not text without spaces like in Chinese or Japanese
+            !content[..*start]
+                .ends_with(|c: char| c.is_ascii_alphanumeric() || "./@:-_".contains(c))
+        })
+        .filter_map(|(start, _)| {
+            let mut end = start
+                + content[start..]
+                    .find(|c: char| c.is_whitespace() || ends_link(c) || "<>\"'`".contains(c))
+                    .unwrap_or(content.len() - start);
+            // Never trim into the `www.` itself, e.g. at the end of "see www."
+            while end > start + 4 {
+                let link = &content[start..end];
+                let unbalanced =
+                    link.ends_with(')') && link.matches(')').count() > link.matches('(').count();
+                if unbalanced || (trim_punctuation && link.ends_with(is_punctuation)) {
+                    end -= 1;
+                } else {
+                    break;
+                }
+            }
+            // The host needs a top-level domain after `www.`
+            let host = content[start + 4..end]
+                .split('/')
+                .next()
+                .unwrap_or_default();
+            (host.contains('.') && !host.ends_with('.')).then(|| start..end)
+        })
+        .collect()
+}
 
-    let mut urls = Vec::new();
+// Use LinkFinder here to offload the actual link searching in plaintext.
+// It takes everything up to whitespace for a link, so links are cut at the
+// first character which ends them, e.g. the `。` of `见https://example.com。`,
+// and the punctuation before that goes to the sentence as usual.
+fn find_links(input: &str) -> Vec<Range<usize>> {
+    let finder = LinkFinder::new();
+    finder
+        .links(input)
+        .filter_map(|link| {
+            let start = link.start();
+            let mut text = link.as_str();
+            if let Some(cut) = text.find(ends_link) {
+                text = text[..cut].trim_end_matches(is_punctuation);
+                if text.ends_with(')') && text.matches(')').count() > text.matches('(').count() {
+                    text = text[..text.len() - 1].trim_end_matches(is_punctuation);
+                }
+            }
+            // Nothing is left of `https://“` after the cut
+            let rest = text.split_once("://").map_or(text, |(_, rest)| rest);
+            (!rest.is_empty()).then(|| start..start + text.len())
+        })
+        .collect()
+}
 
-    // we pass mutable urls reference to avoid extra allocations in each
-    // recursive descent
-    walk_html_links(&mut urls, &rc_dom.document);
+/// Settings of the link extraction from Markdown and MDX. By default, links in
+/// code are skipped, as code often contains example URLs
+/// (e.g. `https://example.com/<id>`). Use it for Markdown inputs with
+/// [`Collector::markdown`](crate::Collector::markdown).
+///
+/// Front matter (YAML between `---` lines or TOML between `+++` lines) is
+/// skipped, except for the values of `frontmatter_keys`:
+///
+/// ```
+/// use lychee_lib::extract::{Extractor, FileType, MarkdownExtractor};
+///
+/// let extractor = MarkdownExtractor {
+///     frontmatter_keys: vec!["image".to_owned()],
+///     ..MarkdownExtractor::default()
+/// };
+/// let markdown = "---\ntitle: Release\nimage: https://example.org/cover.png\n---\n";
+/// let links = extractor.extract(markdown.as_bytes(), FileType::Markdown);
+/// assert_eq!(links[0].text, "https://example.org/cover.png");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownExtractor {
+    /// Skip URLs in fenced and indented code blocks
+    pub skip_code_blocks: bool,
+    /// Skip URLs in inline code, e.g. `` `https://example.com` ``
+    pub skip_inline_code: bool,
+    /// Skip everything in blockquotes, including links,
+    /// e.g. for quotes of outdated documents
+    pub skip_blockquotes: bool,
+    /// Front matter keys with links, e.g. `image` or `social.links`
+    /// for nested keys. The values can be strings or arrays of strings.
+    pub frontmatter_keys: Vec<String>,
+}
 
-    urls
+impl Default for MarkdownExtractor {
+    fn default() -> Self {
+        Self {
+            skip_code_blocks: true,
+            skip_inline_code: true,
+            skip_blockquotes: false,
+            frontmatter_keys: Vec::new(),
+        }
+    }
 }
 
-/// Recursively walk links in a HTML document, aggregating URL strings in `urls`.
-fn walk_html_links(mut urls: &mut Vec<String>, node: &Handle) {
-    match node.data {
-        NodeData::Text { ref contents } => {
-            urls.append(&mut extract_links_from_plaintext(&contents.borrow()));
+impl MarkdownExtractor {
+    /// Extract unparsed URL strings from a markdown string.
+    /// MDX files have their ES module statements and JSX expressions skipped.
+    fn links(&self, input: &str, file_type: FileType) -> Vec<String> {
+        let (frontmatter, input) = split_frontmatter(input);
+        let mut links = frontmatter
+            .map(|frontmatter| frontmatter_links(&frontmatter, &self.frontmatter_keys))
+            .unwrap_or_default();
+        let mdx = file_type == FileType::Mdx;
+        let input = if mdx {
+            Cow::Owned(strip_esm(input))
+        } else {
+            Cow::Borrowed(input)
+        };
+        let mut code_block = false;
+        let mut blockquotes = 0_usize;
+        for event in Parser::new(&input) {
+            match event {
+                MDEvent::Start(Tag::CodeBlock(_)) => code_block = true,
+                MDEvent::End(Tag::CodeBlock(_)) => code_block = false,
+                MDEvent::Start(Tag::BlockQuote) => blockquotes += 1,
+                MDEvent::End(Tag::BlockQuote) => blockquotes = blockquotes.saturating_sub(1),
+                _ => {}
+            }
+            if (code_block && self.skip_code_blocks) || (blockquotes > 0 && self.skip_blockquotes) {
+                continue;
+            }
+            match event {
+                MDEvent::Start(Tag::Link(_, url, _)) | MDEvent::Start(Tag::Image(_, url, _)) => {
+                    links.push(url.to_string());
+                }
+                MDEvent::Text(txt) => links.append(&mut extract_links_from_plaintext(&txt)),
+                MDEvent::Code(code) if !self.skip_inline_code => {
+                    links.append(&mut extract_links_from_plaintext(&code));
+                }
+                MDEvent::Html(html) => links.extend(
+                    extract_links_from_html(&html)
+                        .into_iter()
+                        // Attribute values of JSX can be expressions, e.g. `href={url}`
+                        .filter(|link| !(mdx && link.starts_with('{'))),
+                ),
+                _ => {}
+            }
         }
+        links
+    }
+}
+
+/// Blank out the `import` and `export` statements of an MDX document,
+/// which are JavaScript rather than Markdown. Code blocks are kept.
+fn strip_esm(input: &str) -> String {
+    let mut fence: Option<&str> = None;
+    input
+        .split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            for &marker in &["```", "~~~"] {
+                if trimmed.starts_with(marker) {
+                    fence = match fence {
+                        Some(open) if open == marker => None,
+                        None => Some(marker),
+                        open => open,
+                    };
+                }
+            }
+            let is_esm =
+                fence.is_none() && (line.starts_with("import ") || line.starts_with("export "));
+            if is_esm {
+                "\n"
+            } else {
+                line
+            }
+        })
+        .collect()
+}
 
-        NodeData::Comment { ref contents } => {
-            urls.append(&mut extract_links_from_plaintext(contents));
+impl Extractor for MarkdownExtractor {
+    fn extract(&self, content: &[u8], file_type: FileType) -> Vec<RawLink> {
+        self.links(&String::from_utf8_lossy(content), file_type)
+            .into_iter()
+            .map(RawLink::new)
+            .collect()
+    }
+}
+
+/// Extract unparsed URL strings from a markdown string with the default settings
+fn extract_links_from_markdown(input: &str) -> Vec<String> {
+    MarkdownExtractor::default().links(input, FileType::Markdown)
+}
+
+/// Settings of the link extraction from HTML. By default, all links are
+/// extracted. Use it for HTML inputs with
+/// [`Collector::html`](crate::Collector::html).
+///
+/// Relative links are resolved against the `<base href>` of the document,
+/// if it has one.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lychee_lib::extract::{Extractor, FileType, HtmlExtractor, Selector};
+///
+/// let extractor = HtmlExtractor {
+///     skip_nofollow: true,
+///     skip: Some(Selector::try_from(".no-check").unwrap()),
+///     ..HtmlExtractor::default()
+/// };
+/// let html = r#"<a href="https://example.org/ad" rel="sponsored nofollow">Ad</a>
+///     <div class="no-check"><a href="https://example.org/example">Example</a></div>
+///     <a href="https://example.org/docs">Docs</a>"#;
+/// let links = extractor.extract(html.as_bytes(), FileType::Html);
+/// assert_eq!(links.len(), 1);
+/// assert_eq!(links[0].text, "https://example.org/docs");
+/// ```
+///
+/// Site-wide navigation can be left out by only extracting links from the
+/// main content of pages:
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use lychee_lib::extract::{Extractor, FileType, HtmlExtractor, Selector};
+///
+/// let extractor = HtmlExtractor {
+///     include: Some(Selector::try_from("main, article").unwrap()),
+///     skip: Some(Selector::try_from("//aside[@class='sidebar']").unwrap()),
+///     ..HtmlExtractor::default()
+/// };
+/// let html = r#"<nav><a href="https://example.org/">Home</a></nav>
+///     <main><a href="https://example.org/post">Post</a>
+///     <aside class="sidebar"><a href="https://example.org/tags">Tags</a></aside></main>"#;
+/// let links = extractor.extract(html.as_bytes(), FileType::Html);
+/// assert_eq!(links.len(), 1);
+/// assert_eq!(links[0].text, "https://example.org/post");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlExtractor {
+    /// Skip the links of elements with `rel="nofollow"`
+    pub skip_nofollow: bool,
+    /// Skip elements matching the selector and everything within them
+    pub skip: Option<Selector>,
+    /// Only extract links within elements matching the selector,
+    /// e.g. `main` to leave out the navigation of a site
+    pub include: Option<Selector>,
+}
+
+impl HtmlExtractor {
+    /// Extract unparsed URL strings from a HTML string.
+    fn links(&self, input: &str) -> Vec<String> {
+        let tendril = StrTendril::from(input);
+        let rc_dom = parse_document(RcDom::default(), html5ever::ParseOpts::default()).one(tendril);
+
+        let mut urls = Vec::new();
+        let mut base = None;
+
+        // we pass mutable urls reference to avoid extra allocations in each
+        // recursive descent
+        self.walk(
+            &mut urls,
+            &mut base,
+            &rc_dom.document,
+            self.include.is_none(),
+        );
+
+        match base {
+            Some(base) => urls
+                .into_iter()
+                .map(|url| resolve_base(&base, &url).unwrap_or(url))
+                .collect(),
+            None => urls,
         }
+    }
 
-        NodeData::Element {
-            ref name,
-            ref attrs,
-            ..
-        } => {
-            for attr in attrs.borrow().iter() {
-                let attr_value = attr.value.to_string();
+    /// Recursively walk links in a HTML document, aggregating URL strings in
+    /// `urls` and the first `<base href>` in `base`.
+    /// Links are only aggregated for nodes which are `included`.
+    fn walk(
+        &self,
+        urls: &mut Vec<String>,
+        base: &mut Option<String>,
+        node: &Handle,
+        mut included: bool,
+    ) {
+        match node.data {
+            NodeData::Text { ref contents } if included => {
+                urls.append(&mut extract_links_from_plaintext(&contents.borrow()));
+            }
 
-                if elem_attr_is_link(attr.name.local.as_ref(), name.local.as_ref()) {
-                    urls.push(attr_value);
-                } else {
-                    urls.append(&mut extract_links_from_plaintext(&attr_value));
+            NodeData::Comment { ref contents } if included => {
+                urls.append(&mut extract_links_from_plaintext(contents));
+            }
+
+            NodeData::Element {
+                ref name,
+                ref attrs,
+                ..
+            } => {
+                let attrs = attrs.borrow();
+                let element = name.local.as_ref();
+                if let Some(skip) = &self.skip {
+                    if skip.matches(element, &attrs) {
+                        return;
+                    }
+                }
+                if let Some(include) = &self.include {
+                    included = included || include.matches(element, &attrs);
+                }
+                let nofollow = self.skip_nofollow
+                    && attrs.iter().any(|attr| {
+                        attr.name.local.as_ref() == "rel"
+                            && attr
+                                .value
+                                .split_whitespace()
+                                .any(|rel| rel.eq_ignore_ascii_case("nofollow"))
+                    });
+                for attr in attrs.iter() {
+                    let attr_value = attr.value.to_string();
+
+                    if elem_attr_is_link(attr.name.local.as_ref(), element) {
+                        if element == "base" && attr.name.local.as_ref() == "href" && base.is_none()
+                        {
+                            *base = Some(attr_value.clone());
+                        }
+                        if included && !nofollow {
+                            urls.push(attr_value);
+                        }
+                    } else if included {
+                        urls.append(&mut extract_links_from_plaintext(&attr_value));
+                    }
                 }
             }
+
+            _ => {}
         }
 
-        _ => {}
+        // recursively traverse the document's nodes -- this doesn't need any extra
+        // exit conditions because the document is a tree
+        for child in node.children.borrow().iter() {
+            self.walk(urls, base, child, included);
+        }
     }
+}
 
-    // recursively traverse the document's nodes -- this doesn't need any extra
-    // exit conditions because the document is a tree
-    for child in node.children.borrow().iter() {
-        walk_html_links(&mut urls, child);
+impl Extractor for HtmlExtractor {
+    fn extract(&self, content: &[u8], _: FileType) -> Vec<RawLink> {
+        self.links(&String::from_utf8_lossy(content))
+            .into_iter()
+            .map(RawLink::new)
+            .collect()
     }
 }
 
+/// Resolve a relative link against the `href` of a `<base>` element.
+/// Returns `None` for links which are absolute already.
+/// A relative base (e.g. `/docs/`) gives a root-relative link,
+/// which is resolved against the base URL of the input later on.
+fn resolve_base(base: &str, link: &str) -> Option<String> {
+    if Url::parse(link).is_ok() {
+        return None;
+    }
+    if let Ok(base) = Url::parse(base) {
+        return base.join(link).ok().map(String::from);
+    }
+    let resolved = Url::parse("http://base.invalid/")
+        .ok()?
+        .join(base)
+        .ok()?
+        .join(link)
+        .ok()?;
+    Some(resolved[url::Position::BeforePath..].to_owned())
+}
+
+/// Extract unparsed URL strings from a HTML string with the default settings
+fn extract_links_from_html(input: &str) -> Vec<String> {
+    HtmlExtractor::default().links(input)
+}
+
 /// Determine if element's attribute contains a link / URL.
 fn elem_attr_is_link(attr_name: &str, elem_name: &str) -> bool {
     // See a comprehensive list of attributes that might contain URLs/URIs
@@ -137,37 +650,173 @@ fn elem_attr_is_link(attr_name: &str, elem_name: &str) -> bool {
 /// Extract unparsed URL strings from a plaintext.
 fn extract_links_from_plaintext(input: &str) -> Vec<String> {
     find_links(input)
-        .iter()
-        .map(|l| String::from(l.as_str()))
+        .into_iter()
+        .map(|range| input[range].to_owned())
         .collect()
 }
 
-pub(crate) fn extract_links(
-    input_content: &InputContent,
-    base_url: &Option<Url>,
-) -> HashSet<Request> {
+/// Byte offsets of the beginnings of all lines
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Find the locations of a link in the content it was extracted from.
+/// Occurrences which are only the beginning of a longer URL are skipped.
+/// Links which don't appear verbatim (e.g. because of HTML entities) have no spans.
+fn find_spans(content: &str, line_starts: &[usize], link: &str) -> Vec<Span> {
+    content
+        .match_indices(link)
+        .map(|(start, _)| start)
+        .filter(|start| !continues_url(&content[start + link.len()..]))
+        .map(|start| span(content, line_starts, start..start + link.len()))
+        .collect()
+}
+
+/// The span of a byte range of the content, with its line and column
+fn span(content: &str, line_starts: &[usize], range: Range<usize>) -> Span {
+    let line = line_starts.partition_point(|&line_start| line_start <= range.start);
+    Span {
+        start: range.start,
+        end: range.end,
+        line,
+        column: content[line_starts[line - 1]..range.start].chars().count() + 1,
+    }
+}
+
+/// Whether the text following a link is still part of a URL
+fn continues_url(rest: &str) -> bool {
+    let mut chars = rest.chars();
+    match chars.next() {
+        Some(c) if c.is_alphanumeric() || "/-_~%?#&=+@".contains(c) => true,
+        // Punctuation ends a link, unless more of the URL follows
+        Some(c) if ".,:;!".contains(c) => chars.next().map_or(false, char::is_alphanumeric),
+        _ => false,
+    }
+}
+
+/// Extract all links from the content of an input, according to its file type.
+/// Relative links are prefixed with `base_url`, or skipped without it.
+///
+/// This is what the [`Collector`](crate::Collector) does for every input.
+/// Use it directly for content from other sources, e.g. a stream:
+///
+/// ```
+/// use lychee_lib::{extract::{extract_links, FileType}, InputContent};
+///
+/// let content = InputContent::from_string("[docs](https://example.org/docs)", FileType::Markdown);
+/// let links = extract_links(&content, &None);
+/// assert_eq!(links.len(), 1);
+/// ```
+#[must_use]
+pub fn extract_links(input_content: &InputContent, base_url: &Option<Url>) -> HashSet<Request> {
+    extract_requests(input_content, base_url, None, false)
+}
+
+/// The links of an input, found by the built-in extractor for its file type
+fn builtin_links(input_content: &InputContent) -> Vec<RawLink> {
     let links = match input_content.file_type {
         FileType::Markdown => extract_links_from_markdown(&input_content.content),
+        FileType::Mdx => MarkdownExtractor::default().links(&input_content.content, FileType::Mdx),
         FileType::Html => extract_links_from_html(&input_content.content),
         FileType::Plaintext => extract_links_from_plaintext(&input_content.content),
+        FileType::Gettext => extract_links_from_gettext(&input_content.content),
+        FileType::Xliff => extract_links_from_xliff(&input_content.content),
+        FileType::Json => extract_links_from_json(&input_content.content),
+        FileType::Yaml => extract_links_from_yaml(&input_content.content),
     };
+    links.into_iter().map(RawLink::new).collect()
+}
+
+/// Extract all links from the content of an input with a custom [`Extractor`].
+/// Relative links are prefixed with `base_url`, or skipped without it.
+#[must_use]
+pub fn extract_links_with(
+    extractor: &dyn Extractor,
+    input_content: &InputContent,
+    base_url: &Option<Url>,
+) -> HashSet<Request> {
+    extract_requests(input_content, base_url, Some(extractor), false)
+}
+
+/// Extract the links of an input with `extractor`, or the built-in one for
+/// its file type. With `lenient`, invalid characters in links are
+/// percent-encoded with a warning (see [`Uri::parse_lenient`]).
+pub(crate) fn extract_requests(
+    input_content: &InputContent,
+    base_url: &Option<Url>,
+    extractor: Option<&dyn Extractor>,
+    lenient: bool,
+) -> HashSet<Request> {
+    let links = match extractor {
+        Some(extractor) => {
+            extractor.extract(input_content.content.as_bytes(), input_content.file_type)
+        }
+        None => builtin_links(input_content),
+    };
+    requests(input_content, base_url, links, lenient)
+}
 
+/// Turn the raw links of an input into requests
+fn requests(
+    input_content: &InputContent,
+    base_url: &Option<Url>,
+    links: impl IntoIterator<Item = RawLink>,
+    lenient: bool,
+) -> HashSet<Request> {
     // Only keep legit URLs. This sorts out things like anchors.
     // Silently ignore the parse failures for now.
-    let mut requests: HashSet<Request> = HashSet::new();
-    for link in links {
-        if let Ok(uri) = Uri::try_from(link.as_str()) {
-            requests.insert(Request::new(uri, input_content.input.clone()));
+    // Different spellings of a link (e.g. with and without a trailing slash)
+    // end up in the same request, with the spans of all of them.
+    let content = &input_content.content;
+    let mut requests: HashMap<Uri, Request> = HashMap::new();
+    let mut seen = HashSet::new();
+    let line_starts = line_starts(content);
+    for RawLink { text: link, range } in links {
+        // Links without a location are searched for, which finds all of their spans at once
+        if range.is_none() && !seen.insert(link.clone()) {
+            continue;
+        }
+        let parsed = if lenient {
+            Uri::parse_lenient(&link)
+        } else {
+            Uri::try_from(link.as_str()).map(|uri| (uri, None))
+        };
+        if let Ok((uri, warning)) = parsed {
+            let spans = match range {
+                Some(range) if content.get(range.clone()).is_some() => {
+                    vec![span(content, &line_starts, range)]
+                }
+                Some(_) => Vec::new(),
+                None => find_spans(content, &line_starts, &link),
+            };
+            let request = requests
+                .entry(uri.clone())
+                .or_insert_with(|| Request::new(uri, input_content.input.clone()));
+            request.spans.extend(spans);
+            if let Some(warning) = warning {
+                if !request.warnings.contains(&warning) {
+                    request.warnings.push(warning);
+                }
+            }
         } else if !Path::new(&link).exists() {
             if let Some(new_url) = base_url.as_ref().and_then(|u| u.join(&link).ok()) {
-                requests.insert(Request::new(
-                    Uri { url: new_url },
-                    input_content.input.clone(),
-                ));
+                let uri = Uri { url: new_url };
+                requests
+                    .entry(uri.clone())
+                    .or_insert_with(|| Request::new(uri, input_content.input.clone()));
             }
         };
     }
     requests
+        .into_iter()
+        .map(|(_, mut request)| {
+            request.spans.sort_unstable();
+            request.spans.dedup();
+            request
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -175,6 +824,7 @@ mod test {
     use std::{
         array,
         collections::HashSet,
+        convert::TryFrom,
         fs::File,
         io::{BufReader, Read},
         path::Path,
@@ -185,12 +835,13 @@ mod test {
 
     use super::{
         extract_links, extract_links_from_html, extract_links_from_markdown,
-        extract_links_from_plaintext, find_links, FileType,
+        extract_links_from_plaintext, find_links, find_spans, line_starts, Extractor, FileType,
+        HtmlExtractor, MarkdownExtractor, PlaintextExtractor, Selector,
     };
     use crate::{
         collector::InputContent,
         test_utils::{mail, website},
-        Uri,
+        Span, Uri,
     };
 
     fn load_fixture(filename: &str) -> String {
@@ -229,6 +880,10 @@ mod test {
         assert_eq!(FileType::from("test.markdown"), FileType::Markdown);
         assert_eq!(FileType::from("test.html"), FileType::Html);
         assert_eq!(FileType::from("test.txt"), FileType::Plaintext);
+        assert_eq!(FileType::from("locale/de.po"), FileType::Gettext);
+        assert_eq!(FileType::from("messages.xlf"), FileType::Xliff);
+        assert_eq!(FileType::from("i18n/de.json"), FileType::Json);
+        assert_eq!(FileType::from("config/locales/de.yml"), FileType::Yaml);
         assert_eq!(FileType::from("test.something"), FileType::Plaintext);
         assert_eq!(
             FileType::from("/absolute/path/to/test.something"),
@@ -351,7 +1006,7 @@ mod test {
         let links = find_links(input);
         let expected = "http://msdn.microsoft.com/library/ie/ms535874(v=vs.85).aspx)";
 
-        matches!(&links[..], [link] if link.as_str() == expected);
+        matches!(&links[..], [link] if &input[link.clone()] == expected);
     }
 
     #[test]
@@ -450,6 +1105,77 @@ mod test {
         assert_eq!(links, expected_links);
     }
 
+    #[test]
+    fn test_extract_html_base_href() {
+        let input = r#"<head><base href="https://example.org/docs/"></head>
+            <a href="intro">Intro</a> <a href="/about">About</a> <a href="https://example.com">Other</a>"#;
+        let links = extract_uris(input, FileType::Html, Some("https://staging.example.org"));
+
+        let expected_links = array::IntoIter::new([
+            website("https://example.org/docs/"),
+            website("https://example.org/docs/intro"),
+            website("https://example.org/about"),
+            website("https://example.com/"),
+        ])
+        .collect::<HashSet<Uri>>();
+        assert_eq!(links, expected_links);
+
+        // A relative base is resolved against the base URL
+        let input = r#"<base href="/v2/"><a href="guide/setup">Setup</a>"#;
+        let links = extract_uris(input, FileType::Html, Some("https://example.org"));
+
+        let expected_links = array::IntoIter::new([
+            website("https://example.org/v2/"),
+            website("https://example.org/v2/guide/setup"),
+        ])
+        .collect::<HashSet<Uri>>();
+        assert_eq!(links, expected_links);
+    }
+
+    #[test]
+    fn test_html_extractor() {
+        let input = r#"<a href="https://example.org/ad" rel="Sponsored NoFollow">Ad</a>
+            <div id="examples"><a href="https://example.org/example">Example</a>
+            See https://example.org/text</div>
+            <a href="https://example.org/docs" title="https://example.org/title">Docs</a>"#;
+        let extract = |extractor: HtmlExtractor| -> Vec<String> {
+            extractor
+                .extract(input.as_bytes(), FileType::Html)
+                .into_iter()
+                .map(|link| link.text)
+                .collect()
+        };
+
+        assert_eq!(extract(HtmlExtractor::default()).len(), 5);
+        assert_eq!(
+            extract(HtmlExtractor {
+                skip_nofollow: true,
+                skip: Some(Selector::try_from("div#examples").unwrap()),
+                ..HtmlExtractor::default()
+            }),
+            vec!["https://example.org/docs", "https://example.org/title"]
+        );
+        assert_eq!(
+            extract(HtmlExtractor {
+                include: Some(Selector::try_from("#examples").unwrap()),
+                ..HtmlExtractor::default()
+            }),
+            vec!["https://example.org/example", "https://example.org/text"]
+        );
+        assert_eq!(
+            extract(HtmlExtractor {
+                include: Some(Selector::try_from("div, a[title]").unwrap()),
+                skip: Some(Selector::try_from("//a[@href='https://example.org/example']").unwrap()),
+                ..HtmlExtractor::default()
+            }),
+            vec![
+                "https://example.org/text",
+                "https://example.org/docs",
+                "https://example.org/title"
+            ]
+        );
+    }
+
     #[test]
     fn test_extract_urls_with_at_sign_properly() {
         // note that these used to parse as emails
@@ -464,4 +1190,292 @@ mod test {
 
         assert_eq!(links, expected_links);
     }
+
+    #[test]
+    fn test_find_spans() {
+        let content =
+            "See https://example.com.\nÄlso https://example.com/docs and https://example.com";
+        let link = "https://example.com";
+        assert_eq!(
+            find_spans(content, &line_starts(content), link),
+            vec![
+                Span {
+                    start: 4,
+                    end: 23,
+                    line: 1,
+                    column: 5
+                },
+                // Columns count characters, not bytes
+                Span {
+                    start: 60,
+                    end: 79,
+                    line: 2,
+                    column: 35
+                }
+            ]
+        );
+        let content = "https://example.com.au";
+        assert!(find_spans(content, &line_starts(content), link).is_empty());
+    }
+
+    #[test]
+    fn test_extract_spans() {
+        let input = "[a](http://example.com/) and [b](http://example.com)";
+        let requests = extract_links(&InputContent::from_string(input, FileType::Markdown), &None);
+        let request = requests.into_iter().next().unwrap();
+
+        // Both spellings are the same URI, so the spans of both are merged
+        let positions: Vec<_> = request.spans.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(positions, vec![(4, 23), (33, 51)]);
+    }
+
+    #[test]
+    fn test_markdown_extractor() {
+        let input = "\
+See https://example.org/text and `https://example.org/inline`
+
+```sh
+curl https://example.org/fenced
+```
+
+    wget https://example.org/indented
+
+> Quoted [link](https://example.org/quoted)
+";
+        let extract = |extractor: MarkdownExtractor| -> HashSet<String> {
+            extractor
+                .extract(input.as_bytes(), FileType::Markdown)
+                .into_iter()
+                .map(|link| link.text)
+                .collect()
+        };
+        let links = |links: &[&str]| -> HashSet<String> {
+            links.iter().map(|link| (*link).to_owned()).collect()
+        };
+
+        let default = MarkdownExtractor::default();
+        assert_eq!(
+            extract(default),
+            links(&["https://example.org/text", "https://example.org/quoted"])
+        );
+        assert_eq!(
+            extract(MarkdownExtractor {
+                skip_code_blocks: false,
+                skip_inline_code: false,
+                skip_blockquotes: true,
+                frontmatter_keys: Vec::new(),
+            }),
+            links(&[
+                "https://example.org/text",
+                "https://example.org/inline",
+                "https://example.org/fenced",
+                "https://example.org/indented",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_mdx() {
+        let input = r#"---
+title: Docs https://example.org/title
+---
+import Tabs from '@theme/Tabs';
+export const site = 'https://example.org/export';
+
+<Tabs href={site} data-source="https://example.org/jsx">
+
+See [the guide](https://example.org/guide).
+
+</Tabs>
+
+```js
+import x from 'https://example.org/code';
+```
+"#;
+        let links: HashSet<Uri> = extract_uris(input, FileType::Mdx, None);
+        let expected = array::IntoIter::new([
+            website("https://example.org/jsx"),
+            website("https://example.org/guide"),
+        ])
+        .collect::<HashSet<Uri>>();
+        assert_eq!(links, expected);
+        assert_eq!(FileType::from("docs/intro.mdx"), FileType::Mdx);
+    }
+
+    #[test]
+    fn test_extract_multilingual_link_boundaries() {
+        for (text, link) in &[
+            (
+                "详情见https://example.com/文档。下一句",
+                "https://example.com/文档",
+            ),
+            ("参见（https://example.com/zh）。", "https://example.com/zh"),
+            (
+                "https://ja.wikipedia.org/wiki/東京、大阪",
+                "https://ja.wikipedia.org/wiki/東京",
+            ),
+            (
+                "「https://example.jp/page」を参照",
+                "https://example.jp/page",
+            ),
+            (
+                "Read “https://example.org/quotes.” now",
+                "https://example.org/quotes",
+            ),
+            (
+                "It’s at ‘https://example.org/single’",
+                "https://example.org/single",
+            ),
+            ("Voir «https://example.fr/page».", "https://example.fr/page"),
+            (
+                "Siehe https://example.de/seite… oder",
+                "https://example.de/seite",
+            ),
+            (
+                "Party 🎉https://example.com/party🎉!",
+                "https://example.com/party",
+            ),
+            (
+                "See https://en.wikipedia.org/wiki/Rust_(language)—a language",
+                "https://en.wikipedia.org/wiki/Rust_(language)",
+            ),
+            (
+                "Café: https://example.com/café/über",
+                "https://example.com/café/über",
+            ),
+            (
+                "Подробнее: https://example.ru/страница.",
+                "https://example.ru/страница",
+            ),
+        ] {
+            assert_eq!(extract_links_from_plaintext(text), [*link], "{}", text);
+        }
+        assert!(extract_links_from_plaintext("https://“example").is_empty());
+
+        let bare = PlaintextExtractor {
+            bare_domains: true,
+            ..PlaintextExtractor::default()
+        };
+        let links = bare.extract(
+            "见www.example.com/文档，谢谢".as_bytes(),
+            FileType::Plaintext,
+        );
+        assert_eq!(links[0].text, "https://www.example.com/文档");
+    }
+
+    #[test]
+    fn test_plaintext_extractor() {
+        let extract = |extractor: PlaintextExtractor, text: &str| -> Vec<String> {
+            extractor
+                .extract(text.as_bytes(), FileType::Plaintext)
+                .into_iter()
+                .map(|link| link.text)
+                .collect()
+        };
+        let default = PlaintextExtractor::default();
+        assert_eq!(
+            extract(default, "See https://example.org/a. Or www.example.org"),
+            ["https://example.org/a"]
+        );
+
+        let untrimmed = PlaintextExtractor {
+            trim_punctuation: false,
+            ..default
+        };
+        assert_eq!(
+            extract(untrimmed, "See https://example.org/a.!"),
+            ["https://example.org/a.!"]
+        );
+
+        let bare = PlaintextExtractor {
+            bare_domains: true,
+            ..default
+        };
+        assert_eq!(
+            extract(
+                bare,
+                "(see www.example.org/a_(b)), https://www.example.com, me@www.example.net, www.local"
+            ),
+            [
+                "https://www.example.com",
+                "me@www.example.net",
+                "https://www.example.org/a_(b)"
+            ]
+        );
+        assert!(extract(bare, "see www.").is_empty());
+        assert!(extract(bare, "see www.)").is_empty());
+
+        let brackets = PlaintextExtractor {
+            angle_brackets: true,
+            ..default
+        };
+        assert_eq!(
+            extract(
+                brackets,
+                "<https://example.org/a.> <mailto:me@example.org> <https://example.org/b c>"
+            ),
+            [
+                "https://example.org/a.",
+                "mailto:me@example.org",
+                "https://example.org/b"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_translation_links() {
+        let cases = [
+            (
+                "TEST_I18N.po",
+                FileType::Gettext,
+                vec![
+                    "https://example.org/docs",
+                    "https://example.org/de/docs",
+                    "https://example.org/guide",
+                    "https://example.org/de/guide",
+                ],
+            ),
+            (
+                "TEST_I18N.xliff",
+                FileType::Xliff,
+                vec![
+                    "https://example.org/docs?lang=en&v=2",
+                    "https://example.org/docs?lang=de&v=2",
+                    "https://example.org/guide",
+                    "https://example.org/de/guide",
+                ],
+            ),
+            (
+                "TEST_I18N.json",
+                FileType::Json,
+                vec![
+                    "https://example.org/de/docs",
+                    "https://example.org/de/guide",
+                ],
+            ),
+            (
+                "TEST_I18N.yml",
+                FileType::Yaml,
+                vec![
+                    "https://example.org/de/docs",
+                    "https://example.org/de/guide",
+                    "https://example.org/de/blog",
+                ],
+            ),
+        ];
+        for (fixture, file_type, expected) in array::IntoIter::new(cases) {
+            let links = extract_uris(&load_fixture(fixture), file_type, None);
+            let expected = expected.into_iter().map(website).collect::<HashSet<Uri>>();
+            assert_eq!(links, expected, "{}", fixture);
+        }
+    }
+
+    #[test]
+    fn test_extract_invalid_json_as_plaintext() {
+        let links = extract_uris("{ see: https://example.org }", FileType::Json, None);
+        assert_eq!(
+            links,
+            array::IntoIter::new([website("https://example.org")]).collect()
+        );
+    }
 }
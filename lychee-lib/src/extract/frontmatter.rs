@@ -0,0 +1,101 @@
+use serde_json::Value;
+
+/// Split the front matter off a Markdown document: YAML between `---` lines
+/// or TOML between `+++` lines at the very start. Returns the parsed front
+/// matter (if it is valid) and the rest of the document.
+pub(super) fn split_frontmatter(input: &str) -> (Option<Value>, &str) {
+    let document = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut lines = document.split_inclusive('\n');
+    let delimiter = match lines.next().map(str::trim_end) {
+        Some(delimiter @ "---") | Some(delimiter @ "+++") => delimiter,
+        _ => return (None, input),
+    };
+    let start = document.len() - lines.as_str().len();
+    let mut end = start;
+    for line in lines {
+        if line.trim_end() == delimiter {
+            let frontmatter = &document[start..end];
+            let value = if delimiter == "---" {
+                serde_yaml::from_str(frontmatter).ok()
+            } else {
+                toml::from_str(frontmatter).ok()
+            };
+            return (value, &document[end + line.len()..]);
+        }
+        end += line.len();
+    }
+    // Without a closing delimiter, this is a thematic break
+    (None, input)
+}
+
+/// The values of the front matter at the given keys. Nested keys are
+/// separated by dots (e.g. `social.image`). Arrays contribute all of their
+/// strings.
+pub(super) fn frontmatter_links(frontmatter: &Value, keys: &[String]) -> Vec<String> {
+    keys.iter()
+        .filter_map(|key| {
+            key.split('.')
+                .try_fold(frontmatter, |value, part| value.get(part))
+        })
+        .flat_map(|value| match value {
+            Value::String(link) => vec![link.trim().to_owned()],
+            Value::Array(values) => values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|link| link.trim().to_owned())
+                .collect(),
+            _ => Vec::new(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{frontmatter_links, split_frontmatter};
+
+    #[test]
+    fn test_yaml() {
+        let (frontmatter, body) = split_frontmatter(
+            "---\ntitle: See https://example.org/title\nimage: /img/cover.png\n\
+             social:\n  links: [\"https://example.org/a\", \"https://example.org/b\"]\n---\n# Body\n",
+        );
+        let frontmatter = frontmatter.unwrap();
+        assert_eq!(body, "# Body\n");
+        assert_eq!(
+            frontmatter_links(
+                &frontmatter,
+                &[
+                    "image".to_owned(),
+                    "social.links".to_owned(),
+                    "missing".to_owned()
+                ]
+            ),
+            vec![
+                "/img/cover.png",
+                "https://example.org/a",
+                "https://example.org/b"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toml() {
+        let (frontmatter, body) =
+            split_frontmatter("+++\ncanonical = \"https://example.org/\"\n+++\nBody");
+        assert_eq!(
+            frontmatter,
+            Some(json!({ "canonical": "https://example.org/" }))
+        );
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        let input = "Intro\n---\nNot front matter\n---\n";
+        assert_eq!(split_frontmatter(input), (None, input));
+        let input = "---\nNever closed";
+        assert_eq!(split_frontmatter(input), (None, input));
+    }
+}
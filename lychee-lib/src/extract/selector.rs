@@ -0,0 +1,240 @@
+use std::{convert::TryFrom, fmt};
+
+use html5ever::Attribute;
+
+use crate::ErrorKind;
+
+/// A list of simple CSS selectors, e.g. `.no-check, a[rel=external], #toc`.
+/// Each selector is an optional element name followed by any number of
+/// classes, IDs and attributes. Combinators (e.g. `nav a`) and
+/// pseudo-classes are not supported.
+///
+/// XPath expressions with a single descendant step are supported as well,
+/// e.g. `//nav | //div[@class='sidebar']`. Attributes compare the whole value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    source: String,
+    compounds: Vec<Compound>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Compound {
+    element: Option<String>,
+    classes: Vec<String>,
+    ids: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+impl Selector {
+    /// Whether an element with the given name and attributes matches
+    /// any of the selectors
+    pub(super) fn matches(&self, element: &str, attrs: &[Attribute]) -> bool {
+        let attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|attr| attr.name.local.as_ref() == name)
+                .map(|attr| attr.value.as_ref())
+        };
+        let has_class = |class: &String| {
+            attr("class").map_or(false, |classes| {
+                classes.split_whitespace().any(|other| other == class)
+            })
+        };
+        self.compounds.iter().any(|compound| {
+            compound
+                .element
+                .as_ref()
+                .map_or(true, |name| name.eq_ignore_ascii_case(element))
+                && compound.classes.iter().all(has_class)
+                && compound
+                    .ids
+                    .iter()
+                    .all(|id| attr("id") == Some(id.as_str()))
+                && compound
+                    .attributes
+                    .iter()
+                    .all(|(name, value)| match (attr(name), value) {
+                        (Some(actual), Some(value)) => actual == value.as_str(),
+                        (actual, None) => actual.is_some(),
+                        (None, Some(_)) => false,
+                    })
+        })
+    }
+}
+
+impl TryFrom<&str> for Selector {
+    type Error = ErrorKind;
+
+    fn try_from(source: &str) -> Result<Self, Self::Error> {
+        let invalid = || ErrorKind::InvalidSelector(source.to_owned());
+        let compounds = if source.trim_start().starts_with("//") {
+            source
+                .split('|')
+                .map(|step| parse_xpath(step.trim()).ok_or_else(invalid))
+                .collect::<Result<_, _>>()?
+        } else {
+            source
+                .split(',')
+                .map(|compound| parse_compound(compound.trim()).ok_or_else(invalid))
+                .collect::<Result<_, _>>()?
+        };
+        Ok(Self {
+            source: source.to_owned(),
+            compounds,
+        })
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Whether `c` can be part of a name, e.g. of an element or a class
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Split a name off the start of `input`
+fn name(input: &str) -> Option<(&str, &str)> {
+    let end = input.find(|c| !is_name_char(c)).unwrap_or(input.len());
+    (end > 0).then(|| input.split_at(end))
+}
+
+fn parse_compound(input: &str) -> Option<Compound> {
+    let mut compound = Compound::default();
+    let mut rest = input;
+    if let Some(tail) = rest.strip_prefix('*') {
+        rest = tail;
+    } else if let Some((element, tail)) = name(rest) {
+        compound.element = Some(element.to_owned());
+        rest = tail;
+    }
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix('.') {
+            let (class, tail) = name(tail)?;
+            compound.classes.push(class.to_owned());
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('#') {
+            let (id, tail) = name(tail)?;
+            compound.ids.push(id.to_owned());
+            rest = tail;
+        } else if let Some(tail) = rest.strip_prefix('[') {
+            let (attribute, tail) = tail.split_once(']')?;
+            compound.attributes.push(parse_attribute(attribute.trim())?);
+            rest = tail;
+        } else {
+            return None;
+        }
+    }
+    (compound != Compound::default() || input == "*").then(|| compound)
+}
+
+/// A descendant step of an XPath expression with attribute predicates,
+/// e.g. `//div[@class='sidebar']` or `//*[@data-skip]`
+fn parse_xpath(input: &str) -> Option<Compound> {
+    let mut compound = Compound::default();
+    let rest = input.strip_prefix("//")?;
+    let mut rest = match rest.strip_prefix('*') {
+        Some(tail) => tail,
+        None => {
+            let (element, tail) = name(rest)?;
+            compound.element = Some(element.to_owned());
+            tail
+        }
+    };
+    while !rest.is_empty() {
+        let (predicate, tail) = rest.strip_prefix('[')?.split_once(']')?;
+        let attribute = predicate.trim().strip_prefix('@')?;
+        compound.attributes.push(parse_attribute(attribute)?);
+        rest = tail.trim_start();
+    }
+    Some(compound)
+}
+
+/// An attribute selector without its brackets, e.g. `data-skip` or `rel="nofollow"`
+fn parse_attribute(input: &str) -> Option<(String, Option<String>)> {
+    let (attribute, value) = match input.split_once('=') {
+        Some((attribute, value)) => {
+            let value = value.trim();
+            let unquoted = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value);
+            (attribute.trim(), Some(unquoted.to_owned()))
+        }
+        None => (input, None),
+    };
+    let (name, rest) = name(attribute)?;
+    rest.is_empty().then(|| (name.to_owned(), value))
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+
+    use html5ever::{ns, tendril::StrTendril, Attribute, LocalName, QualName};
+
+    use super::Selector;
+    use crate::ErrorKind;
+
+    fn attrs(attrs: &[(&str, &str)]) -> Vec<Attribute> {
+        attrs
+            .iter()
+            .map(|(name, value)| Attribute {
+                name: QualName::new(None, ns!(), LocalName::from(*name)),
+                value: StrTendril::from(*value),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_matches() {
+        let selector =
+            Selector::try_from(".no-check, nav#toc, a[data-skip], [rel='external']").unwrap();
+        assert!(selector.matches("a", &attrs(&[("class", "link no-check")])));
+        assert!(selector.matches("nav", &attrs(&[("id", "toc")])));
+        assert!(!selector.matches("div", &attrs(&[("id", "toc")])));
+        assert!(selector.matches("a", &attrs(&[("data-skip", "")])));
+        assert!(!selector.matches("img", &attrs(&[("data-skip", "")])));
+        assert!(selector.matches("link", &attrs(&[("rel", "external")])));
+        assert!(!selector.matches("a", &attrs(&[("class", "no-checks")])));
+    }
+
+    #[test]
+    fn test_xpath() {
+        let selector =
+            Selector::try_from("//footer | //div[@class='sidebar'] | //*[@data-skip]").unwrap();
+        assert!(selector.matches("footer", &attrs(&[])));
+        assert!(selector.matches("div", &attrs(&[("class", "sidebar")])));
+        assert!(!selector.matches("div", &attrs(&[("class", "sidebar wide")])));
+        assert!(selector.matches("section", &attrs(&[("data-skip", "true")])));
+        assert!(!selector.matches("nav", &attrs(&[])));
+    }
+
+    #[test]
+    fn test_invalid() {
+        for selector in &[
+            "",
+            "nav a",
+            "a:hover",
+            ".",
+            "a,",
+            "[data skip]",
+            "//nav/a",
+            "//div[1]",
+            "//",
+        ] {
+            assert_eq!(
+                Selector::try_from(*selector),
+                Err(ErrorKind::InvalidSelector((*selector).to_owned()))
+            );
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use html5ever::tendril::{StrTendril, TendrilSink};
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use xml5ever::driver::{parse_document, XmlParseOpts};
+
+use super::{extract_links_from_html, extract_links_from_plaintext};
+
+/// Extract unparsed URL strings from a translated string.
+/// Translations often contain HTML markup, so they are parsed as HTML if they
+/// look like it.
+fn extract_links_from_string(input: &str) -> Vec<String> {
+    if input.contains('<') {
+        extract_links_from_html(input)
+    } else {
+        extract_links_from_plaintext(input)
+    }
+}
+
+/// Extract unparsed URL strings from the messages of a gettext PO file.
+/// Comments, e.g. references to the source code, are skipped.
+pub(super) fn extract_links_from_gettext(input: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    // A message may continue with quoted strings on the following lines
+    let mut message: Option<String> = None;
+    for line in input.lines() {
+        let line = line.trim();
+        if line.starts_with('"') {
+            if let Some(message) = &mut message {
+                message.push_str(&unquote(line));
+            }
+            continue;
+        }
+        messages.extend(message.take());
+        if line.starts_with("msg") {
+            message = line.find('"').map(|start| unquote(&line[start..]));
+        }
+    }
+    messages.extend(message);
+    messages
+        .iter()
+        .flat_map(|message| extract_links_from_string(message))
+        .collect()
+}
+
+/// The value of a quoted string of a PO file, with its escape sequences resolved
+fn unquote(quoted: &str) -> String {
+    let inner = quoted.strip_prefix('"').unwrap_or(quoted);
+    let inner = inner.strip_suffix('"').unwrap_or(inner);
+    let mut value = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some(escaped) => value.push(escaped),
+            None => {}
+        }
+    }
+    value
+}
+
+/// Extract unparsed URL strings from the `source` and `target` elements
+/// of an XLIFF file
+pub(super) fn extract_links_from_xliff(input: &str) -> Vec<String> {
+    let tendril = StrTendril::from(input);
+    let rc_dom = parse_document(RcDom::default(), XmlParseOpts::default()).one(tendril);
+
+    let mut urls = Vec::new();
+    walk_xliff_links(&mut urls, &rc_dom.document);
+    urls
+}
+
+/// Recursively walk the translation units of an XLIFF document
+fn walk_xliff_links(urls: &mut Vec<String>, node: &Handle) {
+    if let NodeData::Element { ref name, .. } = node.data {
+        if matches!(name.local.as_ref(), "source" | "target") {
+            // Inline elements (e.g. `<g>`) are part of the translated string
+            let mut text = String::new();
+            text_content(&mut text, node);
+            urls.append(&mut extract_links_from_string(&text));
+            return;
+        }
+    }
+    for child in node.children.borrow().iter() {
+        walk_xliff_links(urls, child);
+    }
+}
+
+/// Append the text of a node and all of its descendants
+fn text_content(text: &mut String, node: &Handle) {
+    if let NodeData::Text { ref contents } = node.data {
+        text.push_str(&contents.borrow());
+    }
+    for child in node.children.borrow().iter() {
+        text_content(text, child);
+    }
+}
+
+/// Extract unparsed URL strings from the string values of a JSON file.
+/// Keys are skipped. Files which aren't valid JSON are searched as plaintext.
+pub(super) fn extract_links_from_json(input: &str) -> Vec<String> {
+    match serde_json::from_str(input) {
+        Ok(value) => {
+            let mut urls = Vec::new();
+            walk_json_links(&mut urls, &value);
+            urls
+        }
+        Err(_) => extract_links_from_plaintext(input),
+    }
+}
+
+fn walk_json_links(urls: &mut Vec<String>, value: &serde_json::Value) {
+    match value {
+        serde_json::Value::String(string) => urls.append(&mut extract_links_from_string(string)),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                walk_json_links(urls, value);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values() {
+                walk_json_links(urls, value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Extract unparsed URL strings from the string values of a YAML file.
+/// Keys are skipped. Files which aren't valid YAML are searched as plaintext.
+pub(super) fn extract_links_from_yaml(input: &str) -> Vec<String> {
+    match serde_yaml::from_str(input) {
+        Ok(value) => {
+            let mut urls = Vec::new();
+            walk_yaml_links(&mut urls, &value);
+            urls
+        }
+        Err(_) => extract_links_from_plaintext(input),
+    }
+}
+
+fn walk_yaml_links(urls: &mut Vec<String>, value: &serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::String(string) => urls.append(&mut extract_links_from_string(string)),
+        serde_yaml::Value::Sequence(values) => {
+            for value in values {
+                walk_yaml_links(urls, value);
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for (_, value) in map.iter() {
+                walk_yaml_links(urls, value);
+            }
+        }
+        _ => {}
+    }
+}
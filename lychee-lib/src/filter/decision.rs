@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+/// The rule which decided whether a URI gets checked,
+/// as reported by [`Filter::explain`](super::Filter::explain)
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Decision {
+    /// No rule applies, so the URI is checked
+    Checked,
+    /// The URI matches an include pattern, which takes precedence over excludes
+    Included {
+        /// Index of the pattern among the includes
+        index: usize,
+        /// The regular expression
+        pattern: String,
+    },
+    /// Mail addresses are excluded
+    MailExcluded,
+    /// The URI points to a private IP address, which are excluded
+    PrivateIpExcluded,
+    /// The URI points to a link-local IP address, which are excluded
+    LinkLocalIpExcluded,
+    /// The URI points to a loopback IP address, which are excluded
+    LoopbackIpExcluded,
+    /// The URI points to an IP address in an excluded network.
+    /// Contains the network.
+    NetworkExcluded(String),
+    /// The scheme of the URI is excluded
+    SchemeExcluded(String),
+    /// The scheme of the URI is not one of the allowed schemes
+    SchemeNotAllowed(String),
+    /// The host of the URI is a blocked host or one of its subdomains.
+    /// Contains the blocked host.
+    HostBlocked(String),
+    /// The host of the URI is not one of the allowed hosts
+    HostNotAllowed(String),
+    /// In intranet mode, the host of the URI doesn't resolve to an address of
+    /// a local network. Contains the host.
+    NotIntranet(String),
+    /// The registrable domain of the URI matches a blocked domain rule.
+    /// Contains the rule.
+    DomainBlocked(String),
+    /// The registrable domain of the URI doesn't match any allowed domain rule
+    DomainNotAllowed(String),
+    /// The URI contains a placeholder of a template (e.g. `{id}`),
+    /// so it can't be checked before the template is rendered
+    Template {
+        /// The placeholder pattern
+        pattern: String,
+    },
+    /// The URI is a well-known false positive, e.g. an XML namespace
+    FalsePositive,
+    /// There are include patterns, but none of them matches the URI
+    NotIncluded,
+    /// The URI matches an exclude pattern
+    Excluded {
+        /// Index of the pattern among the excludes
+        index: usize,
+        /// The regular expression
+        pattern: String,
+    },
+    /// The URI matches one of the exclude patterns which can be replaced at runtime
+    SharedExcluded {
+        /// Index of the pattern among the current shared excludes
+        index: usize,
+        /// The regular expression
+        pattern: String,
+    },
+}
+
+impl Decision {
+    /// Whether the URI is skipped
+    #[must_use]
+    pub const fn is_excluded(&self) -> bool {
+        !matches!(self, Self::Checked | Self::Included { .. })
+    }
+}
+
+impl Display for Decision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Checked => f.write_str("Checked, no rule applies"),
+            Self::Included { index, pattern } => {
+                write!(f, "Included by include pattern {}: {}", index, pattern)
+            }
+            Self::MailExcluded => f.write_str("Excluded, mail addresses are excluded"),
+            Self::PrivateIpExcluded => f.write_str("Excluded, private IP addresses are excluded"),
+            Self::LinkLocalIpExcluded => {
+                f.write_str("Excluded, link-local IP addresses are excluded")
+            }
+            Self::LoopbackIpExcluded => f.write_str("Excluded, loopback IP addresses are excluded"),
+            Self::NetworkExcluded(network) => {
+                write!(f, "Excluded, network {} is excluded", network)
+            }
+            Self::SchemeExcluded(scheme) => write!(f, "Excluded, scheme {} is excluded", scheme),
+            Self::SchemeNotAllowed(scheme) => {
+                write!(f, "Excluded, scheme {} is not allowed", scheme)
+            }
+            Self::HostBlocked(host) => write!(f, "Excluded, host {} is blocked", host),
+            Self::HostNotAllowed(host) => write!(f, "Excluded, host {} is not allowed", host),
+            Self::NotIntranet(host) => {
+                write!(f, "Excluded, host {} is not in a local network", host)
+            }
+            Self::DomainBlocked(rule) => write!(f, "Excluded, domain {} is blocked", rule),
+            Self::DomainNotAllowed(domain) => {
+                write!(f, "Excluded, domain {} is not allowed", domain)
+            }
+            Self::Template { pattern } => {
+                write!(
+                    f,
+                    "Excluded as a template, placeholder pattern: {}",
+                    pattern
+                )
+            }
+            Self::FalsePositive => f.write_str("Excluded as a known false positive"),
+            Self::NotIncluded => f.write_str("Excluded, no include pattern matches"),
+            Self::Excluded { index, pattern } => {
+                write!(f, "Excluded by exclude pattern {}: {}", index, pattern)
+            }
+            Self::SharedExcluded { index, pattern } => {
+                write!(
+                    f,
+                    "Excluded by shared exclude pattern {}: {}",
+                    index, pattern
+                )
+            }
+        }
+    }
+}
@@ -17,6 +17,14 @@ impl Includes {
         self.regex.is_match(input)
     }
 
+    #[must_use]
+    /// The index and the pattern of the first regular expression
+    /// matching the given input, if any
+    pub fn first_match(&self, input: &str) -> Option<(usize, String)> {
+        let index = self.regex.matches(input).into_iter().next()?;
+        Some((index, self.regex.patterns()[index].clone()))
+    }
+
     #[inline]
     #[must_use]
     /// Whether there were no regular expressions defined for inclusion
@@ -1,10 +1,18 @@
+mod decision;
 mod excludes;
 mod includes;
+mod networks;
+mod shared_excludes;
+mod templates;
 
 use std::{collections::HashSet, net::IpAddr};
 
+pub use decision::Decision;
 pub use excludes::Excludes;
 pub use includes::Includes;
+pub use networks::IpNetwork;
+pub use shared_excludes::SharedExcludes;
+pub use templates::{Templates, DEFAULT_TEMPLATE_PATTERNS};
 
 use crate::uri::Uri;
 
@@ -29,10 +37,13 @@ pub struct Filter {
     pub includes: Option<Includes>,
     /// URIs excluded from checking
     pub excludes: Option<Excludes>,
+    /// URIs excluded from checking, which can be replaced at runtime
+    pub shared_excludes: Option<SharedExcludes>,
     /// Only check URIs with the given schemes (e.g. `https` and `http`)
-    // TODO: includes scheme and excludes scheme
     // TODO: excludes_mail should be merged to excludes scheme
     pub schemes: HashSet<String>,
+    /// Don't check URIs with the given schemes (e.g. `http` or `mailto`)
+    pub exclude_schemes: HashSet<String>,
     /// Example: 192.168.0.1
     pub exclude_private_ips: bool,
     /// Example: 169.254.0.0
@@ -40,8 +51,32 @@ pub struct Filter {
     /// For IPv4: 127.0.0.1/8
     /// For IPv6: ::1/128
     pub exclude_loopback_ips: bool,
+    /// Don't check URIs with IP addresses in these networks
+    pub exclude_networks: Vec<IpNetwork>,
+    /// Check URIs with IP addresses in these networks, even if the IP address
+    /// excludes (including `exclude_networks`) apply to them.
+    /// Other URIs are not affected.
+    pub include_networks: Vec<IpNetwork>,
     /// Example: octocat@github.com
     pub exclude_mail: bool,
+    /// Only check URIs on these hosts (in lowercase), including their subdomains:
+    /// `example.com` matches `docs.example.com`, but not `notexample.com`.
+    /// URIs without a host, like mail addresses, are not affected.
+    pub allowed_hosts: HashSet<String>,
+    /// Don't check URIs on these hosts (in lowercase), including their subdomains
+    pub blocked_hosts: HashSet<String>,
+    /// Only check URIs whose registrable domain matches one of these rules
+    /// (in lowercase), according to the public suffix list:
+    /// `example.com` matches the registrable domain `example.com`, so all of
+    /// its subdomains, and `*.gov.cn` matches all registrable domains under
+    /// the public suffix `gov.cn`.
+    /// URIs without a domain, like mail addresses and IP addresses, are not affected.
+    pub allowed_domains: HashSet<String>,
+    /// Don't check URIs whose registrable domain matches one of these rules
+    /// (in lowercase), like for `allowed_domains`
+    pub blocked_domains: HashSet<String>,
+    /// Placeholders of templated URIs (e.g. `{id}`), which are excluded
+    pub templates: Option<Templates>,
 }
 
 impl Filter {
@@ -55,18 +90,37 @@ impl Filter {
     #[must_use]
     /// Whether IP addresses are excluded from checking
     pub fn is_ip_excluded(&self, uri: &Uri) -> bool {
-        match uri.host_ip() {
-            Some(ip_addr) if self.exclude_loopback_ips && ip_addr.is_loopback() => true,
+        self.ip_decision(uri).is_some()
+    }
+
+    fn ip_decision(&self, uri: &Uri) -> Option<Decision> {
+        self.ip_addr_decision(uri.host_ip()?)
+    }
+
+    /// Whether the given IP address, e.g. one a host resolves to, is excluded
+    pub(crate) fn ip_addr_decision(&self, ip_addr: IpAddr) -> Option<Decision> {
+        if self.include_networks.iter().any(|n| n.contains(ip_addr)) {
+            return None;
+        }
+        if let Some(network) = self.exclude_networks.iter().find(|n| n.contains(ip_addr)) {
+            return Some(Decision::NetworkExcluded(network.to_string()));
+        }
+        match ip_addr {
+            _ if self.exclude_loopback_ips && ip_addr.is_loopback() => {
+                Some(Decision::LoopbackIpExcluded)
+            }
             // Note: in a pathological case, an IPv6 address can be IPv4-mapped
             //       (IPv4 address embedded in a IPv6).  We purposefully
             //       don't deal with it here, and assume if an address is IPv6,
             //       we shouldn't attempt to map it to IPv4.
             //       See: https://tools.ietf.org/html/rfc4291#section-2.5.5.2
-            Some(IpAddr::V4(v4_addr)) if self.exclude_private_ips && v4_addr.is_private() => true,
-            Some(IpAddr::V4(v4_addr)) if self.exclude_link_local_ips && v4_addr.is_link_local() => {
-                true
+            IpAddr::V4(v4_addr) if self.exclude_private_ips && v4_addr.is_private() => {
+                Some(Decision::PrivateIpExcluded)
+            }
+            IpAddr::V4(v4_addr) if self.exclude_link_local_ips && v4_addr.is_link_local() => {
+                Some(Decision::LinkLocalIpExcluded)
             }
-            _ => false,
+            _ => None,
         }
     }
 
@@ -74,10 +128,83 @@ impl Filter {
     #[must_use]
     /// Whether the scheme of the given URI is excluded
     pub fn is_scheme_excluded(&self, uri: &Uri) -> bool {
-        if self.schemes.is_empty() {
-            return false;
+        self.scheme_decision(uri).is_some()
+    }
+
+    fn scheme_decision(&self, uri: &Uri) -> Option<Decision> {
+        let scheme = uri.scheme();
+        if self.exclude_schemes.contains(scheme) {
+            return Some(Decision::SchemeExcluded(scheme.to_owned()));
         }
-        !self.schemes.contains(uri.scheme())
+        if self.schemes.is_empty() || self.schemes.contains(scheme) {
+            return None;
+        }
+        Some(Decision::SchemeNotAllowed(scheme.to_owned()))
+    }
+
+    #[must_use]
+    /// Whether the host of the given URI is blocked, or not allowed
+    pub fn is_host_excluded(&self, uri: &Uri) -> bool {
+        self.host_decision(uri).is_some()
+    }
+
+    fn host_decision(&self, uri: &Uri) -> Option<Decision> {
+        if self.allowed_hosts.is_empty() && self.blocked_hosts.is_empty() {
+            return None;
+        }
+        let host = uri.url.host_str()?.trim_end_matches('.').to_lowercase();
+        if let Some(blocked) = self.blocked_hosts.iter().find(|h| is_subdomain(&host, h)) {
+            return Some(Decision::HostBlocked(blocked.clone()));
+        }
+        if !self.allowed_hosts.is_empty()
+            && !self.allowed_hosts.iter().any(|h| is_subdomain(&host, h))
+        {
+            return Some(Decision::HostNotAllowed(host));
+        }
+        None
+    }
+
+    #[must_use]
+    /// Whether the registrable domain of the given URI is blocked, or not allowed
+    pub fn is_domain_excluded(&self, uri: &Uri) -> bool {
+        self.domain_decision(uri).is_some()
+    }
+
+    fn domain_decision(&self, uri: &Uri) -> Option<Decision> {
+        if self.allowed_domains.is_empty() && self.blocked_domains.is_empty() {
+            return None;
+        }
+        if uri.is_mail() || uri.host_ip().is_some() {
+            return None;
+        }
+        let domain = uri.domain()?.trim_end_matches('.').to_lowercase();
+        if let Some(blocked) = self
+            .blocked_domains
+            .iter()
+            .find(|rule| domain_rule_matches(rule, &domain))
+        {
+            return Some(Decision::DomainBlocked(blocked.clone()));
+        }
+        if !self.allowed_domains.is_empty()
+            && !self
+                .allowed_domains
+                .iter()
+                .any(|rule| domain_rule_matches(rule, &domain))
+        {
+            return Some(Decision::DomainNotAllowed(domain));
+        }
+        None
+    }
+
+    #[must_use]
+    /// Whether the given URI is templated, e.g. contains `{id}` or `$VERSION`
+    pub fn is_template(&self, uri: &Uri) -> bool {
+        self.template_decision(uri).is_some()
+    }
+
+    fn template_decision(&self, uri: &Uri) -> Option<Decision> {
+        let pattern = self.templates.as_ref()?.first_match(uri.as_str())?;
+        Some(Decision::Template { pattern })
     }
 
     #[inline]
@@ -88,16 +215,20 @@ impl Filter {
     #[inline]
     fn is_excludes_empty(&self) -> bool {
         !matches!(self.excludes, Some(ref excludes) if !excludes.is_empty())
+            && !matches!(self.shared_excludes, Some(ref excludes) if !excludes.is_empty())
     }
 
-    #[inline]
-    fn is_includes_match(&self, input: &str) -> bool {
-        matches!(self.includes, Some(ref includes) if includes.is_match(input))
+    fn includes_match(&self, input: &str) -> Option<Decision> {
+        let (index, pattern) = self.includes.as_ref()?.first_match(input)?;
+        Some(Decision::Included { index, pattern })
     }
 
-    #[inline]
-    fn is_excludes_match(&self, input: &str) -> bool {
-        matches!(self.excludes, Some(ref excludes) if excludes.is_match(input))
+    fn excludes_match(&self, input: &str) -> Option<Decision> {
+        if let Some((index, pattern)) = self.excludes.as_ref().and_then(|e| e.first_match(input)) {
+            return Some(Decision::Excluded { index, pattern });
+        }
+        let (index, pattern) = self.shared_excludes.as_ref()?.first_match(input)?;
+        Some(Decision::SharedExcluded { index, pattern })
     }
 
     /// Determine whether a given [`Uri`] should be excluded.
@@ -106,8 +237,12 @@ impl Filter {
     ///
     /// 1. If any of the following conditions are met, the URI is excluded:
     ///   - If it's a mail address and it's configured to ignore mail addresses.
-    ///   - If the IP address belongs to a type that is configured to exclude.
-    ///   - If the scheme of URI is not the allowed scheme.
+    ///   - If the IP address belongs to a type or network that is configured to
+    ///     exclude, unless it belongs to an included network.
+    ///   - If the scheme of URI is excluded, or not one of the allowed schemes.
+    ///   - If the host is blocked, or not one of the allowed hosts.
+    ///   - If the registrable domain is blocked, or not one of the allowed domains.
+    ///   - If it contains a placeholder of a template (e.g. `{id}`).
     /// 2. Decide whether the URI is *presumably included* or *explicitly included*:
     ///    - When both excludes and includes rules are empty, it's *presumably included* unless
     ///      it's a known false positive.
@@ -118,11 +253,42 @@ impl Filter {
     ///      *presumably excluded*.
     ///    - When the excludes rules matches the URI, it's *explicitly excluded*.
     ///    - When the excludes rules matches the URI, it's *explicitly excluded*.
+    ///
+    /// See [`Filter::explain`] for the rule which made the decision.
     #[must_use]
     pub fn is_excluded(&self, uri: &Uri) -> bool {
+        self.explain(uri).is_excluded()
+    }
+
+    /// The rule which decides whether the given [`Uri`] is checked or excluded,
+    /// in the order described for [`Filter::is_excluded`].
+    /// This helps to debug layered include and exclude patterns.
+    ///
+    /// ```
+    /// use lychee_lib::{filter::Decision, Filter, Uri};
+    /// use std::convert::TryFrom;
+    ///
+    /// let filter = Filter {
+    ///     exclude_mail: true,
+    ///     ..Filter::default()
+    /// };
+    /// let uri = Uri::try_from("mail@example.org").unwrap();
+    /// assert_eq!(filter.explain(&uri), Decision::MailExcluded);
+    /// ```
+    #[must_use]
+    pub fn explain(&self, uri: &Uri) -> Decision {
         // Skip mail address, specific IP, and scheme
-        if self.is_mail_excluded(uri) || self.is_ip_excluded(uri) || self.is_scheme_excluded(uri) {
-            return true;
+        if self.is_mail_excluded(uri) {
+            return Decision::MailExcluded;
+        }
+        if let Some(decision) = self
+            .ip_decision(uri)
+            .or_else(|| self.scheme_decision(uri))
+            .or_else(|| self.host_decision(uri))
+            .or_else(|| self.domain_decision(uri))
+            .or_else(|| self.template_decision(uri))
+        {
+            return decision;
         }
 
         let input = uri.as_str();
@@ -131,37 +297,85 @@ impl Filter {
             if self.is_excludes_empty() {
                 // Both excludes and includes rules are empty:
                 // *Presumably included* unless it's false positive
-                return is_false_positive(input);
+                return if is_false_positive(input) {
+                    Decision::FalsePositive
+                } else {
+                    Decision::Checked
+                };
             }
-        } else if self.is_includes_match(input) {
+        } else if let Some(included) = self.includes_match(input) {
             // *Explicitly included* (Includes take precedence over excludes)
-            return false;
+            return included;
         }
 
-        if is_false_positive(input)
         // Exclude well-known false-positives
         // Performed after checking includes to allow user-overwriddes
-                || self.is_excludes_empty()
-                // Previous checks imply input is not explicitly included,
-                // if excludes rules is empty, then *presumably excluded*
-            || self.is_excludes_match(input)
-        // If excludes rules matches input, then
-        // *explicitly excluded*
-        {
-            return true;
+        if is_false_positive(input) {
+            return Decision::FalsePositive;
+        }
+        // Previous checks imply input is not explicitly included,
+        // if excludes rules is empty, then *presumably excluded*
+        if self.is_excludes_empty() {
+            return Decision::NotIncluded;
         }
+        // If excludes rules matches input, then *explicitly excluded*
+        self.excludes_match(input).unwrap_or(Decision::Checked)
+    }
+}
+
+/// Whether `ip` is an address of a local network: a loopback, private or
+/// link-local address, including IPv6 unique local and link-local addresses
+pub(crate) const fn is_internal_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
+        IpAddr::V6(v6) => {
+            let first = v6.segments()[0];
+            v6.is_loopback() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+        }
+    }
+}
 
-        false
+/// Check if `host` is `domain` or one of its subdomains
+pub(crate) fn is_subdomain(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .map_or(false, |prefix| prefix.ends_with('.'))
+}
+
+/// Whether `rule` is a registrable domain (e.g. `example.com`) or a public
+/// suffix with a wildcard (e.g. `*.gov.cn`) according to the public suffix list
+pub(crate) fn is_domain_rule(rule: &str) -> bool {
+    let rule = rule.trim_end_matches('.').to_lowercase();
+    match rule.strip_prefix("*.") {
+        Some(suffix) => psl::suffix_str(suffix) == Some(suffix),
+        None => psl::domain_str(&rule) == Some(rule.as_str()),
+    }
+}
+
+/// Check if the lowercase `domain` matches the domain rule, see
+/// [`Filter::allowed_domains`]
+fn domain_rule_matches(rule: &str, domain: &str) -> bool {
+    match rule.strip_prefix("*.") {
+        Some(suffix) => {
+            psl::suffix_str(domain).map_or(false, |public| is_subdomain(public, suffix))
+        }
+        None => psl::domain_str(domain) == Some(rule),
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::convert::TryFrom;
+
     use regex::RegexSet;
     use reqwest::Url;
     use url::Host;
 
-    use super::{Excludes, Filter, Includes};
+    use super::{
+        is_domain_rule, is_internal_ip, Decision, Excludes, Filter, Includes, IpNetwork,
+        SharedExcludes,
+    };
     use crate::test_utils::{mail, website};
 
     // Note: the standard library as of Rust stable 1.47.0 does not expose
@@ -293,6 +507,22 @@ mod test {
         assert!(!filter.is_excluded(&website("http://bar.dev")));
         assert!(!filter.is_excluded(&mail("foo@bar.dev")));
     }
+    #[test]
+    fn test_shared_excludes() {
+        let shared_excludes = SharedExcludes::default();
+        let filter = Filter {
+            shared_excludes: Some(shared_excludes.clone()),
+            ..Filter::default()
+        };
+        assert!(!filter.is_excluded(&website("http://github.com")));
+
+        shared_excludes.replace(Some(Excludes {
+            regex: RegexSet::new(&[r"github.com"]).unwrap(),
+        }));
+        assert!(filter.is_excluded(&website("http://github.com")));
+        assert!(!filter.is_excluded(&website("http://bar.dev")));
+    }
+
     #[test]
     fn test_exclude_include_regex() {
         let includes = Includes {
@@ -373,4 +603,221 @@ mod test {
         assert!(!filter.is_excluded(&website(V6_MAPPED_V4_PRIVATE_CLASS_A)));
         assert!(!filter.is_excluded(&website(V6_MAPPED_V4_LINK_LOCAL)));
     }
+
+    #[test]
+    fn test_schemes() {
+        let filter = Filter {
+            schemes: vec!["mailto".to_owned()].into_iter().collect(),
+            ..Filter::default()
+        };
+        assert!(!filter.is_excluded(&mail("mail@example.org")));
+        assert!(filter.is_excluded(&website("https://example.org")));
+
+        let filter = Filter {
+            exclude_schemes: vec!["http".to_owned()].into_iter().collect(),
+            ..Filter::default()
+        };
+        assert!(filter.is_excluded(&website("http://example.org")));
+        assert!(!filter.is_excluded(&website("https://example.org")));
+        assert!(!filter.is_excluded(&mail("mail@example.org")));
+    }
+
+    #[test]
+    fn test_allowed_hosts() {
+        let filter = Filter {
+            allowed_hosts: vec!["example.com".to_owned()].into_iter().collect(),
+            ..Filter::default()
+        };
+
+        assert!(!filter.is_excluded(&website("https://example.com/docs")));
+        assert!(!filter.is_excluded(&website("https://docs.Example.com/")));
+        assert!(filter.is_excluded(&website("https://notexample.com")));
+        assert!(filter.is_excluded(&website("https://example.community")));
+        assert!(!filter.is_excluded(&mail("mail@example.org")));
+    }
+
+    #[test]
+    fn test_blocked_hosts() {
+        let filter = Filter {
+            blocked_hosts: vec!["example.com".to_owned()].into_iter().collect(),
+            ..Filter::default()
+        };
+
+        assert!(filter.is_excluded(&website("https://example.com/docs")));
+        assert!(filter.is_excluded(&website("https://docs.example.com./")));
+        assert!(!filter.is_excluded(&website("https://notexample.com")));
+    }
+
+    #[test]
+    fn test_networks() {
+        let network = |network: &str| IpNetwork::try_from(network).unwrap();
+        let filter = Filter {
+            exclude_networks: vec![network("10.42.0.0/16"), network("2001:db8::/32")],
+            include_networks: vec![network("10.42.7.0/24")],
+            ..Filter::default()
+        };
+        assert_eq!(
+            filter.explain(&website("http://10.42.1.1/")),
+            Decision::NetworkExcluded("10.42.0.0/16".to_owned())
+        );
+        assert!(filter.is_excluded(&website("http://[2001:db8::1]/")));
+        assert!(!filter.is_excluded(&website("http://10.42.7.1/")));
+        assert!(!filter.is_excluded(&website("http://10.43.0.1/")));
+
+        // Included networks take precedence over the other IP address excludes
+        let filter = Filter {
+            exclude_private_ips: true,
+            include_networks: vec![network("10.42.0.0/16")],
+            ..Filter::default()
+        };
+        assert!(!filter.is_excluded(&website("http://10.42.0.1/")));
+        assert!(filter.is_excluded(&website("http://10.43.0.1/")));
+    }
+
+    #[test]
+    fn test_domain_rules() {
+        let filter = Filter {
+            blocked_domains: vec!["*.gov.cn".to_owned(), "example.co.uk".to_owned()]
+                .into_iter()
+                .collect(),
+            ..Filter::default()
+        };
+        assert!(filter.is_excluded(&website("https://www.beijing.gov.cn/")));
+        assert!(filter.is_excluded(&website("https://docs.example.co.uk/")));
+        // Regular expressions like `gov\.cn` would match these, too
+        assert!(!filter.is_excluded(&website("https://gov.cn.example.com/")));
+        assert!(!filter.is_excluded(&website("https://www.example.cn/")));
+        assert!(!filter.is_excluded(&website("https://other.co.uk/")));
+        assert_eq!(
+            filter.explain(&website("https://www.beijing.gov.cn/")),
+            Decision::DomainBlocked("*.gov.cn".to_owned())
+        );
+
+        let filter = Filter {
+            allowed_domains: vec!["example.com".to_owned(), "*.github.io".to_owned()]
+                .into_iter()
+                .collect(),
+            ..Filter::default()
+        };
+        assert!(!filter.is_excluded(&website("https://example.com/")));
+        assert!(!filter.is_excluded(&website("https://docs.example.com./")));
+        assert!(!filter.is_excluded(&website("https://user.github.io/")));
+        assert!(!filter.is_excluded(&website("http://127.0.0.1/")));
+        assert!(!filter.is_excluded(&mail("mail@example.org")));
+        assert_eq!(
+            filter.explain(&website("https://example.com.evil.net/")),
+            Decision::DomainNotAllowed("example.com.evil.net".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_is_domain_rule() {
+        assert!(is_domain_rule("example.com"));
+        assert!(is_domain_rule("Example.co.uk."));
+        assert!(is_domain_rule("*.gov.cn"));
+        assert!(is_domain_rule("*.github.io"));
+        assert!(!is_domain_rule("www.example.com"));
+        assert!(!is_domain_rule("github.io"));
+        assert!(!is_domain_rule("*.example.com"));
+        assert!(!is_domain_rule(""));
+    }
+
+    #[test]
+    fn test_is_internal_ip() {
+        for ip in &[
+            "127.0.0.1",
+            "10.1.2.3",
+            "192.168.0.1",
+            "169.254.0.1",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+        ] {
+            assert!(is_internal_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in &["93.184.216.34", "2606:2800:220:1::1"] {
+            assert!(!is_internal_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let filter = Filter {
+            includes: Some(Includes {
+                regex: RegexSet::new(&[r"docs", r"foo\.example\.org"]).unwrap(),
+            }),
+            excludes: Some(Excludes {
+                regex: RegexSet::new(&[r"github\.com", r"example\.org"]).unwrap(),
+            }),
+            exclude_loopback_ips: true,
+            exclude_schemes: vec!["ftp".to_owned()].into_iter().collect(),
+            blocked_hosts: vec!["example.com".to_owned()].into_iter().collect(),
+            ..Filter::default()
+        };
+
+        assert_eq!(
+            filter.explain(&website("https://foo.example.org")),
+            Decision::Included {
+                index: 1,
+                pattern: r"foo\.example\.org".to_owned()
+            }
+        );
+        assert_eq!(
+            filter.explain(&website("https://bar.example.org")),
+            Decision::Excluded {
+                index: 1,
+                pattern: r"example\.org".to_owned()
+            }
+        );
+        assert_eq!(
+            filter.explain(&website("https://bar.dev")),
+            Decision::Checked
+        );
+        assert_eq!(
+            filter.explain(&website("http://127.0.0.1/docs")),
+            Decision::LoopbackIpExcluded
+        );
+        assert_eq!(
+            filter.explain(&website("ftp://bar.dev/docs")),
+            Decision::SchemeExcluded("ftp".to_owned())
+        );
+        assert_eq!(
+            filter.explain(&website("https://docs.example.com")),
+            Decision::HostBlocked("example.com".to_owned())
+        );
+        assert!(!filter.explain(&website("https://bar.dev")).is_excluded());
+
+        let filter = Filter {
+            includes: Some(Includes {
+                regex: RegexSet::new(&[r"docs"]).unwrap(),
+            }),
+            ..Filter::default()
+        };
+        assert_eq!(
+            filter.explain(&website("https://bar.dev")),
+            Decision::NotIncluded
+        );
+        assert_eq!(
+            filter.explain(&website("http://www.w3.org/1999/xhtml")),
+            Decision::FalsePositive
+        );
+    }
+
+    #[test]
+    fn test_templates() {
+        let filter = Filter {
+            templates: Some(Templates::default()),
+            ..Filter::default()
+        };
+        assert!(filter.is_template(&website("https://example.com/users/{id}")));
+        assert!(filter.is_excluded(&website("https://example.com/releases/$VERSION")));
+        assert_eq!(
+            filter.explain(&website("https://example.com/items/<id>")),
+            Decision::Template {
+                pattern: DEFAULT_TEMPLATE_PATTERNS[3].to_owned()
+            }
+        );
+        assert!(!filter.is_excluded(&website("https://example.com/users/42")));
+        assert!(!Filter::default().is_excluded(&website("https://example.com/users/{id}")));
+    }
 }
@@ -0,0 +1,136 @@
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display},
+    net::IpAddr,
+};
+
+use crate::ErrorKind;
+
+/// An IP network in CIDR notation, e.g. `10.42.0.0/16` or `2001:db8::/32`.
+/// A single address (e.g. `10.0.0.5`) is a network of its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IpNetwork {
+    /// The first address of the network, with all host bits zero
+    address: IpAddr,
+    /// The number of leading bits which identify the network
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// Whether the network contains the given IP address. Networks only
+    /// contain addresses of their own family.
+    #[must_use]
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                u128::from(u32::from(network)) == mask(u32::from(ip).into(), 32, self.prefix)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                u128::from(network) == mask(ip.into(), 128, self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Zero all bits of a `bits` long address after the first `prefix` bits
+fn mask(address: u128, bits: u32, prefix: u8) -> u128 {
+    let host_bits = bits - u32::from(prefix);
+    if host_bits >= 128 {
+        0
+    } else {
+        address >> host_bits << host_bits
+    }
+}
+
+impl TryFrom<&str> for IpNetwork {
+    type Error = ErrorKind;
+
+    fn try_from(network: &str) -> Result<Self, Self::Error> {
+        let invalid = || ErrorKind::InvalidNetwork(network.to_owned());
+        let (address, prefix) = match network.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (network.trim(), None),
+        };
+        let address: IpAddr = address
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .map_err(|_| invalid())?;
+        let bits = if address.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| u32::from(*prefix) <= bits)
+                .ok_or_else(invalid)?,
+            #[allow(clippy::cast_possible_truncation)]
+            None => bits as u8,
+        };
+        // Host bits are dropped, so `10.42.1.2/16` is `10.42.0.0/16`
+        let address = match address {
+            IpAddr::V4(v4) => {
+                #[allow(clippy::cast_possible_truncation)]
+                let masked = mask(u32::from(v4).into(), 32, prefix) as u32;
+                IpAddr::V4(masked.into())
+            }
+            IpAddr::V6(v6) => IpAddr::V6(mask(v6.into(), 128, prefix).into()),
+        };
+        Ok(Self { address, prefix })
+    }
+}
+
+impl Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+
+    use super::IpNetwork;
+    use crate::ErrorKind;
+
+    fn network(network: &str) -> IpNetwork {
+        IpNetwork::try_from(network).unwrap()
+    }
+
+    #[test]
+    fn test_contains() {
+        let corporate = network("10.42.0.0/16");
+        assert!(corporate.contains("10.42.0.1".parse().unwrap()));
+        assert!(corporate.contains("10.42.255.255".parse().unwrap()));
+        assert!(!corporate.contains("10.43.0.1".parse().unwrap()));
+        assert!(!corporate.contains("::ffff:10.42.0.1".parse().unwrap()));
+
+        let documentation = network("2001:db8::/32");
+        assert!(documentation.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!documentation.contains("2001:db9::1".parse().unwrap()));
+
+        assert!(network("10.0.0.5").contains("10.0.0.5".parse().unwrap()));
+        assert!(!network("10.0.0.5").contains("10.0.0.6".parse().unwrap()));
+        assert!(network("0.0.0.0/0").contains("93.184.216.34".parse().unwrap()));
+        assert!(network("::/0").contains("::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(network("10.42.1.2/16").to_string(), "10.42.0.0/16");
+        assert_eq!(network("[2001:db8::1]/32").to_string(), "2001:db8::/32");
+        assert_eq!(network("::1").to_string(), "::1/128");
+        for invalid in &[
+            "10.42.0.0/33",
+            "2001:db8::/129",
+            "10.42.0.0/",
+            "corp/16",
+            "",
+        ] {
+            assert_eq!(
+                IpNetwork::try_from(*invalid),
+                Err(ErrorKind::InvalidNetwork((*invalid).to_owned()))
+            );
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use regex::RegexSet;
+use tokio::{fs::read_to_string, task::JoinHandle, time::sleep};
+
+use super::Excludes;
+use crate::Result;
+
+/// Exclude patterns which can be replaced while links are being checked.
+///
+/// Clones share the same patterns, so replacing them affects every client
+/// of a pool. This allows long-running checkers to silence a noisy link
+/// without a restart.
+#[derive(Clone, Debug, Default)]
+pub struct SharedExcludes {
+    excludes: Arc<RwLock<Option<Excludes>>>,
+}
+
+impl SharedExcludes {
+    #[must_use]
+    /// Create shared excludes from the given initial patterns
+    pub fn new(excludes: Option<Excludes>) -> Self {
+        Self {
+            excludes: Arc::new(RwLock::new(excludes)),
+        }
+    }
+
+    /// Replace the patterns for all subsequent checks
+    pub fn replace(&self, excludes: Option<Excludes>) {
+        if let Ok(mut current) = self.excludes.write() {
+            *current = excludes;
+        }
+    }
+
+    #[must_use]
+    /// Returns `true` if the given input string matches the current patterns
+    /// and should hence be excluded from checking
+    pub fn is_match(&self, input: &str) -> bool {
+        match self.excludes.read() {
+            Ok(excludes) => matches!(*excludes, Some(ref e) if e.is_match(input)),
+            Err(_) => false,
+        }
+    }
+
+    #[must_use]
+    /// The index and the pattern of the first current pattern
+    /// matching the given input, if any
+    pub fn first_match(&self, input: &str) -> Option<(usize, String)> {
+        match self.excludes.read() {
+            Ok(excludes) => excludes.as_ref()?.first_match(input),
+            Err(_) => None,
+        }
+    }
+
+    #[must_use]
+    /// Whether there are currently no patterns defined to be excluded
+    pub fn is_empty(&self) -> bool {
+        match self.excludes.read() {
+            Ok(excludes) => !matches!(*excludes, Some(ref e) if !e.is_empty()),
+            Err(_) => true,
+        }
+    }
+
+    /// Load exclude patterns from a file and reload them whenever its
+    /// contents change. The file contains one regular expression per line;
+    /// empty lines and lines starting with `#` are ignored.
+    ///
+    /// The file is checked every `interval`. If it can't be read or contains
+    /// an invalid pattern after a change, the previous patterns stay active.
+    /// Abort the returned handle to stop watching.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read initially
+    /// or contains invalid regular expressions.
+    pub async fn watch(path: PathBuf, interval: Duration) -> Result<(Self, JoinHandle<()>)> {
+        let mut contents = read_to_string(&path).await.map_err(|e| (path.clone(), e))?;
+        let shared = Self::new(parse_excludes(&contents)?);
+
+        let watched = shared.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                sleep(interval).await;
+                let new_contents = match read_to_string(&path).await {
+                    Ok(c) if c != contents => c,
+                    _ => continue,
+                };
+                if let Ok(excludes) = parse_excludes(&new_contents) {
+                    watched.replace(excludes);
+                    contents = new_contents;
+                }
+            }
+        });
+
+        Ok((shared, handle))
+    }
+}
+
+/// Parse one regular expression per line, skipping empty lines and comments
+fn parse_excludes(contents: &str) -> Result<Option<Excludes>> {
+    let patterns: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Excludes {
+        regex: RegexSet::new(patterns)?,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use tokio::time::sleep;
+
+    use super::SharedExcludes;
+
+    #[tokio::test]
+    async fn test_watch_reloads_patterns() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), "# comment\nexample\\.org\n").unwrap();
+
+        let (excludes, handle) =
+            SharedExcludes::watch(file.path().to_owned(), Duration::from_millis(10))
+                .await
+                .unwrap();
+        assert!(excludes.is_match("https://example.org"));
+        assert!(!excludes.is_match("https://example.com"));
+
+        fs::write(file.path(), "example\\.com\n").unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert!(!excludes.is_match("https://example.org"));
+        assert!(excludes.is_match("https://example.com"));
+
+        // Invalid patterns keep the previous ones active
+        fs::write(file.path(), "(unclosed\n").unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert!(excludes.is_match("https://example.com"));
+
+        handle.abort();
+    }
+}
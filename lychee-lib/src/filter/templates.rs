@@ -0,0 +1,70 @@
+use regex::RegexSet;
+
+/// Placeholders of templated links, which can't be checked before the
+/// template is rendered. They also match the percent-encoded forms, which
+/// URLs end up with.
+pub const DEFAULT_TEMPLATE_PATTERNS: &[&str] = &[
+    // `{id}` or `{{ site.url }}`
+    r"(?i)(\{|%7B)[^/?#&=]*?(\}|%7D)",
+    // `$VERSION` or `${VERSION}`
+    r"\$\{?[A-Z][A-Z0-9_]*\b",
+    // `%s` or `%d` of format strings, but not escapes like `%d2`
+    r"%(25)?(s|d([^0-9A-Fa-f]|$))",
+    // `<id>`
+    r"(?i)(<|%3C)[a-z_][\w-]*(>|%3E)",
+];
+
+/// Patterns of placeholders (e.g. `{id}` or `$VERSION`) which tell templated
+/// links, so they are excluded instead of failing
+#[derive(Clone, Debug)]
+pub struct Templates {
+    /// Set of placeholder patterns
+    pub regex: RegexSet,
+}
+
+impl Default for Templates {
+    fn default() -> Self {
+        Self {
+            regex: RegexSet::new(DEFAULT_TEMPLATE_PATTERNS).expect("valid template patterns"),
+        }
+    }
+}
+
+impl Templates {
+    #[must_use]
+    /// The pattern of the first placeholder found in the given input, if any
+    pub fn first_match(&self, input: &str) -> Option<String> {
+        let index = self.regex.matches(input).into_iter().next()?;
+        Some(self.regex.patterns()[index].clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Templates;
+
+    #[test]
+    fn test_default_patterns() {
+        let templates = Templates::default();
+        for template in &[
+            "https://example.com/users/%7Bid%7D",
+            "https://example.com/%7B%7B%20page.url%20%7D%7D",
+            "https://example.com/download/$VERSION/app.zip",
+            "https://example.com/download/$%7BVERSION%7D/app.zip",
+            "https://example.com/search?q=%s",
+            "https://example.com/items/%d",
+            "https://example.com/items/%3Cid%3E/edit",
+        ] {
+            assert!(templates.first_match(template).is_some(), "{}", template);
+        }
+        for link in &[
+            "https://example.com/users/42",
+            "https://example.com/caf%C3%A9",
+            "https://example.com/%d2%80",
+            "https://example.com/?price=$5",
+            "https://example.com/search?q=%25",
+        ] {
+            assert!(templates.first_match(link).is_none(), "{}", link);
+        }
+    }
+}
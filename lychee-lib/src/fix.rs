@@ -0,0 +1,99 @@
+use serde::Serialize;
+
+use crate::{Input, Response, Span};
+
+/// A suggested replacement of a link at a location in an input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Fix {
+    /// The input containing the link
+    pub source: Input,
+    /// Location of the link in the input
+    pub span: Span,
+    /// The link as it was checked
+    pub original: String,
+    /// The link it should be replaced with
+    pub replacement: String,
+}
+
+impl Fix {
+    /// Fixes for all locations of the link of a response
+    /// which has a [replacement](crate::ResponseBody::replacement)
+    #[must_use]
+    pub fn from_response(response: &Response) -> Vec<Fix> {
+        let Response(source, body) = response;
+        let replacement = match &body.replacement {
+            Some(replacement) => replacement,
+            None => return Vec::new(),
+        };
+        body.spans
+            .iter()
+            .map(|span| Fix {
+                source: source.clone(),
+                span: *span,
+                original: body.uri.as_str().to_owned(),
+                replacement: replacement.as_str().to_owned(),
+            })
+            .collect()
+    }
+}
+
+/// Apply fixes to the content of their input.
+/// Fixes which overlap with a previous one are skipped.
+#[must_use]
+pub fn apply(content: &str, fixes: &[Fix]) -> String {
+    let mut fixes: Vec<&Fix> = fixes.iter().collect();
+    fixes.sort_by_key(|fix| fix.span);
+
+    let mut patched = String::with_capacity(content.len());
+    let mut position = 0;
+    for fix in fixes {
+        if fix.span.start < position || content.get(fix.span.start..fix.span.end).is_none() {
+            continue;
+        }
+        patched.push_str(&content[position..fix.span.start]);
+        patched.push_str(&fix.replacement);
+        position = fix.span.end;
+    }
+    patched.push_str(&content[position..]);
+    patched
+}
+
+#[cfg(test)]
+mod test {
+    use http::StatusCode;
+
+    use super::{apply, Fix};
+    use crate::{test_utils::website, Input, Response, ResponseBody, Span, Status};
+
+    #[test]
+    fn test_fixes() {
+        let content = "[a](http://example.org/docs) and http://example.org/docs";
+        let mut body = ResponseBody::new(
+            website("http://example.org/docs"),
+            Status::Ok(StatusCode::OK),
+        );
+        body.spans = vec![
+            Span {
+                start: 4,
+                end: 27,
+                line: 1,
+                column: 5,
+            },
+            Span {
+                start: 33,
+                end: 56,
+                line: 1,
+                column: 34,
+            },
+        ];
+        body.replacement = Some(website("https://example.org/docs/"));
+        let response = Response(Input::String(content.to_owned()), body);
+
+        let fixes = Fix::from_response(&response);
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(
+            apply(content, &fixes),
+            "[a](https://example.org/docs/) and https://example.org/docs/"
+        );
+    }
+}
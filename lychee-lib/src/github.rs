@@ -0,0 +1,462 @@
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use http::header::{self, HeaderMap};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{redact::REDACTED, ErrorKind, Result, Uri};
+
+pub(crate) const GITHUB_API: &str = "https://api.github.com";
+
+/// Installation tokens expire after an hour, so they get refreshed a bit earlier
+const INSTALLATION_TOKEN_LIFETIME: Duration = Duration::from_secs(55 * 60);
+
+/// First path segments on github.com which are pages rather than user profiles
+const RESERVED_PATHS: &[&str] = &[
+    "about",
+    "collections",
+    "contact",
+    "customer-stories",
+    "enterprise",
+    "explore",
+    "features",
+    "issues",
+    "login",
+    "marketplace",
+    "new",
+    "notifications",
+    "orgs",
+    "pricing",
+    "pulls",
+    "search",
+    "security",
+    "settings",
+    "site",
+    "sponsors",
+    "topics",
+    "trending",
+];
+
+/// Maximum number of ways to split a branch name containing slashes from a path
+const MAX_REF_SPLITS: usize = 3;
+
+/// Budget of GitHub API requests, as reported by the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GithubRateLimit {
+    /// Number of requests left in the current window
+    pub remaining: u64,
+    /// When the budget gets reset
+    pub reset: SystemTime,
+}
+
+impl GithubRateLimit {
+    /// Read the budget from the `X-RateLimit-*` headers of an API response
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name| headers.get(name)?.to_str().ok()?.parse::<u64>().ok();
+        Some(Self {
+            remaining: header("x-ratelimit-remaining")?,
+            reset: UNIX_EPOCH + Duration::from_secs(header("x-ratelimit-reset")?),
+        })
+    }
+
+    /// Time to wait until requests can be made again,
+    /// or `None` if there is budget left
+    pub(crate) fn wait(&self) -> Option<Duration> {
+        if self.remaining > 0 {
+            return None;
+        }
+        self.reset.duration_since(SystemTime::now()).ok()
+    }
+}
+
+/// Credentials of a GitHub App, used to authenticate as one of its installations
+/// instead of with a personal access token
+#[derive(Clone, PartialEq, Eq)]
+pub struct GithubApp {
+    /// ID of the app
+    pub app_id: u64,
+    /// ID of the installation of the app on an organization or account
+    pub installation_id: u64,
+    /// PEM-encoded private key of the app
+    pub private_key: String,
+}
+
+impl Debug for GithubApp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GithubApp")
+            .field("app_id", &self.app_id)
+            .field("installation_id", &self.installation_id)
+            .field("private_key", &REDACTED)
+            .finish()
+    }
+}
+
+/// Claims of the JSON Web Token which identifies the app itself
+#[derive(Serialize)]
+struct AppClaims {
+    iat: u64,
+    exp: u64,
+    iss: String,
+}
+
+#[derive(Deserialize)]
+struct InstallationToken {
+    token: String,
+}
+
+/// Credentials for the GitHub API
+#[derive(Clone)]
+pub(crate) enum GithubAuth {
+    /// Personal access token
+    Token(String),
+    /// Installation of a GitHub App, whose tokens expire and get refreshed
+    App(GithubAppTokens),
+}
+
+impl Debug for GithubAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Token(_) => f.debug_tuple("Token").field(&REDACTED).finish(),
+            Self::App(app) => f.debug_tuple("App").field(app).finish(),
+        }
+    }
+}
+
+impl GithubAuth {
+    /// A token which is currently valid
+    pub(crate) async fn token(&self, client: &reqwest::Client) -> Result<String> {
+        match self {
+            Self::Token(token) => Ok(token.clone()),
+            Self::App(app) => app.token(client).await,
+        }
+    }
+}
+
+/// Installation tokens of a GitHub App, shared between clones
+#[derive(Clone)]
+pub(crate) struct GithubAppTokens {
+    app_id: u64,
+    installation_id: u64,
+    key: EncodingKey,
+    /// Last installation token and when it was issued
+    current: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+impl Debug for GithubAppTokens {
+    // The private key stays out of logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GithubAppTokens")
+            .field("app_id", &self.app_id)
+            .field("installation_id", &self.installation_id)
+            .finish()
+    }
+}
+
+impl GithubAppTokens {
+    pub(crate) fn new(app: &GithubApp) -> Result<Self> {
+        let key = EncodingKey::from_rsa_pem(app.private_key.as_bytes())
+            .map_err(|e| ErrorKind::GitHubAppAuth(e.to_string()))?;
+        Ok(Self {
+            app_id: app.app_id,
+            installation_id: app.installation_id,
+            key,
+            current: Arc::default(),
+        })
+    }
+
+    /// Sign a short-lived token which identifies the app
+    fn jwt(&self) -> Result<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = AppClaims {
+            // Allow for clock drift, as recommended by GitHub
+            iat: now - 60,
+            exp: now + 9 * 60,
+            iss: self.app_id.to_string(),
+        };
+        jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &self.key)
+            .map_err(|e| ErrorKind::GitHubAppAuth(e.to_string()))
+    }
+
+    /// The current installation token, requesting a new one if it is about to expire.
+    /// Concurrent checks wait for a single refresh.
+    async fn token(&self, client: &reqwest::Client) -> Result<String> {
+        let mut current = self.current.lock().await;
+        if let Some((token, issued)) = &*current {
+            if issued.elapsed() < INSTALLATION_TOKEN_LIFETIME {
+                return Ok(token.clone());
+            }
+        }
+
+        let response = client
+            .post(format!(
+                "{}/app/installations/{}/access_tokens",
+                GITHUB_API, self.installation_id
+            ))
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.jwt()?))
+            .header(header::ACCEPT, "application/vnd.github.v3+json")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(ErrorKind::GitHubAppAuth(format!(
+                "installation token request returned {}",
+                response.status()
+            )));
+        }
+        let InstallationToken { token } = serde_json::from_str(&response.text().await?)
+            .map_err(|e| ErrorKind::GitHubAppAuth(e.to_string()))?;
+        *current = Some((token.clone(), Instant::now()));
+        Ok(token)
+    }
+}
+
+/// A link to a resource on GitHub which can be validated via the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GithubLink<'a> {
+    /// A repository (e.g. `/lycheeverse/lychee`)
+    Repo { owner: &'a str, repo: &'a str },
+    /// A file or directory on a branch, tag or commit
+    /// (e.g. `/lycheeverse/lychee/blob/master/README.md#L10-L20`).
+    /// As branch names may contain slashes, the segments after `blob`
+    /// or `tree` can't be split into reference and path up front.
+    Content {
+        owner: &'a str,
+        repo: &'a str,
+        segments: Vec<&'a str>,
+        /// Whether the link points to a directory (`tree`) rather than a file (`blob`)
+        directory: bool,
+        /// Last line of the line anchor, if any
+        line: Option<usize>,
+    },
+    /// An issue (`/issues/42`) or pull request (`/pull/42`).
+    /// The API treats both the same.
+    Issue {
+        owner: &'a str,
+        repo: &'a str,
+        number: u64,
+    },
+    /// A release (e.g. `/releases/tag/v0.7.0`)
+    Release {
+        owner: &'a str,
+        repo: &'a str,
+        tag: &'a str,
+    },
+    /// A user or organization profile (e.g. `/lycheeverse`)
+    User { name: &'a str },
+}
+
+impl<'a> GithubLink<'a> {
+    /// Parse a link to github.com.
+    /// Returns `None` for other hosts and for pages which can't be checked via the API.
+    pub(crate) fn parse(uri: &'a Uri) -> Option<Self> {
+        if !matches!(uri.domain()?, "github.com" | "www.github.com") {
+            return None;
+        }
+        let segments: Vec<&str> = uri.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match *segments.as_slice() {
+            [name] if !RESERVED_PATHS.contains(&name) => Some(Self::User { name }),
+            [owner, repo] => Some(Self::Repo { owner, repo }),
+            [owner, repo, "blob", _, _, ..] => Some(Self::Content {
+                owner,
+                repo,
+                segments: segments[3..].to_vec(),
+                directory: false,
+                line: uri.url.fragment().and_then(parse_line_anchor),
+            }),
+            [owner, repo, "tree", _, ..] => Some(Self::Content {
+                owner,
+                repo,
+                segments: segments[3..].to_vec(),
+                directory: true,
+                line: None,
+            }),
+            [owner, repo, "issues", number] | [owner, repo, "pull", number, ..] => {
+                Some(Self::Issue {
+                    owner,
+                    repo,
+                    number: number.parse().ok()?,
+                })
+            }
+            [owner, repo, "releases", "tag", tag] => Some(Self::Release { owner, repo, tag }),
+            _ => None,
+        }
+    }
+
+    /// Whether the link points below a repository (or to a user),
+    /// where a successful HTML response doesn't mean the resource exists
+    pub(crate) const fn is_deep_link(&self) -> bool {
+        !matches!(self, Self::Repo { .. })
+    }
+
+    /// Paths of the API endpoints which return the linked resource.
+    /// There are several candidates for content, as the split of the
+    /// branch name and the path is ambiguous.
+    pub(crate) fn api_paths(&self) -> Vec<String> {
+        match self {
+            Self::Repo { owner, repo } => vec![format!("/repos/{}/{}", owner, repo)],
+            Self::Content {
+                owner,
+                repo,
+                segments,
+                directory,
+                ..
+            } => {
+                // Files need a non-empty path, directories can be the root
+                let max_split = if *directory {
+                    segments.len()
+                } else {
+                    segments.len() - 1
+                };
+                (1..=max_split.min(MAX_REF_SPLITS))
+                    .map(|split| {
+                        let (reference, path) = segments.split_at(split);
+                        format!(
+                            "/repos/{}/{}/contents/{}?ref={}",
+                            owner,
+                            repo,
+                            path.join("/"),
+                            reference.join("/")
+                        )
+                    })
+                    .collect()
+            }
+            Self::Issue {
+                owner,
+                repo,
+                number,
+            } => vec![format!("/repos/{}/{}/issues/{}", owner, repo, number)],
+            Self::Release { owner, repo, tag } => {
+                vec![format!("/repos/{}/{}/releases/tags/{}", owner, repo, tag)]
+            }
+            Self::User { name } => vec![format!("/users/{}", name)],
+        }
+    }
+}
+
+/// Parse a line anchor like `L42` or `L42-L50`, returning the last line
+fn parse_line_anchor(fragment: &str) -> Option<usize> {
+    let last = fragment.rsplit('-').next()?;
+    last.strip_prefix('L')?.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use http::header::HeaderMap;
+
+    use super::{GithubApp, GithubAppTokens, GithubLink, GithubRateLimit};
+    use crate::{test_utils::website, ErrorKind};
+
+    #[test]
+    fn test_app_invalid_key() {
+        let app = GithubApp {
+            app_id: 1,
+            installation_id: 2,
+            private_key: "not a key".to_string(),
+        };
+        assert!(matches!(
+            GithubAppTokens::new(&app),
+            Err(ErrorKind::GitHubAppAuth(_))
+        ));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(GithubRateLimit::from_headers(&headers), None);
+
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1623772800".parse().unwrap());
+        let limit = GithubRateLimit::from_headers(&headers).unwrap();
+        assert_eq!(
+            limit,
+            GithubRateLimit {
+                remaining: 0,
+                reset: UNIX_EPOCH + Duration::from_secs(1_623_772_800),
+            }
+        );
+        // The reset is in the past
+        assert_eq!(limit.wait(), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let uri = website("https://github.com/lycheeverse/lychee/blob/master/src/lib.rs#L10-L20");
+        assert_eq!(
+            GithubLink::parse(&uri),
+            Some(GithubLink::Content {
+                owner: "lycheeverse",
+                repo: "lychee",
+                segments: vec!["master", "src", "lib.rs"],
+                directory: false,
+                line: Some(20),
+            })
+        );
+
+        let uri = website("https://github.com/lycheeverse/lychee/pull/42/files");
+        assert_eq!(
+            GithubLink::parse(&uri),
+            Some(GithubLink::Issue {
+                owner: "lycheeverse",
+                repo: "lychee",
+                number: 42,
+            })
+        );
+
+        let uri = website("https://github.com/lycheeverse/lychee/releases/tag/v0.7.0");
+        assert_eq!(
+            GithubLink::parse(&uri),
+            Some(GithubLink::Release {
+                owner: "lycheeverse",
+                repo: "lychee",
+                tag: "v0.7.0",
+            })
+        );
+
+        let uri = website("https://github.com/lycheeverse");
+        assert_eq!(
+            GithubLink::parse(&uri),
+            Some(GithubLink::User {
+                name: "lycheeverse"
+            })
+        );
+
+        assert!(GithubLink::parse(&website("https://github.com/pricing")).is_none());
+        assert!(
+            GithubLink::parse(&website("https://github.com/lycheeverse/lychee/actions")).is_none()
+        );
+        assert!(GithubLink::parse(&website("https://gitlab.com/lycheeverse/lychee")).is_none());
+    }
+
+    #[test]
+    fn test_api_paths() {
+        let uri = website("https://github.com/lycheeverse/lychee/blob/feature/x/README.md");
+        assert_eq!(
+            GithubLink::parse(&uri).unwrap().api_paths(),
+            vec![
+                "/repos/lycheeverse/lychee/contents/x/README.md?ref=feature",
+                "/repos/lycheeverse/lychee/contents/README.md?ref=feature/x",
+            ]
+        );
+
+        let uri = website("https://github.com/lycheeverse/lychee/tree/master");
+        assert_eq!(
+            GithubLink::parse(&uri).unwrap().api_paths(),
+            vec!["/repos/lycheeverse/lychee/contents/?ref=master"]
+        );
+
+        let uri = website("https://github.com/lycheeverse/lychee/issues/12");
+        assert_eq!(
+            GithubLink::parse(&uri).unwrap().api_paths(),
+            vec!["/repos/lycheeverse/lychee/issues/12"]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::{Input, Uri};
+
+/// The links found by a [`Collector`](crate::Collector), from the inputs
+/// (e.g. pages) to the URIs they link to. Record it with
+/// [`Collector::link_graph`](crate::Collector::link_graph).
+///
+/// Inputs are named like in the reports, i.e. by their URL or path.
+/// Links which are excluded or which were checked before are part of the graph
+/// as well. The graph serializes to JSON as adjacency lists.
+///
+/// ```
+/// use std::sync::Arc;
+/// use lychee_lib::{graph::LinkGraph, Collector, Input, Result};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let graph = Arc::new(LinkGraph::default());
+///     Collector::default()
+///         .link_graph(graph.clone())
+///         .collect_links(&[Input::String("See https://example.org".to_owned())])
+///         .await?;
+///     assert_eq!(graph.referrers("https://example.org/"), ["raw input string"]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct LinkGraph {
+    edges: Mutex<BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl LinkGraph {
+    /// The edges, which are still consistent if a thread panicked while
+    /// holding the lock, as they are only ever extended
+    fn edges(&self) -> MutexGuard<'_, BTreeMap<String, BTreeSet<String>>> {
+        self.edges.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record the links of an input
+    pub(crate) fn add<'a>(&self, source: &Input, targets: impl IntoIterator<Item = &'a Uri>) {
+        self.edges()
+            .entry(source.to_string())
+            .or_default()
+            .extend(targets.into_iter().map(|target| target.as_str().to_owned()));
+    }
+
+    /// The links of every input, by input
+    #[must_use]
+    pub fn adjacency(&self) -> BTreeMap<String, BTreeSet<String>> {
+        self.edges().clone()
+    }
+
+    /// The inputs which link to `target`, e.g. to find the pages with a
+    /// broken link
+    #[must_use]
+    pub fn referrers(&self, target: &str) -> Vec<String> {
+        self.edges()
+            .iter()
+            .filter(|(_, targets)| targets.contains(target))
+            .map(|(source, _)| source.clone())
+            .collect()
+    }
+
+    /// The inputs which no other input links to.
+    /// Inputs are compared to links by their name, so this works best with
+    /// URL inputs, e.g. all pages of a site.
+    #[must_use]
+    pub fn orphans(&self) -> Vec<String> {
+        let edges = self.edges();
+        edges
+            .keys()
+            .filter(|source| {
+                !edges
+                    .iter()
+                    .any(|(other, targets)| other != *source && targets.contains(*source))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The graph in the DOT language of Graphviz
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let edges: String = self
+            .edges()
+            .iter()
+            .flat_map(|(source, targets)| {
+                targets
+                    .iter()
+                    .map(move |target| format!("  {:?} -> {:?};\n", source, target))
+            })
+            .collect();
+        format!("digraph links {{\n{}}}\n", edges)
+    }
+}
+
+impl Serialize for LinkGraph {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.adjacency().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::LinkGraph;
+    use crate::{test_utils::website, Input};
+
+    fn graph() -> LinkGraph {
+        let graph = LinkGraph::default();
+        let page = |url: &str| Input::new(url, false);
+        graph.add(
+            &page("https://example.org/"),
+            &[
+                website("https://example.org/docs"),
+                website("https://example.org/broken"),
+            ],
+        );
+        graph.add(
+            &page("https://example.org/docs"),
+            &[
+                website("https://example.org/"),
+                website("https://example.org/broken"),
+            ],
+        );
+        graph.add(
+            &page("https://example.org/old"),
+            &[website("https://example.org/")],
+        );
+        graph
+    }
+
+    #[test]
+    fn test_referrers() {
+        assert_eq!(
+            graph().referrers("https://example.org/broken"),
+            vec!["https://example.org/", "https://example.org/docs"]
+        );
+        assert!(graph().referrers("https://example.org/old").is_empty());
+    }
+
+    #[test]
+    fn test_orphans() {
+        assert_eq!(graph().orphans(), vec!["https://example.org/old"]);
+    }
+
+    #[test]
+    fn test_formats() {
+        let graph = graph();
+        assert!(graph
+            .to_dot()
+            .contains("  \"https://example.org/old\" -> \"https://example.org/\";\n"));
+        let json = serde_json::to_value(&graph).unwrap();
+        assert_eq!(
+            json["https://example.org/docs"],
+            serde_json::json!(["https://example.org/", "https://example.org/broken"])
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+
+use crate::{Status, Uri};
+
+/// Hooks around the HTTP requests of website checks, e.g. to sign requests
+/// (AWS `SigV4`, HMAC), to add correlation IDs or to export metrics.
+/// Register them with [`ClientBuilder::interceptors`](crate::ClientBuilder).
+///
+/// Unlike an [`Observer`](crate::Observer), an interceptor can change the
+/// requests which get sent. Both methods have default implementations,
+/// so only the relevant one needs to be implemented.
+///
+/// ```
+/// use lychee_lib::{ClientBuilder, Interceptor};
+/// use std::sync::Arc;
+///
+/// #[derive(Debug)]
+/// struct CorrelationId(&'static str);
+///
+/// impl Interceptor for CorrelationId {
+///     fn before_request(&self, mut request: reqwest::Request) -> reqwest::Request {
+///         let value = reqwest::header::HeaderValue::from_static(self.0);
+///         request.headers_mut().insert("x-correlation-id", value);
+///         request
+///     }
+/// }
+///
+/// let client = ClientBuilder::builder()
+///     .interceptors(vec![Arc::new(CorrelationId("docs-build-42")) as Arc<dyn Interceptor>])
+///     .build()
+///     .client();
+/// ```
+pub trait Interceptor: Debug + Send + Sync {
+    /// Called before each HTTP request gets sent, including the requests
+    /// for redirects. The returned request gets sent instead.
+    fn before_request(&self, request: reqwest::Request) -> reqwest::Request {
+        request
+    }
+
+    /// Called with the status of each attempt to check a website,
+    /// i.e. once more for every retry
+    fn after_response(&self, _uri: &Uri, _status: &Status) {}
+}
@@ -45,23 +45,83 @@
 #[cfg(doctest)]
 doc_comment::doctest!("../../README.md");
 
+mod adaptive_timeout;
+mod checksum;
 mod client;
 mod client_pool;
+mod codeowners;
+mod concurrency;
+mod connectivity;
+mod data_uri;
+mod dns_cache;
+mod docker;
+mod event_log;
+mod github;
+mod interceptor;
+mod mail_policy;
+mod mailbox;
+mod observer;
+mod packages;
+#[cfg(feature = "pdf")]
+mod pdf;
 mod quirks;
+mod redact;
+#[cfg(feature = "render")]
+mod render;
+#[cfg(feature = "server")]
+mod server;
 mod types;
 mod uri;
 
-/// A pool of clients, to handle concurrent checks
+/// Collection of links from inputs (files, globs, URLs, stdin and strings)
 pub mod collector;
 
+/// Configuration of the whole checker, e.g. from a TOML, YAML or JSON file
+pub mod config;
+
+/// Comparing the failed links of two runs, e.g. to fail only on regressions
+pub mod comparison;
+
+/// Restricting checks to the lines a unified diff (e.g. of a pull request)
+/// added or modified
+pub mod diff;
+
 /// Functionality to extract URIs from inputs
 pub mod extract;
 
+/// Suggested replacements of links (e.g. after permanent redirects)
+/// at their locations in the inputs, and a way to apply them
+pub mod fix;
+
+/// The graph of the links between inputs, e.g. to find orphan pages
+pub mod graph;
+
 /// Filters are a way to define behavior when encountering
 /// URIs that need to be treated differently, such as
 /// local IPs or e-mail addresses
 pub mod filter;
 
+/// Metrics of the checks for Prometheus, e.g. to monitor links continuously
+pub mod metrics;
+
+/// The stages a request passes through while being checked,
+/// which can be rearranged, extended or removed
+pub mod pipeline;
+
+/// Hooks which amend responses after their status has been classified,
+/// including built-in detectors for soft 404s, parked domains and SSO pages
+pub mod post_process;
+
+/// Checking links periodically, with the history of their results
+/// to tell flaky links from broken ones
+pub mod scheduler;
+
+/// Sitemaps of the pages of a site which were checked successfully
+pub mod sitemap;
+
+/// Re-checking the links of files whenever they change
+pub mod watch;
+
 #[cfg(test)]
 #[macro_use]
 pub mod test_utils;
@@ -69,14 +129,37 @@ pub mod test_utils;
 #[cfg(test)]
 use doc_comment as _; // required for doctest
 use openssl_sys as _; // required for vendored-openssl feature
-use ring as _; // required for apple silicon
 
 #[doc(inline)]
 pub use crate::{
+    adaptive_timeout::AdaptiveTimeout,
+    checksum::Checksums,
     client::{check, ClientBuilder},
-    client_pool::ClientPool,
-    collector::{Collector, Input},
-    filter::{Excludes, Filter, Includes},
-    types::{ErrorKind, Request, Response, ResponseBody, Result, Status},
+    client_pool::{CancellationToken, ClientPool},
+    codeowners::CodeOwners,
+    collector::{Collector, Input, InputContent, Truncation},
+    connectivity::Connectivity,
+    dns_cache::DnsCache,
+    event_log::{Event, EventLog},
+    filter::{Excludes, Filter, Includes, IpNetwork, SharedExcludes, Templates},
+    github::{GithubApp, GithubRateLimit},
+    interceptor::Interceptor,
+    mail_policy::{MailCheckMode, MailPolicy, SmtpConfig},
+    observer::Observer,
+    pipeline::{CheckRequest, Pipeline, Stage},
+    post_process::{PostProcessor, ProcessContext},
+    redact::{redact, redact_headers},
+    types::{
+        CheckPhase, ErrorKind, NetworkError, Redirect, Request, Response, ResponseBody, Result,
+        SkipReason, Span, Status, Timeline, TimelineEvent,
+    },
     uri::Uri,
 };
+
+#[cfg(feature = "render")]
+#[doc(inline)]
+pub use crate::render::Renderer;
+
+#[cfg(feature = "server")]
+#[doc(inline)]
+pub use crate::server::{Checks, Document, Server};
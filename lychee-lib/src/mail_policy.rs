@@ -0,0 +1,189 @@
+use std::{collections::HashSet, time::Duration};
+
+use crate::Uri;
+
+/// Domains of well-known free-mail providers
+const FREE_MAIL_DOMAINS: &[&str] = &[
+    "aol.com",
+    "gmail.com",
+    "gmx.de",
+    "gmx.net",
+    "googlemail.com",
+    "hotmail.com",
+    "icloud.com",
+    "live.com",
+    "mail.ru",
+    "outlook.com",
+    "protonmail.com",
+    "web.de",
+    "yahoo.com",
+    "yandex.ru",
+];
+
+/// Local parts of addresses which belong to a role rather than a person
+const ROLE_ACCOUNTS: &[&str] = &[
+    "abuse",
+    "admin",
+    "administrator",
+    "contact",
+    "hello",
+    "help",
+    "hostmaster",
+    "info",
+    "no-reply",
+    "noreply",
+    "office",
+    "postmaster",
+    "sales",
+    "support",
+    "team",
+    "webmaster",
+];
+
+/// How thoroughly mail addresses get checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailCheckMode {
+    /// Only validate the syntax of the address, without any network access
+    Syntax,
+    /// Check that the domain of the address has mail servers (MX records)
+    Mx,
+    /// Ask the mail server whether the address exists.
+    /// This is the most accurate, but also the slowest mode,
+    /// and some providers block these probes.
+    Smtp,
+}
+
+impl Default for MailCheckMode {
+    fn default() -> Self {
+        Self::Smtp
+    }
+}
+
+/// Settings of the SMTP probe of [`MailCheckMode::Smtp`],
+/// e.g. to go through a relay where port 25 is blocked (like most CI services)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SmtpConfig {
+    /// Sender address of the probe (`MAIL FROM`)
+    pub from_email: Option<String>,
+    /// Name to greet the mail server with (`EHLO`)
+    pub hello_name: Option<String>,
+    /// Host and port of a SOCKS5 proxy to connect through
+    pub proxy: Option<(String, u16)>,
+    /// Port of the mail servers, 25 by default
+    pub port: Option<u16>,
+    /// Timeout of the whole SMTP conversation
+    pub timeout: Option<Duration>,
+}
+
+/// Rules for mail addresses, which are applied before checking
+/// whether an address is reachable.
+#[derive(Debug, Clone, Default)]
+pub struct MailPolicy {
+    /// Domains which are always fine (e.g. the corporate domain).
+    /// Addresses at these domains are never denied or flagged.
+    pub allowed_domains: HashSet<String>,
+    /// Domains which must not be used
+    pub denied_domains: HashSet<String>,
+    /// Deny addresses at well-known free-mail providers (e.g. `gmail.com`)
+    pub deny_free_mail: bool,
+    /// Tag role accounts (e.g. `info@` or `admin@`) with `role-account`
+    pub flag_role_accounts: bool,
+    /// Only apply the policy to addresses from inputs with any of these tags.
+    /// Applies to all inputs if empty.
+    pub tags: Vec<String>,
+}
+
+/// Outcome of applying a [`MailPolicy`] to an address
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    /// The address is fine
+    Pass,
+    /// The domain of the address is denied
+    Deny(String),
+    /// The address is a role account
+    RoleAccount,
+}
+
+impl MailPolicy {
+    /// Apply the policy to a mail address found in an input with the given tags
+    pub(crate) fn evaluate(&self, uri: &Uri, input_tags: &[String]) -> Verdict {
+        if !self.tags.is_empty() && !input_tags.iter().any(|t| self.tags.contains(t)) {
+            return Verdict::Pass;
+        }
+        let (local, domain) = match uri.as_str().rsplit_once('@') {
+            Some((local, domain)) => (local.to_lowercase(), domain.to_lowercase()),
+            None => return Verdict::Pass,
+        };
+        if self.allowed_domains.contains(&domain) {
+            return Verdict::Pass;
+        }
+        if self.denied_domains.contains(&domain)
+            || (self.deny_free_mail && FREE_MAIL_DOMAINS.contains(&domain.as_str()))
+        {
+            return Verdict::Deny(domain);
+        }
+        if self.flag_role_accounts && ROLE_ACCOUNTS.contains(&local.as_str()) {
+            return Verdict::RoleAccount;
+        }
+        Verdict::Pass
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+
+    use super::{MailPolicy, Verdict};
+    use crate::Uri;
+
+    fn mail(address: &str) -> Uri {
+        Uri::try_from(address).unwrap()
+    }
+
+    #[test]
+    fn test_mail_policy() {
+        let policy = MailPolicy {
+            allowed_domains: vec!["example.com".to_owned()].into_iter().collect(),
+            denied_domains: vec!["spam.org".to_owned()].into_iter().collect(),
+            deny_free_mail: true,
+            flag_role_accounts: true,
+            tags: vec![],
+        };
+
+        assert_eq!(
+            policy.evaluate(&mail("jane@example.org"), &[]),
+            Verdict::Pass
+        );
+        assert_eq!(
+            policy.evaluate(&mail("info@example.com"), &[]),
+            Verdict::Pass
+        );
+        assert_eq!(
+            policy.evaluate(&mail("jane@Gmail.com"), &[]),
+            Verdict::Deny("gmail.com".to_owned())
+        );
+        assert_eq!(
+            policy.evaluate(&mail("jane@spam.org"), &[]),
+            Verdict::Deny("spam.org".to_owned())
+        );
+        assert_eq!(
+            policy.evaluate(&mail("info@example.org"), &[]),
+            Verdict::RoleAccount
+        );
+    }
+
+    #[test]
+    fn test_mail_policy_tags() {
+        let policy = MailPolicy {
+            deny_free_mail: true,
+            tags: vec!["docs".to_owned()],
+            ..MailPolicy::default()
+        };
+
+        assert_eq!(policy.evaluate(&mail("jane@gmail.com"), &[]), Verdict::Pass);
+        assert_eq!(
+            policy.evaluate(&mail("jane@gmail.com"), &["docs".to_owned()]),
+            Verdict::Deny("gmail.com".to_owned())
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use mailparse::{parse_mail, MailParseError, ParsedMail};
+
+use crate::{extract::FileType, ErrorKind, Input, InputContent, Result};
+
+/// Whether the file at `path` is a mail message (`.eml`) or a mailbox (`.mbox`)
+pub(crate) fn is_mailbox(path: &Path) -> bool {
+    path.extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .map_or(false, |extension| {
+            extension.eq_ignore_ascii_case("eml") || extension.eq_ignore_ascii_case("mbox")
+        })
+}
+
+/// The decoded text and HTML bodies of all messages in a mail file
+pub(crate) async fn path_contents(path: &Path) -> Result<Vec<InputContent>> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| (path.to_path_buf(), e))?;
+    let mut bodies = Vec::new();
+    for message in messages(&bytes) {
+        parse_mail(message)
+            .and_then(|mail| collect_bodies(&mail, &mut bodies))
+            .map_err(|e| ErrorKind::InvalidMailbox(path.to_path_buf(), e.to_string()))?;
+    }
+    Ok(bodies
+        .into_iter()
+        .map(|(file_type, content)| InputContent {
+            input: Input::FsPath(path.to_path_buf()),
+            file_type,
+            content,
+        })
+        .collect())
+}
+
+/// The messages of a mailbox in mbox format, without their `From ` separator
+/// lines. Anything else is a single message.
+fn messages(bytes: &[u8]) -> Vec<&[u8]> {
+    if !bytes.starts_with(b"From ") {
+        return vec![bytes];
+    }
+    let mut starts = vec![0];
+    starts.extend(
+        bytes
+            .windows(6)
+            .enumerate()
+            .filter(|(_, window)| *window == b"\nFrom ")
+            .map(|(i, _)| i + 1),
+    );
+    let ends = starts.iter().skip(1).copied().chain(Some(bytes.len()));
+    starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| {
+            let message = &bytes[start..end];
+            let headers = message
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(message.len(), |i| i + 1);
+            &message[headers..]
+        })
+        .collect()
+}
+
+/// Decode the text and HTML parts of a message, including the ones of
+/// attached messages (e.g. forwarded newsletters)
+fn collect_bodies(
+    mail: &ParsedMail,
+    bodies: &mut Vec<(FileType, String)>,
+) -> std::result::Result<(), MailParseError> {
+    for part in &mail.subparts {
+        collect_bodies(part, bodies)?;
+    }
+    match mail.ctype.mimetype.as_str() {
+        "text/html" => bodies.push((FileType::Html, mail.get_body()?)),
+        "text/plain" => bodies.push((FileType::Plaintext, mail.get_body()?)),
+        "message/rfc822" => collect_bodies(&parse_mail(&mail.get_body_raw()?)?, bodies)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    use super::{is_mailbox, messages};
+    use crate::{test_utils::website, Collector, Input, Result};
+
+    const NEWSLETTER: &str = "\
+From: news@example.org
+To: reader@example.org
+Subject: Launch
+MIME-Version: 1.0
+Content-Type: multipart/alternative; boundary=\"XYZ\"
+
+--XYZ
+Content-Type: text/plain; charset=utf-8
+Content-Transfer-Encoding: quoted-printable
+
+Our launch is live: https://example.org/pl=
+ain
+--XYZ
+Content-Type: text/html; charset=utf-8
+Content-Transfer-Encoding: base64
+
+PHA+T3VyIDxhIGhyZWY9Imh0dHBzOi8vZXhhbXBsZS5vcmcvaHRtbCI+bGF1bmNoPC9hPiBpcyBs
+aXZlITwvcD4=
+--XYZ--
+";
+
+    #[test]
+    fn test_is_mailbox() {
+        assert!(is_mailbox(Path::new("campaigns/launch.eml")));
+        assert!(is_mailbox(Path::new("archive.MBOX")));
+        assert!(!is_mailbox(Path::new("README.md")));
+    }
+
+    #[test]
+    fn test_messages() {
+        let mbox = b"From a@example.org Mon Jan  1 00:00:00 2021\nSubject: A\n\n\
+                     First\n\nFrom b@example.org Tue Jan  2 00:00:00 2021\nSubject: B\n\n\
+                     >From the archive\n";
+        let split = messages(mbox);
+        assert_eq!(split.len(), 2);
+        assert_eq!(split[0], &b"Subject: A\n\nFirst\n\n"[..]);
+        assert_eq!(split[1], &b"Subject: B\n\n>From the archive\n"[..]);
+
+        assert_eq!(messages(b"Subject: C\n\nBody\n").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mailbox_links() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let eml = temp_dir.path().join("launch.eml");
+        fs::write(&eml, NEWSLETTER)?;
+        let mbox = temp_dir.path().join("archive.mbox");
+        fs::write(
+            &mbox,
+            format!(
+                "From news@example.org Mon Jan  1 00:00:00 2021\n{}\n\
+                 From news@example.org Tue Jan  2 00:00:00 2021\n\
+                 Subject: Follow-up\n\nSee https://example.org/follow-up\n",
+                NEWSLETTER
+            ),
+        )?;
+
+        let links = Collector::default()
+            .collect_links(&[Input::FsPath(eml)])
+            .await?;
+        let mut uris: Vec<_> = links.into_iter().map(|request| request.uri).collect();
+        uris.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        assert_eq!(
+            uris,
+            vec![
+                website("https://example.org/html"),
+                website("https://example.org/plain")
+            ]
+        );
+
+        let links = Collector::default()
+            .collect_links(&[Input::FsPath(mbox)])
+            .await?;
+        assert_eq!(links.len(), 3);
+        Ok(())
+    }
+}
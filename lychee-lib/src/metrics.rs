@@ -0,0 +1,322 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{Observer, Request, Response, Status, Uri};
+
+/// Upper bounds of the buckets of the check duration histogram, in seconds
+const BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Maximum size of the head of a request to `/metrics`
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// Metrics of the checks of a [`Client`](crate::ClientBuilder) in the
+/// `OpenMetrics` text format, which Prometheus can scrape, e.g. to run lychee
+/// as a continuous link monitor. Register it as the
+/// [observer](crate::ClientBuilder::observer) of the client.
+///
+/// * `lychee_checks_total`: checked links by status
+/// * `lychee_retries_total`: retried checks
+/// * `lychee_in_flight_checks`: checks which are running
+/// * `lychee_check_duration_seconds`: histogram of the check durations per host
+///
+/// ```
+/// use lychee_lib::{metrics::Metrics, ClientBuilder, Observer};
+/// use std::sync::Arc;
+///
+/// let metrics = Arc::new(Metrics::default());
+/// let client = ClientBuilder::builder()
+///     .observer(metrics.clone() as Arc<dyn Observer>)
+///     .build()
+///     .client();
+/// assert!(metrics.render().ends_with("# EOF\n"));
+/// ```
+#[derive(Debug, Default)]
+pub struct Metrics {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    checks: BTreeMap<&'static str, u64>,
+    retries: u64,
+    in_flight: u64,
+    durations: BTreeMap<String, Histogram>,
+}
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Cumulative counts of the buckets in [`BUCKETS`]
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: Duration,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, le) in self.buckets.iter_mut().zip(&BUCKETS) {
+            if seconds <= *le {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += duration;
+    }
+}
+
+impl Metrics {
+    /// The state, which is still consistent if a thread panicked while
+    /// holding the lock, as every update is a single counter change
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The current metrics in the `OpenMetrics` text format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let state = self.state();
+        let mut out = String::new();
+        // Writing to a string can't fail
+        let _ = writeln!(out, "# TYPE lychee_checks counter");
+        let _ = writeln!(out, "# HELP lychee_checks Checked links by status");
+        for (status, count) in &state.checks {
+            let _ = writeln!(
+                out,
+                "lychee_checks_total{{status=\"{}\"}} {}",
+                status, count
+            );
+        }
+        let _ = writeln!(out, "# TYPE lychee_retries counter");
+        let _ = writeln!(out, "# HELP lychee_retries Retried checks");
+        let _ = writeln!(out, "lychee_retries_total {}", state.retries);
+        let _ = writeln!(out, "# TYPE lychee_in_flight_checks gauge");
+        let _ = writeln!(out, "# HELP lychee_in_flight_checks Running checks");
+        let _ = writeln!(out, "lychee_in_flight_checks {}", state.in_flight);
+        let _ = writeln!(out, "# TYPE lychee_check_duration_seconds histogram");
+        let _ = writeln!(
+            out,
+            "# HELP lychee_check_duration_seconds Duration of the checks by host"
+        );
+        for (host, histogram) in &state.durations {
+            let name = "lychee_check_duration_seconds";
+            for (count, le) in histogram.buckets.iter().zip(&BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{{host=\"{}\",le=\"{}\"}} {}",
+                    name, host, le, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{{host=\"{}\",le=\"+Inf\"}} {}",
+                name, host, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{}_count{{host=\"{}\"}} {}",
+                name, host, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{}_sum{{host=\"{}\"}} {}",
+                name,
+                host,
+                histogram.sum.as_secs_f64()
+            );
+        }
+        out.push_str("# EOF\n");
+        out
+    }
+
+    /// Serve the metrics at `/metrics` to every connection to `listener`,
+    /// until the future gets dropped. Other paths get a 404.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no connection can be accepted anymore
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+        loop {
+            let (mut stream, _) = listener.accept().await?;
+            let metrics = self.clone();
+            tokio::spawn(async move {
+                let head = read_head(&mut stream).await;
+                let request = String::from_utf8_lossy(&head);
+                let response = if request.starts_with("GET /metrics ") {
+                    let body = metrics.render();
+                    format!(
+                        "HTTP/1.1 200 OK\r\n\
+                         Content-Type: application/openmetrics-text; version=1.0.0; charset=utf-8\r\n\
+                         Content-Length: {}\r\n\
+                         Connection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                } else {
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_owned()
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    }
+}
+
+/// Read the request line and the headers of a request, which may arrive
+/// in several segments. Stops at the end of the headers, at the end of the
+/// stream or after [`MAX_HEAD_SIZE`] bytes.
+async fn read_head(stream: &mut TcpStream) -> Vec<u8> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    while head.len() < MAX_HEAD_SIZE && !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(read) => head.extend_from_slice(&buffer[..read]),
+        }
+    }
+    head
+}
+
+impl Observer for Metrics {
+    fn on_request_started(&self, _request: &Request) {
+        self.state().in_flight += 1;
+    }
+
+    fn on_retry(&self, _uri: &Uri, _attempt: usize, _status: &Status) {
+        self.state().retries += 1;
+    }
+
+    fn on_response(&self, response: &Response) {
+        let Response(_, body) = response;
+        let mut state = self.state();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        *state.checks.entry(label(&body.status)).or_default() += 1;
+        if let (Some(host), Some(duration)) = (body.uri.url.host_str(), body.duration) {
+            state
+                .durations
+                .entry(host.to_owned())
+                .or_default()
+                .observe(duration);
+        }
+    }
+}
+
+/// The label of a status in the metrics
+const fn label(status: &Status) -> &'static str {
+    match status {
+        Status::Ok(_) => "ok",
+        Status::Redirected(_) => "redirected",
+        Status::Excluded => "excluded",
+        Status::Error(_) => "failed",
+        Status::Timeout(_) => "timeout",
+        Status::Unsupported(_) => "unsupported",
+        Status::ProtectedByBotCheck(_) => "bot_check",
+        Status::Warning(_) => "warning",
+        Status::Skipped(_) => "skipped",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::StatusCode;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::Metrics;
+    use crate::{
+        test_utils::{response, website},
+        Observer, Response, Status,
+    };
+
+    fn timed(url: &str, status: Status, millis: u64) -> Response {
+        let mut timed = response(url, status);
+        timed.1.duration = Some(Duration::from_millis(millis));
+        timed
+    }
+
+    fn metrics() -> Metrics {
+        let metrics = Metrics::default();
+        let ok = || Status::Ok(StatusCode::OK);
+        metrics.on_response(&timed("https://example.org/a", ok(), 80));
+        metrics.on_response(&timed("https://example.org/b", ok(), 300));
+        metrics.on_retry(&website("https://example.com/"), 1, &Status::Timeout(None));
+        metrics.on_response(&timed(
+            "https://example.com/",
+            Status::Timeout(None),
+            20_000,
+        ));
+        metrics
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = metrics().render();
+        for line in &[
+            "lychee_checks_total{status=\"ok\"} 2",
+            "lychee_checks_total{status=\"timeout\"} 1",
+            "lychee_retries_total 1",
+            "lychee_in_flight_checks 0",
+            "lychee_check_duration_seconds_bucket{host=\"example.org\",le=\"0.05\"} 0",
+            "lychee_check_duration_seconds_bucket{host=\"example.org\",le=\"0.1\"} 1",
+            "lychee_check_duration_seconds_bucket{host=\"example.org\",le=\"0.5\"} 2",
+            "lychee_check_duration_seconds_bucket{host=\"example.com\",le=\"10\"} 0",
+            "lychee_check_duration_seconds_bucket{host=\"example.com\",le=\"+Inf\"} 1",
+            "lychee_check_duration_seconds_count{host=\"example.org\"} 2",
+            "lychee_check_duration_seconds_sum{host=\"example.org\"} 0.38",
+        ] {
+            assert!(rendered.lines().any(|l| l == *line), "missing {}", line);
+        }
+        assert!(rendered.ends_with("# EOF\n"));
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(std::sync::Arc::new(metrics()).serve(listener));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("lychee_retries_total 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_split_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(std::sync::Arc::new(metrics()).serve(listener));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.set_nodelay(true).unwrap();
+        for segment in &[
+            "GE",
+            "T /metr",
+            "ics HTTP/1.1\r\nHost: localhost\r\n",
+            "\r\n",
+        ] {
+            stream.write_all(segment.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
@@ -0,0 +1,41 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{Request, Response, Status, Uri};
+
+/// Hooks which get called by the [`Client`](crate::ClientBuilder) while
+/// checking links, e.g. to drive a progress bar or to log progress.
+///
+/// All methods have empty default implementations,
+/// so only the relevant ones need to be implemented.
+pub trait Observer: Debug + Send + Sync {
+    /// Called before a request gets checked
+    fn on_request_started(&self, _request: &Request) {}
+
+    /// Called before an unsuccessful check is retried.
+    /// `attempt` is the number of the upcoming retry, starting at 1.
+    fn on_retry(&self, _uri: &Uri, _attempt: usize, _status: &Status) {}
+
+    /// Called once the response for a request is available
+    fn on_response(&self, _response: &Response) {}
+}
+
+/// Notifies several observers in order, e.g. an event log and metrics
+impl Observer for Vec<Arc<dyn Observer>> {
+    fn on_request_started(&self, request: &Request) {
+        for observer in self {
+            observer.on_request_started(request);
+        }
+    }
+
+    fn on_retry(&self, uri: &Uri, attempt: usize, status: &Status) {
+        for observer in self {
+            observer.on_retry(uri, attempt, status);
+        }
+    }
+
+    fn on_response(&self, response: &Response) {
+        for observer in self {
+            observer.on_response(response);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use http::header;
+use serde::Deserialize;
+
+use crate::{ErrorKind, Status, Uri};
+
+/// Abbreviated package metadata of npm, which is a lot smaller than the full document
+const NPM_ABBREVIATED: &str = "application/vnd.npm.install-v1+json";
+
+/// A package registry with a JSON API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Registry {
+    CratesIo,
+    PyPi,
+    Npm,
+}
+
+/// A link to a package, and optionally one of its versions,
+/// on the page of a package registry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PackageLink<'a> {
+    registry: Registry,
+    name: String,
+    version: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct NpmPackage {
+    versions: HashMap<String, serde_json::Value>,
+}
+
+impl<'a> PackageLink<'a> {
+    /// Parse a link to the page of a package:
+    ///
+    /// - `crates.io/crates/<name>[/<version>]`
+    /// - `pypi.org/project/<name>[/<version>]`
+    /// - `npmjs.com/package/[@<scope>/]<name>[/v/<version>]`
+    ///
+    /// Returns `None` for any other URI.
+    pub(crate) fn parse(uri: &'a Uri) -> Option<Self> {
+        let segments: Vec<&str> = uri.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let domain = uri.domain()?.trim_start_matches("www.");
+        let (registry, name, version) = match (domain, segments.as_slice()) {
+            ("crates.io", ["crates", name, rest @ ..]) => {
+                (Registry::CratesIo, (*name).to_string(), version(rest))
+            }
+            ("pypi.org", ["project", name, rest @ ..]) => {
+                (Registry::PyPi, (*name).to_string(), version(rest))
+            }
+            ("npmjs.com", ["package", scope, name, rest @ ..]) if scope.starts_with('@') => (
+                Registry::Npm,
+                format!("{}/{}", scope, name),
+                npm_version(rest),
+            ),
+            ("npmjs.com", ["package", name, rest @ ..]) => {
+                (Registry::Npm, (*name).to_string(), npm_version(rest))
+            }
+            _ => return None,
+        };
+        Some(Self {
+            registry,
+            name,
+            version,
+        })
+    }
+
+    /// Endpoint of the registry API which describes the package or version
+    fn api_url(&self) -> String {
+        match (self.registry, self.version) {
+            (Registry::CratesIo, None) => format!("https://crates.io/api/v1/crates/{}", self.name),
+            (Registry::CratesIo, Some(version)) => {
+                format!("https://crates.io/api/v1/crates/{}/{}", self.name, version)
+            }
+            (Registry::PyPi, None) => format!("https://pypi.org/pypi/{}/json", self.name),
+            (Registry::PyPi, Some(version)) => {
+                format!("https://pypi.org/pypi/{}/{}/json", self.name, version)
+            }
+            // Versions of scoped packages have no endpoint of their own,
+            // so they are looked up in the package document
+            (Registry::Npm, _) => format!("https://registry.npmjs.org/{}", self.name),
+        }
+    }
+
+    /// Check the package via the API of its registry
+    pub(crate) async fn check(&self, client: &reqwest::Client) -> Status {
+        let accept = match self.registry {
+            Registry::Npm => NPM_ABBREVIATED,
+            Registry::CratesIo | Registry::PyPi => "application/json",
+        };
+        let response = match client
+            .get(self.api_url())
+            .header(header::ACCEPT, accept)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return e.into(),
+        };
+        let status = Status::new(&response, None);
+        match (self.registry, self.version) {
+            (Registry::Npm, Some(version)) if status.is_success() => {
+                let package = match response.text().await {
+                    Ok(body) => serde_json::from_str::<NpmPackage>(&body).ok(),
+                    Err(e) => return e.into(),
+                };
+                match package {
+                    Some(package) if package.versions.contains_key(version) => status,
+                    _ => ErrorKind::MissingPackageVersion(version.to_string()).into(),
+                }
+            }
+            _ => status,
+        }
+    }
+}
+
+/// Version in the path segments after the package name.
+/// Other pages of a package, like its dependencies, don't start with a digit.
+fn version<'a>(rest: &[&'a str]) -> Option<&'a str> {
+    rest.first()
+        .copied()
+        .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+/// Version in the path segments after the name of an npm package (`/v/<version>`)
+fn npm_version<'a>(rest: &[&'a str]) -> Option<&'a str> {
+    match *rest {
+        ["v", version, ..] => Some(version),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PackageLink, Registry};
+    use crate::test_utils::website;
+
+    #[test]
+    fn test_parse() {
+        let cases = [
+            (
+                "https://crates.io/crates/lychee",
+                Some((Registry::CratesIo, "lychee", None)),
+            ),
+            (
+                "https://crates.io/crates/lychee/0.7.0",
+                Some((Registry::CratesIo, "lychee", Some("0.7.0"))),
+            ),
+            (
+                "https://crates.io/crates/lychee/reverse_dependencies",
+                Some((Registry::CratesIo, "lychee", None)),
+            ),
+            (
+                "https://pypi.org/project/requests/2.25.1/",
+                Some((Registry::PyPi, "requests", Some("2.25.1"))),
+            ),
+            (
+                "https://www.npmjs.com/package/@types/node/v/15.12.2",
+                Some((Registry::Npm, "@types/node", Some("15.12.2"))),
+            ),
+            (
+                "https://www.npmjs.com/package/react",
+                Some((Registry::Npm, "react", None)),
+            ),
+            ("https://crates.io/search?q=lychee", None),
+            ("https://pypi.org/", None),
+        ];
+        for (uri, expected) in &cases {
+            let uri = website(uri);
+            let expected = expected.map(|(registry, name, version)| PackageLink {
+                registry,
+                name: name.to_string(),
+                version,
+            });
+            assert_eq!(PackageLink::parse(&uri), expected, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn test_api_url() {
+        let uri = website("https://crates.io/crates/lychee/0.7.0");
+        assert_eq!(
+            PackageLink::parse(&uri).unwrap().api_url(),
+            "https://crates.io/api/v1/crates/lychee/0.7.0"
+        );
+        let uri = website("https://pypi.org/project/requests");
+        assert_eq!(
+            PackageLink::parse(&uri).unwrap().api_url(),
+            "https://pypi.org/pypi/requests/json"
+        );
+        let uri = website("https://www.npmjs.com/package/@types/node/v/15.12.2");
+        assert_eq!(
+            PackageLink::parse(&uri).unwrap().api_url(),
+            "https://registry.npmjs.org/@types/node"
+        );
+    }
+}
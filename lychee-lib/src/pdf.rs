@@ -0,0 +1,173 @@
+use std::path::{Path, PathBuf};
+
+use lopdf::{Dictionary, Document, Object};
+use reqwest::Url;
+
+use crate::{extract::FileType, ErrorKind, Input, InputContent, Result};
+
+/// Whether the file at `path` is a PDF document, judging by its extension
+pub(crate) fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension.eq_ignore_ascii_case("pdf"))
+}
+
+/// Read a PDF file as plain text to extract links from
+pub(crate) async fn path_content(path: PathBuf) -> Result<InputContent> {
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| (path.clone(), e))?;
+    let content = text(&bytes).map_err(|e| ErrorKind::InvalidPdf(Some(path.clone()), e))?;
+    Ok(InputContent {
+        input: Input::FsPath(path),
+        file_type: FileType::Plaintext,
+        content,
+    })
+}
+
+/// Read a downloaded PDF document as plain text to extract links from
+pub(crate) fn url_content(url: &Url, bytes: &[u8]) -> Result<InputContent> {
+    let content = text(bytes).map_err(|e| ErrorKind::InvalidPdf(None, e))?;
+    Ok(InputContent {
+        input: Input::RemoteUrl(Box::new(url.clone())),
+        file_type: FileType::Plaintext,
+        content,
+    })
+}
+
+/// The targets of all URI actions (e.g. of link annotations), one per line,
+/// followed by the text of all pages
+fn text(bytes: &[u8]) -> std::result::Result<String, String> {
+    let document = Document::load_mem(bytes).map_err(|e| e.to_string())?;
+    let mut text = String::new();
+    for object in document.objects.values() {
+        uri_actions(object, &mut text);
+    }
+    let pages: Vec<u32> = document.get_pages().keys().copied().collect();
+    // Links of pages with fonts which can't be decoded are still found
+    // in their annotations
+    if let Ok(pages) = document.extract_text(&pages) {
+        text.push_str(&pages);
+    }
+    Ok(text)
+}
+
+/// Append the targets of the URI actions within an object to `text`.
+/// Actions are often direct objects of annotations, so nested objects
+/// are searched as well.
+fn uri_actions(object: &Object, text: &mut String) {
+    match object {
+        Object::Dictionary(dictionary) => dictionary_uri_actions(dictionary, text),
+        Object::Stream(stream) => dictionary_uri_actions(&stream.dict, text),
+        Object::Array(objects) => {
+            for object in objects {
+                uri_actions(object, text);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn dictionary_uri_actions(dictionary: &Dictionary, text: &mut String) {
+    let is_uri_action = dictionary
+        .get(b"S")
+        .and_then(Object::as_name)
+        .map_or(false, |name| name == b"URI");
+    if is_uri_action {
+        if let Ok(Object::String(uri, _)) = dictionary.get(b"URI") {
+            text.push_str(&String::from_utf8_lossy(uri));
+            text.push('\n');
+        }
+    }
+    for (_, value) in dictionary.iter() {
+        uri_actions(value, text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use lopdf::{
+        content::{Content, Operation},
+        dictionary, Document, Object, Stream,
+    };
+
+    use super::{is_pdf, text};
+
+    /// A single page document with a link annotation and a URL in its text
+    fn document() -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let content = Content {
+            operations: vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                Operation::new("Td", vec![100.into(), 600.into()]),
+                Operation::new(
+                    "Tj",
+                    vec![Object::string_literal("See https://example.org/text")],
+                ),
+                Operation::new("ET", vec![]),
+            ],
+        };
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+        let link_id = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![0.into(), 0.into(), 100.into(), 20.into()],
+            "A" => dictionary! {
+                "S" => "URI",
+                "URI" => Object::string_literal("https://example.org/annotation"),
+            },
+        });
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "Resources" => dictionary! { "Font" => dictionary! { "F1" => font_id } },
+            "Annots" => vec![link_id.into()],
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_text() {
+        let text = text(&document()).unwrap();
+        assert!(text.starts_with("https://example.org/annotation\n"));
+        assert!(text.contains("https://example.org/text"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(text(b"not a pdf").is_err());
+    }
+
+    #[test]
+    fn test_is_pdf() {
+        assert!(is_pdf(Path::new("docs/whitepaper.pdf")));
+        assert!(is_pdf(Path::new("MANUAL.PDF")));
+        assert!(!is_pdf(Path::new("README.md")));
+    }
+}
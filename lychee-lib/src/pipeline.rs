@@ -0,0 +1,493 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::Url;
+
+use crate::{client::Client, filter::Decision, ErrorKind, ResponseBody, Status, Uri};
+
+/// A request on its way through the check [`Pipeline`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckRequest {
+    /// The URI to check
+    pub uri: Uri,
+    /// Tags of the input the URI was found in
+    pub tags: Vec<String>,
+    /// Rewrite requests to sites which need special treatment (e.g. Twitter).
+    /// Enabled by the `quirks` stage.
+    pub apply_quirks: bool,
+    /// Run the post-processors on the final response.
+    /// Enabled by the `post_process` stage.
+    pub post_process: bool,
+}
+
+impl CheckRequest {
+    /// Create a new request, with quirks and post-processing disabled
+    #[must_use]
+    pub const fn new(uri: Uri, tags: Vec<String>) -> Self {
+        Self {
+            uri,
+            tags,
+            apply_quirks: false,
+            post_process: false,
+        }
+    }
+}
+
+/// Future returned by [`Stage::run`]
+pub type StageFuture<'a> = Pin<Box<dyn Future<Output = ResponseBody> + Send + 'a>>;
+
+/// A single step of checking a request.
+///
+/// Each stage can either answer the request itself (e.g. for excluded URIs)
+/// or modify it and pass it on to the [`Next`] stage. Once all stages ran,
+/// the website gets requested over the network and its response is classified.
+pub trait Stage: Debug + Send + Sync {
+    /// Unique name of the stage, used to insert or remove stages relative to it
+    fn name(&self) -> &str;
+
+    /// Handle the request, usually by calling `next.run(request)`
+    fn run<'a>(&'a self, request: CheckRequest, next: Next<'a>) -> StageFuture<'a>;
+}
+
+/// The remaining stages of a [`Pipeline`]
+#[derive(Debug, Clone, Copy)]
+pub struct Next<'a> {
+    client: &'a Client,
+    stages: &'a [Arc<dyn Stage>],
+}
+
+impl<'a> Next<'a> {
+    /// Start running the stages of the pipeline
+    pub(crate) fn new(client: &'a Client, pipeline: &'a Pipeline) -> Self {
+        Self {
+            client,
+            stages: &pipeline.stages,
+        }
+    }
+
+    /// The client which runs the pipeline
+    #[must_use]
+    pub const fn client(&self) -> &'a Client {
+        self.client
+    }
+
+    /// Pass the request on to the next stage.
+    /// After the last stage, the website gets checked.
+    pub async fn run(self, request: CheckRequest) -> ResponseBody {
+        match self.stages.split_first() {
+            Some((stage, stages)) => {
+                let next = Next {
+                    client: self.client,
+                    stages,
+                };
+                stage.run(request, next).await
+            }
+            None => self.client.check_website_request(&request).await,
+        }
+    }
+}
+
+/// Ordered list of stages every request passes through while being checked.
+///
+/// The default pipeline consists of `filter` → `mail` → `quirks` →
+/// `post_process`, followed by the network request and its classification.
+/// Stages can be added or removed, e.g. to add a [`CacheStage`] or a
+/// [`RemapStage`], or to skip the quirks for known sites.
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    stages: Vec<Arc<dyn Stage>>,
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Self::empty()
+            .push(FilterStage)
+            .push(MailStage)
+            .push(QuirksStage)
+            .push(PostProcessStage)
+    }
+}
+
+impl Pipeline {
+    /// Create a pipeline without any stages,
+    /// which only requests the website and classifies the response
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self { stages: Vec::new() }
+    }
+
+    /// Names of the stages, in order
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// Append a stage at the end of the pipeline
+    #[must_use]
+    pub fn push<S: Stage + 'static>(mut self, stage: S) -> Self {
+        self.stages.push(Arc::new(stage));
+        self
+    }
+
+    /// Insert a stage before the stage with the given name,
+    /// or at the end if there is no such stage
+    #[must_use]
+    pub fn insert_before<S: Stage + 'static>(mut self, name: &str, stage: S) -> Self {
+        let index = self.position(name).unwrap_or(self.stages.len());
+        self.stages.insert(index, Arc::new(stage));
+        self
+    }
+
+    /// Insert a stage after the stage with the given name,
+    /// or at the end if there is no such stage
+    #[must_use]
+    pub fn insert_after<S: Stage + 'static>(mut self, name: &str, stage: S) -> Self {
+        let index = self.position(name).map_or(self.stages.len(), |i| i + 1);
+        self.stages.insert(index, Arc::new(stage));
+        self
+    }
+
+    /// Remove all stages with the given name
+    #[must_use]
+    pub fn remove(mut self, name: &str) -> Self {
+        self.stages.retain(|stage| stage.name() != name);
+        self
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.stages.iter().position(|stage| stage.name() == name)
+    }
+}
+
+/// Answers requests for excluded URIs and URIs violating the approved hosts policy.
+/// Templated URIs (e.g. with `{id}`) are tagged with `template`.
+/// Hosts are resolved here if the excludes depend on their addresses.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FilterStage;
+
+impl Stage for FilterStage {
+    fn name(&self) -> &str {
+        "filter"
+    }
+
+    fn run<'a>(&'a self, request: CheckRequest, next: Next<'a>) -> StageFuture<'a> {
+        Box::pin(async move {
+            let decision = next.client.filter().explain(&request.uri);
+            if decision.is_excluded() {
+                let mut response = ResponseBody::new(request.uri, Status::Excluded);
+                if let Decision::Template { .. } = decision {
+                    response.tags.push("template".to_owned());
+                }
+                return response;
+            }
+            if next
+                .client
+                .resolved_host_decision(&request.uri)
+                .await
+                .is_some()
+            {
+                return ResponseBody::new(request.uri, Status::Excluded);
+            }
+            if let Some(host) = next.client.unapproved_host(&request.uri) {
+                return ResponseBody::new(request.uri, ErrorKind::HostNotAllowed(host).into());
+            }
+            next.run(request).await
+        })
+    }
+}
+
+/// Checks mail addresses, applying the mail policy
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MailStage;
+
+impl Stage for MailStage {
+    fn name(&self) -> &str {
+        "mail"
+    }
+
+    fn run<'a>(&'a self, request: CheckRequest, next: Next<'a>) -> StageFuture<'a> {
+        Box::pin(async move {
+            if request.uri.is_mail() {
+                next.client
+                    .check_mail_address(request.uri, &request.tags)
+                    .await
+            } else {
+                next.run(request).await
+            }
+        })
+    }
+}
+
+/// Enables the rewriting of requests to sites which need special treatment
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QuirksStage;
+
+impl Stage for QuirksStage {
+    fn name(&self) -> &str {
+        "quirks"
+    }
+
+    fn run<'a>(&'a self, mut request: CheckRequest, next: Next<'a>) -> StageFuture<'a> {
+        request.apply_quirks = true;
+        Box::pin(next.run(request))
+    }
+}
+
+/// Enables the post-processors (soft-404 detection, expected content, ...)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PostProcessStage;
+
+impl Stage for PostProcessStage {
+    fn name(&self) -> &str {
+        "post_process"
+    }
+
+    fn run<'a>(&'a self, mut request: CheckRequest, next: Next<'a>) -> StageFuture<'a> {
+        request.post_process = true;
+        Box::pin(next.run(request))
+    }
+}
+
+/// Replaces the beginning of a URL, e.g. to check links to a production
+/// site against a local build
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remap {
+    /// Prefix of the URLs to rewrite, e.g. `https://docs.example.com/`
+    pub from: String,
+    /// Replacement of the prefix, e.g. `http://localhost:8000/`
+    pub to: String,
+}
+
+impl Remap {
+    /// Rewrite URLs starting with `from` to start with `to` instead
+    #[must_use]
+    pub fn new<F: Into<String>, T: Into<String>>(from: F, to: T) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    /// The rewritten URL, if it starts with the prefix
+    #[must_use]
+    pub fn apply(&self, url: &Url) -> Option<Url> {
+        let rest = url.as_str().strip_prefix(&self.from)?;
+        Url::parse(&format!("{}{}", self.to, rest)).ok()
+    }
+
+    /// The rule which undoes this one
+    #[must_use]
+    pub fn reverse(&self) -> Self {
+        Self::new(&self.to, &self.from)
+    }
+}
+
+/// Checks links at rewritten URLs, but reports them with their original URL.
+/// The first matching [`Remap`] applies. Insert it after the `filter` stage,
+/// so includes and excludes match the original URLs.
+#[derive(Debug, Clone, Default)]
+pub struct RemapStage {
+    remaps: Vec<Remap>,
+}
+
+impl RemapStage {
+    /// Create a stage which applies the given rules
+    #[must_use]
+    pub const fn new(remaps: Vec<Remap>) -> Self {
+        Self { remaps }
+    }
+}
+
+impl Stage for RemapStage {
+    fn name(&self) -> &str {
+        "remap"
+    }
+
+    fn run<'a>(&'a self, request: CheckRequest, next: Next<'a>) -> StageFuture<'a> {
+        Box::pin(async move {
+            let remapped = self
+                .remaps
+                .iter()
+                .find_map(|remap| Some((remap, remap.apply(&request.uri.url)?)));
+            let (remap, url) = match remapped {
+                Some(remapped) => remapped,
+                None => return next.run(request).await,
+            };
+            let original = request.uri.clone();
+            let mut response = next
+                .run(CheckRequest {
+                    uri: Uri::from(url),
+                    ..request
+                })
+                .await;
+            response.uri = original;
+            // Suggest the final URL of redirects within the remapped site
+            // with the original prefix
+            let reverse = remap.reverse();
+            response.replacement = response
+                .replacement
+                .map(|uri| reverse.apply(&uri.url).map_or(uri, Uri::from));
+            response
+        })
+    }
+}
+
+/// Remembers successful responses, so URIs which occur in several inputs
+/// only get checked once. Failures are always checked again.
+#[derive(Debug, Clone, Default)]
+pub struct CacheStage {
+    cache: Arc<Mutex<HashMap<Uri, ResponseBody>>>,
+}
+
+impl Stage for CacheStage {
+    fn name(&self) -> &str {
+        "cache"
+    }
+
+    fn run<'a>(&'a self, request: CheckRequest, next: Next<'a>) -> StageFuture<'a> {
+        Box::pin(async move {
+            let cached = self
+                .cache
+                .lock()
+                .unwrap()
+                .get(&request.uri)
+                .and_then(ResponseBody::try_clone);
+            if let Some(cached) = cached {
+                return cached;
+            }
+            let response = next.run(request).await;
+            if let Some(copy) = response.try_clone() {
+                self.cache.lock().unwrap().insert(copy.uri.clone(), copy);
+            }
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use http::StatusCode;
+    use reqwest::Url;
+
+    use super::{CacheStage, CheckRequest, Next, Pipeline, Remap, RemapStage, Stage, StageFuture};
+    use crate::{mock_server, ClientBuilder, ResponseBody, Status};
+
+    /// Answers every request with a 200 without touching the network
+    #[derive(Debug, Clone, Default)]
+    struct Offline(Arc<AtomicUsize>);
+
+    impl Stage for Offline {
+        fn name(&self) -> &str {
+            "offline"
+        }
+
+        fn run<'a>(&'a self, request: CheckRequest, _next: Next<'a>) -> StageFuture<'a> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move { ResponseBody::new(request.uri, Status::Ok(StatusCode::OK)) })
+        }
+    }
+
+    #[test]
+    fn test_pipeline_composition() {
+        let pipeline = Pipeline::default();
+        assert_eq!(
+            pipeline.names(),
+            vec!["filter", "mail", "quirks", "post_process"]
+        );
+
+        let pipeline = pipeline
+            .remove("quirks")
+            .insert_after("filter", CacheStage::default())
+            .insert_before("filter", Offline::default())
+            .insert_after("unknown", Offline::default());
+        assert_eq!(
+            pipeline.names(),
+            vec![
+                "offline",
+                "filter",
+                "cache",
+                "mail",
+                "post_process",
+                "offline"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_stage() {
+        let offline = Offline::default();
+        let client = ClientBuilder::builder()
+            .pipeline(Pipeline::default().push(offline.clone()))
+            .build()
+            .client()
+            .unwrap();
+
+        let res = client.check("https://example.invalid").await.unwrap();
+        assert!(res.status().is_success());
+        assert_eq!(offline.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stage() {
+        let offline = Offline::default();
+        let client = ClientBuilder::builder()
+            .pipeline(
+                Pipeline::empty()
+                    .push(CacheStage::default())
+                    .push(offline.clone()),
+            )
+            .build()
+            .client()
+            .unwrap();
+
+        for _ in 0..3 {
+            let res = client.check("https://example.invalid").await.unwrap();
+            assert!(res.status().is_success());
+        }
+        assert_eq!(offline.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_remap() {
+        let remap = Remap::new("https://docs.example.com/", "http://localhost:8000/");
+        let url = Url::parse("https://docs.example.com/guide/?page=2#intro").unwrap();
+        let local = remap.apply(&url).unwrap();
+        assert_eq!(local.as_str(), "http://localhost:8000/guide/?page=2#intro");
+        assert_eq!(remap.reverse().apply(&local), Some(url));
+        assert_eq!(
+            remap.apply(&Url::parse("https://example.com/").unwrap()),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remap_stage() {
+        let mock_server = mock_server!(StatusCode::OK);
+        let remap = Remap::new(
+            "https://docs.example.invalid/",
+            format!("{}/", mock_server.uri()),
+        );
+        let client = ClientBuilder::builder()
+            .pipeline(Pipeline::default().insert_after("filter", RemapStage::new(vec![remap])))
+            .build()
+            .client()
+            .unwrap();
+
+        let res = client
+            .check("https://docs.example.invalid/guide")
+            .await
+            .unwrap();
+        assert!(res.status().is_success());
+        assert_eq!(res.1.uri.as_str(), "https://docs.example.invalid/guide");
+    }
+}
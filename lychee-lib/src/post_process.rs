@@ -0,0 +1,657 @@
+use std::{
+    fmt::Debug,
+    time::{Duration, SystemTime},
+};
+
+use http::{
+    header::{self, HeaderMap, HeaderName},
+    StatusCode,
+};
+use regex::{Regex, RegexSet};
+use reqwest::Url;
+
+use crate::{
+    filter::is_subdomain,
+    redact::{is_sensitive, REDACTED},
+    ErrorKind, ResponseBody, Status,
+};
+
+/// Hosts of well-known domain parking services
+const PARKING_HOSTS: &[&str] = &[
+    "sedoparking.com",
+    "parkingcrew.net",
+    "bodis.com",
+    "above.com",
+    "dan.com",
+    "afternic.com",
+    "hugedomains.com",
+];
+
+/// Typical phrases on parked domains
+const PARKING_PATTERNS: &[&str] = &[
+    r"(?i)this domain (name )?(is|may be) for sale",
+    r"(?i)buy this domain",
+    r"(?i)domain is parked",
+];
+
+/// Hosts (and path prefixes) of well-known single sign-on providers
+const SSO_HOSTS: &[&str] = &[
+    "login.microsoftonline.com",
+    "accounts.google.com",
+    "github.com/login",
+    "okta.com",
+    "auth0.com",
+    "onelogin.com",
+];
+
+/// The final HTTP response of a check, as seen by a [`PostProcessor`]
+#[derive(Debug)]
+pub struct ProcessContext<'a> {
+    /// URL of the final response after following all redirects
+    pub url: &'a Url,
+    /// Headers of the final response
+    pub headers: &'a HeaderMap,
+    /// Body of the final response.
+    /// Only available for successful responses and only if any of the
+    /// post-processors [needs it](PostProcessor::needs_body).
+    pub body: Option<&'a str>,
+}
+
+/// Hook which runs after the status of a website has been classified.
+///
+/// Post-processors can amend the [`ResponseBody`], e.g. to add tags,
+/// downgrade the status or attach suggestions. They run in the order
+/// they were registered with the [`Client`](crate::ClientBuilder).
+pub trait PostProcessor: Debug + Send + Sync {
+    /// Whether the response body needs to be downloaded for this post-processor
+    fn needs_body(&self) -> bool {
+        false
+    }
+
+    /// Inspect and amend the response of a check
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody);
+}
+
+/// Downgrades successful responses which are most likely
+/// "not found" pages in disguise ("soft 404") to errors
+#[derive(Debug, Clone)]
+pub struct Soft404Detector {
+    /// Response bodies matching any of these patterns are "not found" pages
+    pub patterns: Option<RegexSet>,
+    /// Redirects from a deep link to the homepage are "not found" pages
+    pub homepage: bool,
+}
+
+impl PostProcessor for Soft404Detector {
+    fn needs_body(&self) -> bool {
+        self.patterns.is_some()
+    }
+
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        if !response.status.is_success() {
+            return;
+        }
+        if self.homepage
+            && context.url.path() == "/"
+            && response.uri.url.path() != "/"
+            && context.url.host_str() == response.uri.url.host_str()
+        {
+            response.status = ErrorKind::Soft404("redirected to the homepage").into();
+        } else if let (Some(patterns), Some(body)) = (&self.patterns, context.body) {
+            if patterns.is_match(body) {
+                response.status = ErrorKind::Soft404("body matches a \"not found\" pattern").into();
+            }
+        }
+    }
+}
+
+/// Fails successful responses whose body doesn't match the expected content
+#[derive(Debug, Clone)]
+pub struct ExpectedContent(pub Regex);
+
+impl PostProcessor for ExpectedContent {
+    fn needs_body(&self) -> bool {
+        true
+    }
+
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        if let (true, Some(body)) = (response.status.is_success(), context.body) {
+            if !self.0.is_match(body) {
+                response.status = ErrorKind::MissingContent(self.0.as_str().to_owned()).into();
+            }
+        }
+    }
+}
+
+/// Downgrades links to parked domains to errors.
+/// A domain counts as parked if the link ends up on a parking service
+/// or the page advertises the domain for sale.
+#[derive(Debug, Clone)]
+pub struct ParkedDomainDetector {
+    patterns: RegexSet,
+}
+
+impl Default for ParkedDomainDetector {
+    fn default() -> Self {
+        Self {
+            // The patterns are known to be valid
+            patterns: RegexSet::new(PARKING_PATTERNS).unwrap(),
+        }
+    }
+}
+
+impl PostProcessor for ParkedDomainDetector {
+    fn needs_body(&self) -> bool {
+        true
+    }
+
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        if !response.status.is_success() {
+            return;
+        }
+        let on_parking_host = context.url.host_str().map_or(false, |host| {
+            PARKING_HOSTS.iter().any(|p| is_subdomain(host, p))
+        });
+        if on_parking_host || context.body.map_or(false, |b| self.patterns.is_match(b)) {
+            response.status = ErrorKind::Soft404("domain is parked").into();
+            response.tags.push("parked".to_owned());
+        }
+    }
+}
+
+/// Annotates links which redirect to a single sign-on login page.
+/// Such links can't be checked without credentials,
+/// so they get tagged with `sso` instead of being marked as broken.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SsoDetector;
+
+impl PostProcessor for SsoDetector {
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        if response.redirects.is_empty() {
+            return;
+        }
+        let host = context.url.host_str().unwrap_or_default();
+        let location = format!("{}{}", host, context.url.path());
+        let is_login = SSO_HOSTS
+            .iter()
+            .any(|sso| is_subdomain(host, sso) || location.starts_with(sso))
+            || ["/login", "/signin", "/sso", "/saml"]
+                .iter()
+                .any(|p| context.url.path().starts_with(p));
+        if is_login {
+            response.tags.push("sso".to_owned());
+            response.suggestions.push(format!(
+                "Requires signing in (redirected to {}), consider excluding it",
+                context.url
+            ));
+        }
+    }
+}
+
+/// Suggests replacing links with the URL they redirect to if the redirect
+/// is permanent, or if it only upgrades to HTTPS or adds a trailing slash.
+/// The replacement is recorded in [`ResponseBody::replacement`],
+/// so tools can patch the inputs (see [`Fix`](crate::fix::Fix)).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplacementSuggester;
+
+impl PostProcessor for ReplacementSuggester {
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        if !response.status.is_success() || response.redirects.is_empty() {
+            return;
+        }
+        // Fragments aren't sent to the server, so they get carried over
+        let mut url = context.url.clone();
+        url.set_fragment(response.uri.url.fragment());
+        if url == response.uri.url {
+            return;
+        }
+        let permanent = response
+            .redirects
+            .iter()
+            .all(|redirect| redirect.code == 301 || redirect.code == 308);
+        if permanent || is_known_rewrite(&response.uri.url, &url) {
+            response.suggestions.push(format!("Replace with {}", url));
+            response.replacement = Some(url.into());
+        }
+    }
+}
+
+/// Records selected headers of the final response (e.g. `Content-Type` or
+/// `Last-Modified`) in [`ResponseBody::headers`].
+/// The values of headers with secrets (e.g. `Set-Cookie`) are redacted.
+#[derive(Debug, Clone, Default)]
+pub struct HeaderRecorder(pub Vec<HeaderName>);
+
+impl PostProcessor for HeaderRecorder {
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        for name in &self.0 {
+            if let Some(value) = context.headers.get(name).and_then(|v| v.to_str().ok()) {
+                let value = if is_sensitive(name) { REDACTED } else { value };
+                response
+                    .headers
+                    .insert(name.as_str().to_owned(), value.to_owned());
+            }
+        }
+    }
+}
+
+/// Fails successful responses whose header doesn't match a pattern,
+/// e.g. to require `Content-Type: application/pdf` for links ending in `.pdf`.
+/// This detects links which resolve to the wrong resource, like an HTML page
+/// instead of a download.
+#[derive(Debug, Clone)]
+pub struct HeaderAssertion {
+    /// Only check links matching this pattern (e.g. `\.pdf$`).
+    /// All links are checked without a pattern.
+    pub links: Option<Regex>,
+    /// Name of the header
+    pub header: HeaderName,
+    /// Pattern the value of the header must match.
+    /// Responses without the header fail as well.
+    pub value: Regex,
+}
+
+impl PostProcessor for HeaderAssertion {
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        if !response.status.is_success() {
+            return;
+        }
+        if let Some(links) = &self.links {
+            if !links.is_match(response.uri.as_str()) {
+                return;
+            }
+        }
+        let value = context
+            .headers
+            .get(&self.header)
+            .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned());
+        if value
+            .as_deref()
+            .map_or(true, |value| !self.value.is_match(value))
+        {
+            response.status = ErrorKind::UnexpectedHeader(
+                self.header.as_str().to_owned(),
+                self.value.as_str().to_owned(),
+                value,
+            )
+            .into();
+        }
+    }
+}
+
+/// Reports successful responses whose `Last-Modified` header is older than
+/// the maximum age as [warnings](Status::Warning) tagged with `stale`,
+/// to find references to outdated resources.
+/// The age is relative to the `Date` header of the response, if there is one,
+/// so the clocks of the server and the client don't need to agree.
+#[derive(Debug, Clone, Copy)]
+pub struct StaleContentDetector {
+    /// Resources which haven't been modified for longer are stale
+    pub max_age: Duration,
+}
+
+impl PostProcessor for StaleContentDetector {
+    fn process(&self, context: &ProcessContext<'_>, response: &mut ResponseBody) {
+        let code = match response.status {
+            Status::Ok(code) => code,
+            _ => return,
+        };
+        let date = |name| {
+            context
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| httpdate::parse_http_date(value).ok())
+        };
+        let modified = match date(header::LAST_MODIFIED) {
+            Some(modified) => modified,
+            None => return,
+        };
+        let now = date(header::DATE).unwrap_or_else(SystemTime::now);
+        let age = match now.duration_since(modified) {
+            Ok(age) if age > self.max_age => age,
+            _ => return,
+        };
+        response.status = Status::Warning(code);
+        response.tags.push("stale".to_owned());
+        response.suggestions.push(format!(
+            "Not modified for {} days (since {})",
+            age.as_secs() / 86_400,
+            httpdate::fmt_http_date(modified)
+        ));
+    }
+}
+
+/// The status a [`StatusRule`] gives to the responses it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reclassification {
+    /// Treat the response as successful, like an accepted status code
+    Ok,
+    /// Report the response as a [warning](Status::Warning), which doesn't fail the run
+    Warning,
+    /// Treat the link as excluded from checking
+    Excluded,
+}
+
+/// Gives responses with a status code from a host a different status,
+/// e.g. treats 403 from `linkedin.com` as excluded or 999 from any host as a warning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusRule {
+    /// Host (in lowercase) of the link, including its subdomains.
+    /// Rules without a host apply to all links.
+    pub host: Option<String>,
+    /// Status code of the response
+    pub code: StatusCode,
+    /// The new status of matching responses
+    pub status: Reclassification,
+}
+
+impl StatusRule {
+    fn matches(&self, response: &ResponseBody, code: StatusCode) -> bool {
+        if code != self.code {
+            return false;
+        }
+        match (&self.host, response.uri.url.host_str()) {
+            (None, _) => true,
+            (Some(domain), Some(host)) => {
+                is_subdomain(&host.trim_end_matches('.').to_lowercase(), domain)
+            }
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Applies the first matching [`StatusRule`] to every response.
+/// This generalizes the set of accepted status codes to specific hosts
+/// and to other outcomes than success.
+#[derive(Debug, Clone, Default)]
+pub struct StatusRules(pub Vec<StatusRule>);
+
+impl PostProcessor for StatusRules {
+    fn process(&self, _: &ProcessContext<'_>, response: &mut ResponseBody) {
+        let code = match response.status.code() {
+            Some(code) => code,
+            None => return,
+        };
+        if let Some(rule) = self.0.iter().find(|rule| rule.matches(response, code)) {
+            response.status = match rule.status {
+                Reclassification::Ok => Status::Ok(code),
+                Reclassification::Warning => Status::Warning(code),
+                Reclassification::Excluded => Status::Excluded,
+            };
+        }
+    }
+}
+
+/// Whether `to` is `from`, upgraded to HTTPS and/or with a trailing slash added
+fn is_known_rewrite(from: &Url, to: &Url) -> bool {
+    let scheme =
+        from.scheme() == to.scheme() || (from.scheme() == "http" && to.scheme() == "https");
+    let path = from.path() == to.path() || format!("{}/", from.path()) == to.path();
+    scheme
+        && path
+        && from.host_str() == to.host_str()
+        && from.port() == to.port()
+        && from.query() == to.query()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::{
+        header::{self, HeaderMap, HeaderValue},
+        StatusCode,
+    };
+    use regex::Regex;
+    use reqwest::Url;
+
+    use super::{
+        HeaderAssertion, HeaderRecorder, ParkedDomainDetector, PostProcessor, ProcessContext,
+        Reclassification, ReplacementSuggester, SsoDetector, StaleContentDetector, StatusRule,
+        StatusRules,
+    };
+    use crate::{test_utils::website, ErrorKind, Redirect, ResponseBody, Status};
+
+    fn response(uri: &str) -> ResponseBody {
+        ResponseBody::new(website(uri), Status::Ok(StatusCode::OK))
+    }
+
+    #[test]
+    fn test_parked_domain() {
+        let headers = HeaderMap::new();
+        let detector = ParkedDomainDetector::default();
+
+        let url = Url::parse("https://example.org").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: Some("<h1>This domain may be for sale!</h1>"),
+        };
+        let mut parked = response("https://example.org");
+        detector.process(&context, &mut parked);
+        assert!(parked.status.is_failure());
+        assert_eq!(parked.tags, vec!["parked"]);
+
+        let url = Url::parse("https://www.sedoparking.com/example.org").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: Some(""),
+        };
+        let mut parked = response("https://example.org");
+        detector.process(&context, &mut parked);
+        assert!(parked.status.is_failure());
+
+        let url = Url::parse("https://example.org").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: Some("Welcome to my blog"),
+        };
+        let mut ok = response("https://example.org");
+        detector.process(&context, &mut ok);
+        assert!(ok.status.is_success());
+    }
+
+    #[test]
+    fn test_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        headers.insert(header::SERVER, HeaderValue::from_static("nginx"));
+        let url = Url::parse("https://example.org/manual.pdf").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: None,
+        };
+
+        let recorder = HeaderRecorder(vec![header::CONTENT_TYPE, header::LAST_MODIFIED]);
+        let mut recorded = response("https://example.org/manual.pdf");
+        recorder.process(&context, &mut recorded);
+        assert_eq!(recorded.headers.len(), 1);
+        assert_eq!(recorded.headers["content-type"], "text/html");
+
+        let assertion = HeaderAssertion {
+            links: Some(Regex::new(r"\.pdf$").unwrap()),
+            header: header::CONTENT_TYPE,
+            value: Regex::new("^application/pdf").unwrap(),
+        };
+        let mut wrong = response("https://example.org/manual.pdf");
+        assertion.process(&context, &mut wrong);
+        assert_eq!(
+            wrong.status,
+            ErrorKind::UnexpectedHeader(
+                "content-type".to_owned(),
+                "^application/pdf".to_owned(),
+                Some("text/html".to_owned())
+            )
+            .into()
+        );
+        let mut other = response("https://example.org/manual.html");
+        assertion.process(&context, &mut other);
+        assert!(other.status.is_success());
+    }
+
+    #[test]
+    fn test_stale_content() {
+        let detector = StaleContentDetector {
+            max_age: Duration::from_secs(2 * 365 * 86_400),
+        };
+        let url = Url::parse("https://example.org/spec").unwrap();
+        let check = |last_modified: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::DATE,
+                HeaderValue::from_static("Fri, 01 Oct 2021 00:00:00 GMT"),
+            );
+            headers.insert(
+                header::LAST_MODIFIED,
+                HeaderValue::from_static(last_modified),
+            );
+            let context = ProcessContext {
+                url: &url,
+                headers: &headers,
+                body: None,
+            };
+            let mut response = response("https://example.org/spec");
+            detector.process(&context, &mut response);
+            response
+        };
+
+        let stale = check("Wed, 01 Oct 2014 00:00:00 GMT");
+        assert_eq!(stale.status, Status::Warning(StatusCode::OK));
+        assert_eq!(stale.tags, vec!["stale"]);
+        assert_eq!(
+            stale.suggestions,
+            vec!["Not modified for 2557 days (since Wed, 01 Oct 2014 00:00:00 GMT)"]
+        );
+        let fresh = check("Mon, 01 Mar 2021 00:00:00 GMT");
+        assert!(fresh.status.is_success());
+        assert!(fresh.tags.is_empty());
+    }
+
+    #[test]
+    fn test_sso() {
+        let headers = HeaderMap::new();
+        let url = Url::parse("https://login.microsoftonline.com/common/oauth2").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: None,
+        };
+
+        let mut direct = response("https://login.microsoftonline.com/common/oauth2");
+        SsoDetector.process(&context, &mut direct);
+        assert!(direct.tags.is_empty());
+
+        let mut redirected = response("https://intranet.example.org/wiki");
+        redirected.redirects.push(Redirect {
+            uri: website("https://intranet.example.org/wiki"),
+            code: 302,
+            latency: Duration::from_millis(10),
+        });
+        SsoDetector.process(&context, &mut redirected);
+        assert!(redirected.status.is_success());
+        assert_eq!(redirected.tags, vec!["sso"]);
+        assert_eq!(redirected.suggestions.len(), 1);
+    }
+
+    #[test]
+    fn test_replacement() {
+        let headers = HeaderMap::new();
+        let redirect = |uri: &str, code| Redirect {
+            uri: website(uri),
+            code,
+            latency: Duration::from_millis(10),
+        };
+
+        let url = Url::parse("https://example.org/new").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: None,
+        };
+        let mut moved = response("https://example.org/old#intro");
+        moved
+            .redirects
+            .push(redirect("https://example.org/old#intro", 301));
+        ReplacementSuggester.process(&context, &mut moved);
+        assert_eq!(
+            moved.replacement,
+            Some(website("https://example.org/new#intro"))
+        );
+
+        // Temporary redirects are only followed up on for known rewrites
+        let mut temporary = response("https://example.org/old");
+        temporary
+            .redirects
+            .push(redirect("https://example.org/old", 302));
+        ReplacementSuggester.process(&context, &mut temporary);
+        assert_eq!(temporary.replacement, None);
+
+        let url = Url::parse("https://example.org/docs/").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: None,
+        };
+        let mut upgraded = response("http://example.org/docs");
+        upgraded
+            .redirects
+            .push(redirect("http://example.org/docs", 302));
+        ReplacementSuggester.process(&context, &mut upgraded);
+        assert_eq!(
+            upgraded.replacement,
+            Some(website("https://example.org/docs/"))
+        );
+    }
+
+    #[test]
+    fn test_status_rules() {
+        let headers = HeaderMap::new();
+        let url = Url::parse("https://www.linkedin.com/in/someone").unwrap();
+        let context = ProcessContext {
+            url: &url,
+            headers: &headers,
+            body: None,
+        };
+        let rules = StatusRules(vec![
+            StatusRule {
+                host: Some("linkedin.com".to_owned()),
+                code: StatusCode::FORBIDDEN,
+                status: Reclassification::Excluded,
+            },
+            StatusRule {
+                host: None,
+                code: StatusCode::from_u16(999).unwrap(),
+                status: Reclassification::Warning,
+            },
+        ]);
+
+        // Errors of reqwest can't be built here, but their code is read the same way
+        let mut forbidden = response("https://www.linkedin.com/in/someone");
+        forbidden.status = Status::Timeout(Some(StatusCode::FORBIDDEN));
+        rules.process(&context, &mut forbidden);
+        assert_eq!(forbidden.status, Status::Excluded);
+
+        let mut elsewhere = response("https://example.org");
+        elsewhere.status = Status::Timeout(Some(StatusCode::FORBIDDEN));
+        rules.process(&context, &mut elsewhere);
+        assert_eq!(
+            elsewhere.status,
+            Status::Timeout(Some(StatusCode::FORBIDDEN))
+        );
+
+        let code = StatusCode::from_u16(999).unwrap();
+        let mut unknown = response("https://example.org");
+        unknown.status = ErrorKind::UnexpectedStatusCode(code).into();
+        rules.process(&context, &mut unknown);
+        assert_eq!(unknown.status, Status::Warning(code));
+
+        let mut ok = response("https://www.linkedin.com");
+        rules.process(&context, &mut ok);
+        assert_eq!(ok.status, Status::Ok(StatusCode::OK));
+    }
+}
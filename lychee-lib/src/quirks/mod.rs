@@ -7,11 +7,34 @@ use std::collections::HashMap;
 /// Sadly some pages only return plaintext results if Google is trying to crawl them.
 const GOOGLEBOT: &str = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://google.com/bot.html)";
 
+/// oEmbed endpoint of YouTube
+const YOUTUBE_OEMBED: &str = "https://www.youtube.com/oembed?format=json";
+
+/// oEmbed endpoint of Vimeo
+const VIMEO_OEMBED: &str = "https://vimeo.com/api/oembed.json";
+
+/// Handle API of doi.org, which resolves a DOI without following it
+const DOI_HANDLES: &str = "https://doi.org/api/handles";
+
+/// Mirror of arXiv intended for programmatic access
+const ARXIV_EXPORT: &str = "https://export.arxiv.org/abs";
+
 // Retrieve a map of query params for the given request
 fn query(request: &Request) -> HashMap<String, String> {
     request.url().query_pairs().into_owned().collect()
 }
 
+/// Ask the oEmbed endpoint of a video platform about the requested video
+/// instead of fetching the heavy player page.
+/// The endpoint responds with a 404 for deleted or missing videos.
+fn oembed(request: Request, endpoint: &str) -> Request {
+    let mut out = request;
+    let mut url = Url::parse(endpoint).unwrap();
+    url.query_pairs_mut().append_pair("url", out.url().as_str());
+    *out.url_mut() = url;
+    out
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Quirk {
     pub(crate) pattern: Regex,
@@ -50,21 +73,58 @@ impl Default for Quirks {
                 },
             },
             Quirk {
-                // Even missing YouTube videos return a 200, therefore we use
-                // the thumbnail endpoint instead
-                // (https://img.youtube.com/vi/{video_id}/0.jpg).
-                // This works for all known video visibilities.
+                // Even missing YouTube videos return a 200, therefore we ask
+                // the oEmbed endpoint instead, which returns a 404 for them.
+                // This also works for unlisted videos.
                 // See https://github.com/lycheeverse/lychee/issues/214#issuecomment-819103393)
                 pattern: Regex::new(r"^(https?://)?(www\.)?(youtube\.com|youtu\.?be)").unwrap(),
                 rewrite: |request| {
-                    if request.url().path() != "/watch" {
-                        return request;
+                    let is_video = match request.url().host_str() {
+                        Some("youtu.be") => request.url().path().len() > 1,
+                        _ => request.url().path() == "/watch" && query(&request).contains_key("v"),
+                    };
+                    if is_video {
+                        oembed(request, YOUTUBE_OEMBED)
+                    } else {
+                        request
                     }
+                },
+            },
+            Quirk {
+                // Vimeo videos, same as above
+                pattern: Regex::new(r"^(https?://)?(www\.)?vimeo\.com/\d+").unwrap(),
+                rewrite: |request| oembed(request, VIMEO_OEMBED),
+            },
+            Quirk {
+                // DOIs redirect through the publisher's site, which often
+                // rate-limits or blocks bots. The handle API answers with a
+                // 404 for unknown DOIs instead.
+                pattern: Regex::new(r"^(https?://)?(dx\.)?doi\.org/10\.").unwrap(),
+                rewrite: |request| {
                     let mut out = request;
-                    if let Some(id) = query(&out).get("v") {
-                        *out.url_mut() =
-                            Url::parse(&format!("https://img.youtube.com/vi/{}/0.jpg", &id))
-                                .unwrap()
+                    let url = format!("{}{}", DOI_HANDLES, out.url().path());
+                    if let Ok(url) = Url::parse(&url) {
+                        *out.url_mut() = url;
+                    }
+                    out
+                },
+            },
+            Quirk {
+                // arXiv asks automated clients to use its export mirror,
+                // which returns a 404 for unknown papers. Papers are checked
+                // via their abstract, so PDFs don't get downloaded.
+                pattern: Regex::new(r"^(https?://)?(www\.)?arxiv\.org/(abs|pdf)/").unwrap(),
+                rewrite: |request| {
+                    let mut out = request;
+                    let path = out.url().path();
+                    let id = path
+                        .strip_prefix("/abs/")
+                        .or_else(|| path.strip_prefix("/pdf/"))
+                        .unwrap_or_default();
+                    let id = id.strip_suffix(".pdf").unwrap_or(id);
+                    if let Ok(url) = Url::parse(&format!("{}/{}", ARXIV_EXPORT, id)) {
+                        *out.url_mut() = url;
+                        *out.method_mut() = Method::HEAD;
                     }
                     out
                 },
@@ -96,7 +156,7 @@ mod tests {
     use pretty_assertions::assert_eq;
     use reqwest::{Request, Url};
 
-    use super::{Quirks, GOOGLEBOT};
+    use super::{Quirks, ARXIV_EXPORT, DOI_HANDLES, GOOGLEBOT, VIMEO_OEMBED, YOUTUBE_OEMBED};
 
     #[derive(Debug)]
     struct MockRequest(Request);
@@ -141,9 +201,50 @@ mod tests {
     #[test]
     fn test_youtube_video_request() {
         let url = Url::parse("https://www.youtube.com/watch?v=NlKuICiT470&list=PLbWDhxwM_45mPVToqaIZNbZeIzFchsKKQ&index=7").unwrap();
+        let request = Request::new(Method::GET, url.clone());
+        let modified = Quirks::default().apply(request);
+        let expected_url =
+            Url::parse_with_params(YOUTUBE_OEMBED, &[("url", url.as_str())]).unwrap();
+
+        assert_eq!(
+            MockRequest(modified),
+            MockRequest::new(Method::GET, expected_url)
+        );
+    }
+
+    #[test]
+    fn test_youtube_short_video_request() {
+        let url = Url::parse("https://youtu.be/NlKuICiT470").unwrap();
+        let request = Request::new(Method::GET, url.clone());
+        let modified = Quirks::default().apply(request);
+        let expected_url =
+            Url::parse_with_params(YOUTUBE_OEMBED, &[("url", url.as_str())]).unwrap();
+
+        assert_eq!(
+            MockRequest(modified),
+            MockRequest::new(Method::GET, expected_url)
+        );
+    }
+
+    #[test]
+    fn test_vimeo_video_request() {
+        let url = Url::parse("https://vimeo.com/76979871").unwrap();
+        let request = Request::new(Method::GET, url.clone());
+        let modified = Quirks::default().apply(request);
+        let expected_url = Url::parse_with_params(VIMEO_OEMBED, &[("url", url.as_str())]).unwrap();
+
+        assert_eq!(
+            MockRequest(modified),
+            MockRequest::new(Method::GET, expected_url)
+        );
+    }
+
+    #[test]
+    fn test_doi_request() {
+        let url = Url::parse("https://doi.org/10.1145/3385412.3386018").unwrap();
         let request = Request::new(Method::GET, url);
         let modified = Quirks::default().apply(request);
-        let expected_url = Url::parse("https://img.youtube.com/vi/NlKuICiT470/0.jpg").unwrap();
+        let expected_url = Url::parse(&format!("{}/10.1145/3385412.3386018", DOI_HANDLES)).unwrap();
 
         assert_eq!(
             MockRequest(modified),
@@ -151,6 +252,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_arxiv_request() {
+        let expected_url = Url::parse(&format!("{}/2106.09685v2", ARXIV_EXPORT)).unwrap();
+        for url in &[
+            "https://arxiv.org/abs/2106.09685v2",
+            "https://arxiv.org/pdf/2106.09685v2.pdf",
+        ] {
+            let request = Request::new(Method::GET, Url::parse(url).unwrap());
+            let modified = Quirks::default().apply(request);
+
+            assert_eq!(
+                MockRequest(modified),
+                MockRequest::new(Method::HEAD, expected_url.clone())
+            );
+        }
+
+        // Old-style identifiers contain the archive name
+        let url = Url::parse("https://arxiv.org/abs/hep-th/9901001").unwrap();
+        let modified = Quirks::default().apply(Request::new(Method::GET, url));
+        assert_eq!(
+            modified.url().as_str(),
+            format!("{}/hep-th/9901001", ARXIV_EXPORT)
+        );
+    }
+
     #[test]
     fn test_non_video_youtube_url_untouched() {
         let url = Url::parse("https://www.youtube.com/channel/UCaYhcUwRBNscFNUKTjgPFiA").unwrap();
@@ -0,0 +1,100 @@
+use fantoccini::ClientBuilder as WebDriverBuilder;
+use reqwest::Url;
+use serde_json::json;
+
+use crate::{extract::FileType, ErrorKind, Input, InputContent, Result};
+
+/// Renders pages in a headless browser before their links are extracted,
+/// for pages which only add their links with JavaScript.
+/// The browser is controlled through a WebDriver server,
+/// e.g. `chromedriver --port=4444` or `geckodriver`.
+///
+/// Rendering is much slower than fetching a page, so it can be limited to
+/// the inputs of some hosts. All other inputs are fetched as usual.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renderer {
+    webdriver: Url,
+    hosts: Vec<String>,
+}
+
+impl Renderer {
+    /// Render pages with the WebDriver server at the given URL
+    /// (e.g. `http://localhost:4444`)
+    #[must_use]
+    pub fn new(webdriver: Url) -> Self {
+        Self {
+            webdriver,
+            hosts: Vec::new(),
+        }
+    }
+
+    /// Only render pages of these hosts and their subdomains
+    #[must_use]
+    pub fn hosts(mut self, hosts: Vec<String>) -> Self {
+        self.hosts = hosts;
+        self
+    }
+
+    /// Whether the page at `url` is rendered
+    pub(crate) fn renders(&self, url: &Url) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host.trim_end_matches('.'),
+            None => return false,
+        };
+        self.hosts.is_empty()
+            || self.hosts.iter().any(|other| {
+                let other = other.trim_end_matches('.');
+                host.eq_ignore_ascii_case(other)
+                    || host
+                        .to_lowercase()
+                        .ends_with(&format!(".{}", other.to_lowercase()))
+            })
+    }
+
+    /// The HTML of the page at `url` after its scripts ran
+    pub(crate) async fn render(&self, url: &Url) -> Result<InputContent> {
+        let error = |e: &dyn std::error::Error| ErrorKind::RenderError(url.clone(), e.to_string());
+        // Browsers ignore the options of the others
+        let capabilities = json!({
+            "goog:chromeOptions": { "args": ["--headless", "--disable-gpu"] },
+            "moz:firefoxOptions": { "args": ["-headless"] },
+        });
+        let mut client = WebDriverBuilder::native()
+            .capabilities(capabilities.as_object().cloned().unwrap_or_default())
+            .connect(self.webdriver.as_str())
+            .await
+            .map_err(|e| error(&e))?;
+        let source = match client.goto(url.as_str()).await {
+            Ok(()) => client.source().await,
+            Err(e) => Err(e),
+        };
+        // End the session even if the page can't be rendered,
+        // so the browser doesn't keep running
+        let _ = client.close().await;
+        Ok(InputContent {
+            input: Input::RemoteUrl(Box::new(url.clone())),
+            file_type: FileType::Html,
+            content: source.map_err(|e| error(&e))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::Url;
+
+    use super::Renderer;
+
+    #[test]
+    fn test_renders() {
+        let renderer = Renderer::new(Url::parse("http://localhost:4444").unwrap());
+        assert!(renderer.renders(&Url::parse("https://example.org/app").unwrap()));
+
+        let renderer = renderer.hosts(vec!["app.example.org".to_owned()]);
+        assert!(renderer.renders(&Url::parse("https://app.example.org/").unwrap()));
+        assert!(renderer.renders(&Url::parse("https://eu.APP.example.org/").unwrap()));
+        assert!(!renderer.renders(&Url::parse("https://example.org/").unwrap()));
+        assert!(!renderer.renders(&Url::parse("https://myapp.example.org/").unwrap()));
+        assert!(!renderer.renders(&Url::parse("file:///tmp/index.html").unwrap()));
+    }
+}
@@ -0,0 +1,369 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use tokio::sync::mpsc;
+
+use crate::{
+    client::Client, concurrency::ConcurrencyLimit, Collector, Input, Response, Result, Status, Uri,
+};
+
+/// Default time between two rounds of checks
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Default number of results which are kept per link
+const DEFAULT_HISTORY_SIZE: usize = 10;
+
+/// Checks the links of inputs periodically and keeps the last results of
+/// every link, to tell links which fail now and then (e.g. because of a flaky
+/// host) from links which are broken for good.
+///
+/// The inputs are collected again in every round, so added links are picked
+/// up and the history of removed links is dropped. Timeouts count as failures, while excluded, unsupported
+/// and skipped links and challenges of bot protection services have no history.
+///
+/// ```no_run
+/// use lychee_lib::{scheduler::Scheduler, ClientBuilder, Collector, Input, Result};
+/// use std::time::Duration;
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let client = ClientBuilder::default().client()?;
+///     let scheduler = Scheduler::new(client, Collector::default())
+///         .interval(Duration::from_secs(6 * 60 * 60))
+///         .history_size(28);
+///     let (sender, mut receiver) = mpsc::channel(1);
+///     let inputs = vec![Input::new("docs/**/*.md", false)];
+///     let runner = scheduler.clone();
+///     tokio::spawn(async move { runner.run(&inputs, sender).await });
+///     while let Some(responses) = receiver.recv().await {
+///         for response in responses {
+///             let history = scheduler.history(&response.1.uri).unwrap_or_default();
+///             println!("{} ({:?})", response, history.health());
+///         }
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    client: Client,
+    collector: Collector,
+    interval: Duration,
+    history_size: usize,
+    limit: ConcurrencyLimit,
+    histories: Arc<Mutex<HashMap<Uri, History>>>,
+}
+
+/// The last results of checking a link, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    results: VecDeque<bool>,
+}
+
+/// How reliably a link could be checked over its [`History`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Health {
+    /// The link wasn't checked yet
+    Unknown,
+    /// Every check succeeded
+    Healthy,
+    /// Some checks failed, others succeeded
+    Flaky,
+    /// Every check failed
+    Broken,
+}
+
+impl History {
+    /// The results of the checks, oldest first, where `true` is a success
+    pub fn results(&self) -> impl Iterator<Item = bool> + '_ {
+        self.results.iter().copied()
+    }
+
+    /// Number of failed checks
+    #[must_use]
+    pub fn failures(&self) -> usize {
+        self.results.iter().filter(|success| !**success).count()
+    }
+
+    /// Share of the checks which failed, between 0 and 1
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn failure_rate(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.failures() as f64 / self.results.len() as f64
+    }
+
+    /// Whether the link failed intermittently or consistently
+    #[must_use]
+    pub fn health(&self) -> Health {
+        match self.failures() {
+            _ if self.results.is_empty() => Health::Unknown,
+            0 => Health::Healthy,
+            failures if failures == self.results.len() => Health::Broken,
+            _ => Health::Flaky,
+        }
+    }
+
+    fn push(&mut self, success: bool, size: usize) {
+        self.results.push_back(success);
+        while self.results.len() > size {
+            self.results.pop_front();
+        }
+    }
+}
+
+impl Scheduler {
+    /// Check links periodically with the given client and collector
+    #[must_use]
+    pub fn new(client: Client, collector: Collector) -> Self {
+        Scheduler {
+            client,
+            collector,
+            interval: DEFAULT_INTERVAL,
+            history_size: DEFAULT_HISTORY_SIZE,
+            limit: ConcurrencyLimit::default(),
+            histories: Arc::default(),
+        }
+    }
+
+    /// Wait this long between the starts of two rounds of checks
+    #[must_use]
+    pub const fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Keep this many results per link
+    #[must_use]
+    pub fn history_size(mut self, history_size: usize) -> Self {
+        self.history_size = history_size.max(1);
+        self
+    }
+
+    /// Check at most this many links at the same time
+    #[must_use]
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.limit = ConcurrencyLimit::new(max_concurrency);
+        self
+    }
+
+    /// The histories, which are still consistent if a thread panicked while
+    /// holding the lock, as every update is a single push or removal
+    fn histories(&self) -> MutexGuard<'_, HashMap<Uri, History>> {
+        self.histories
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The results of the last checks of a link, if it was checked
+    #[must_use]
+    pub fn history(&self, uri: &Uri) -> Option<History> {
+        self.histories().get(uri).cloned()
+    }
+
+    /// The links whose checks failed intermittently
+    #[must_use]
+    pub fn flaky(&self) -> Vec<Uri> {
+        self.with_health(Health::Flaky)
+    }
+
+    /// The links whose checks all failed
+    #[must_use]
+    pub fn broken(&self) -> Vec<Uri> {
+        self.with_health(Health::Broken)
+    }
+
+    fn with_health(&self, health: Health) -> Vec<Uri> {
+        let mut uris: Vec<Uri> = self
+            .histories()
+            .iter()
+            .filter(|(_, history)| history.health() == health)
+            .map(|(uri, _)| uri.clone())
+            .collect();
+        uris.sort();
+        uris
+    }
+
+    /// Check all links of `inputs` once and add the results to their history.
+    /// Links which aren't in the inputs anymore lose their history.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the inputs can't be read
+    pub async fn check(&self, inputs: &[Input]) -> Result<Vec<Response>> {
+        let requests = self.collector.clone().collect_links(inputs).await?;
+        let collected: HashSet<Uri> = requests.iter().map(|request| request.uri.clone()).collect();
+        self.histories().retain(|uri, _| collected.contains(uri));
+        let handles: Vec<_> = requests
+            .into_iter()
+            .map(|request| {
+                let client = self.client.clone();
+                self.limit.spawn(async move { client.check(request).await })
+            })
+            .collect();
+        let mut responses = Vec::with_capacity(handles.len());
+        for handle in handles {
+            let response = handle.await??;
+            self.record(&response);
+            responses.push(response);
+        }
+        Ok(responses)
+    }
+
+    /// Check the links of `inputs` every interval, starting right away,
+    /// and send the responses of every round.
+    /// Runs until the receiver of `sender` is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the inputs can't be read
+    pub async fn run(&self, inputs: &[Input], sender: mpsc::Sender<Vec<Response>>) -> Result<()> {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            let responses = self.check(inputs).await?;
+            if sender.send(responses).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn record(&self, response: &Response) {
+        let Response(_, body) = response;
+        let success = match body.status {
+            Status::Ok(_) | Status::Redirected(_) | Status::Warning(_) => true,
+            Status::Error(_) | Status::Timeout(_) => false,
+            Status::Excluded
+            | Status::Unsupported(_)
+            | Status::ProtectedByBotCheck(_)
+            | Status::Skipped(_) => return,
+        };
+        self.histories()
+            .entry(body.uri.clone())
+            .or_default()
+            .push(success, self.history_size);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::StatusCode;
+    use tokio::sync::mpsc;
+
+    use super::{Health, History, Scheduler};
+    use crate::{
+        mock_server,
+        test_utils::{response, website},
+        ClientBuilder, Collector, Input, Status,
+    };
+
+    fn scheduler() -> Scheduler {
+        Scheduler::new(
+            ClientBuilder::default().client().unwrap(),
+            Collector::default(),
+        )
+        .history_size(3)
+    }
+
+    #[test]
+    fn test_health() {
+        let history = |results: &[bool]| {
+            let mut history = History::default();
+            for success in results {
+                history.push(*success, 10);
+            }
+            history
+        };
+        assert_eq!(history(&[]).health(), Health::Unknown);
+        assert_eq!(history(&[true, true]).health(), Health::Healthy);
+        assert_eq!(history(&[true, false, true]).health(), Health::Flaky);
+        assert_eq!(history(&[false, false]).health(), Health::Broken);
+        assert!((history(&[true, false, false, true]).failure_rate() - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_record() {
+        let scheduler = scheduler();
+        let ok = || Status::Ok(StatusCode::OK);
+        let failed = || Status::Timeout(None);
+        for status in vec![ok(), failed(), failed(), failed()] {
+            scheduler.record(&response("https://example.org/flaky", status));
+        }
+        for status in vec![failed(), ok(), failed()] {
+            scheduler.record(&response("https://example.com/", status));
+        }
+        scheduler.record(&response("https://example.net/", Status::Excluded));
+
+        // Only the last three results are kept
+        assert_eq!(
+            scheduler.broken(),
+            vec![website("https://example.org/flaky")]
+        );
+        assert_eq!(scheduler.flaky(), vec![website("https://example.com/")]);
+        let history = scheduler.history(&website("https://example.com/")).unwrap();
+        assert_eq!(
+            history.results().collect::<Vec<_>>(),
+            vec![false, true, false]
+        );
+        assert_eq!(scheduler.history(&website("https://example.net/")), None);
+    }
+
+    #[tokio::test]
+    async fn test_prune_removed_links() {
+        let mock_server = mock_server!(StatusCode::OK);
+        let scheduler = scheduler();
+        let (a, b) = (
+            format!("{}/a", mock_server.uri()),
+            format!("{}/b", mock_server.uri()),
+        );
+
+        scheduler
+            .check(&[Input::String(format!("{} {}", a, b))])
+            .await
+            .unwrap();
+        assert!(scheduler.history(&website(&b)).is_some());
+
+        scheduler.check(&[Input::String(a.clone())]).await.unwrap();
+        assert_eq!(
+            scheduler
+                .history(&website(&a))
+                .unwrap()
+                .results()
+                .collect::<Vec<_>>(),
+            vec![true, true]
+        );
+        assert_eq!(scheduler.history(&website(&b)), None);
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let mock_server = mock_server!(StatusCode::OK);
+        let scheduler = scheduler()
+            .interval(std::time::Duration::from_millis(10))
+            .max_concurrency(1);
+        let inputs = vec![Input::String(format!(
+            "{}/a {}/b",
+            mock_server.uri(),
+            mock_server.uri()
+        ))];
+        let (sender, mut receiver) = mpsc::channel(1);
+        let runner = scheduler.clone();
+        let handle = tokio::spawn(async move { runner.run(&inputs, sender).await });
+        for _ in 0..2 {
+            assert_eq!(receiver.recv().await.unwrap().len(), 2);
+        }
+        drop(receiver);
+        handle.await.unwrap().unwrap();
+
+        let uri = website(&format!("{}/a", mock_server.uri()));
+        let history = scheduler.history(&uri).unwrap();
+        assert!(history.results().count() >= 2);
+        assert_eq!(history.health(), Health::Healthy);
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    convert::{Infallible, TryFrom},
+    net::SocketAddr,
+    sync::Arc,
+};
+
+use hyper::{
+    header,
+    service::{make_service_fn, service_fn},
+    Body, Method, StatusCode,
+};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::Client,
+    concurrency::ConcurrencyLimit,
+    extract::{extract_links, FileType},
+    Input, InputContent, Request, Response, Result,
+};
+
+/// Maximum size of the body of a request to the service
+const MAX_BODY_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A long-running service which checks links on request, for link checking
+/// inside an infrastructure without running lychee for every check.
+/// All requests share the client, including its connections and pipeline,
+/// so add a [`CacheStage`](crate::pipeline::CacheStage) to its pipeline to
+/// answer repeated links from a cache.
+///
+/// `POST /check` takes the URLs and documents to check as JSON and answers
+/// with the responses of all links, in the format of the JSON report:
+///
+/// ```json
+/// {
+///   "urls": ["https://example.org"],
+///   "documents": [{ "name": "README.md", "content": "See [docs](https://example.org/docs)" }]
+/// }
+/// ```
+///
+/// The name of a document determines its file type (plaintext by default).
+/// Relative links of a document are resolved against its `base_url`, if any.
+/// `GET /health` answers with 200 as long as the service is running.
+#[derive(Debug, Clone)]
+pub struct Server {
+    client: Client,
+    limit: ConcurrencyLimit,
+}
+
+/// The body of a `POST /check` request
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Checks {
+    /// Links to check
+    pub urls: Vec<String>,
+    /// Documents whose links get checked
+    pub documents: Vec<Document>,
+}
+
+/// A document whose links get checked
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Document {
+    /// Name of the document, e.g. `README.md`, which determines its file type
+    pub name: String,
+    /// Content of the document
+    pub content: String,
+    /// Base URL of relative links in the document
+    pub base_url: Option<Url>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl Server {
+    /// A service which checks links with the given client
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            limit: ConcurrencyLimit::default(),
+        }
+    }
+
+    /// Check at most this many links at the same time, over all requests
+    #[must_use]
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.limit = ConcurrencyLimit::new(max_concurrency);
+        self
+    }
+
+    /// Check the links of a request to the service
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if one of the URLs is invalid
+    pub async fn check(&self, checks: Checks) -> Result<Vec<Response>> {
+        let mut requests = checks
+            .urls
+            .iter()
+            .map(|url| Request::try_from(url.as_str()))
+            .collect::<Result<Vec<_>>>()?;
+        for document in checks.documents {
+            let input_content = InputContent {
+                input: Input::String(document.name.clone()),
+                file_type: if document.name.is_empty() {
+                    FileType::default()
+                } else {
+                    FileType::from(&document.name)
+                },
+                content: document.content,
+            };
+            requests.extend(extract_links(&input_content, &document.base_url));
+        }
+
+        let handles: Vec<_> = requests
+            .into_iter()
+            .map(|request| {
+                let client = self.client.clone();
+                self.limit.spawn(async move { client.check(request).await })
+            })
+            .collect();
+        let mut responses = Vec::with_capacity(handles.len());
+        for handle in handles {
+            let response = handle
+                .await
+                .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))?;
+            responses.push(response);
+        }
+        Ok(responses)
+    }
+
+    /// Answer a request to the service
+    async fn handle(&self, request: hyper::Request<Body>) -> hyper::Response<Body> {
+        match (request.method(), request.uri().path()) {
+            (&Method::GET, "/health") => return json(StatusCode::OK, &"ok"),
+            (&Method::POST, "/check") => {}
+            _ => return error(StatusCode::NOT_FOUND, "Not found"),
+        }
+        let length = request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok()?.parse::<u64>().ok());
+        match length {
+            None => return error(StatusCode::LENGTH_REQUIRED, "Missing Content-Length"),
+            Some(length) if length > MAX_BODY_SIZE => {
+                return error(StatusCode::PAYLOAD_TOO_LARGE, "The body exceeds 10 MiB")
+            }
+            Some(_) => {}
+        }
+        let body = match hyper::body::to_bytes(request.into_body()).await {
+            Ok(body) => body,
+            Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        let checks = match serde_json::from_slice(&body) {
+            Ok(checks) => checks,
+            Err(e) => return error(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+        match self.check(checks).await {
+            Ok(responses) => json(StatusCode::OK, &responses),
+            Err(e) => error(StatusCode::UNPROCESSABLE_ENTITY, &e.to_string()),
+        }
+    }
+
+    /// Serve requests at the given address until the future gets dropped
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address can't be bound
+    /// or if the server fails
+    pub async fn serve(self, address: &SocketAddr) -> hyper::Result<()> {
+        let server = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let server = server.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.handle(request).await) }
+                }))
+            }
+        });
+        hyper::Server::try_bind(address)?.serve(make_service).await
+    }
+}
+
+/// A response with the given value as JSON
+fn json<T: Serialize>(status: StatusCode, value: &T) -> hyper::Response<Body> {
+    let mut response =
+        hyper::Response::new(Body::from(serde_json::to_string(value).unwrap_or_default()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// A response with an error message as JSON
+fn error(status: StatusCode, message: &str) -> hyper::Response<Body> {
+    json(
+        status,
+        &ErrorBody {
+            error: message.to_owned(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use hyper::{Body, Method, StatusCode};
+
+    use super::{Checks, Document, Server};
+    use crate::{mock_server, ClientBuilder};
+
+    fn server() -> Server {
+        Server::new(ClientBuilder::default().client().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let mock_server = mock_server!(StatusCode::OK);
+        let checks = Checks {
+            urls: vec![mock_server.uri()],
+            documents: vec![Document {
+                name: "README.md".to_owned(),
+                content: "See [docs](docs) and [API](/api)".to_owned(),
+                base_url: Some(format!("{}/", mock_server.uri()).parse().unwrap()),
+            }],
+        };
+        let responses = server().check(checks).await.unwrap();
+        assert_eq!(responses.len(), 3);
+        assert!(responses.iter().all(|r| r.status().is_success()));
+
+        let invalid = Checks {
+            urls: vec!["not a link".to_owned()],
+            ..Checks::default()
+        };
+        assert!(server().check(invalid).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle() {
+        let mock_server = mock_server!(StatusCode::OK);
+        let body = format!("{{\"urls\": [\"{}\"]}}", mock_server.uri());
+        let request = hyper::Request::builder()
+            .method(Method::POST)
+            .uri("/check")
+            .header("content-length", body.len())
+            .body(Body::from(body))
+            .unwrap();
+        let response = server().handle(request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json[0]["status"], "OK (200 OK)");
+
+        let request = hyper::Request::builder()
+            .method(Method::POST)
+            .uri("/check")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = server().handle(request).await;
+        assert_eq!(response.status(), StatusCode::LENGTH_REQUIRED);
+
+        let request = hyper::Request::builder()
+            .uri("/unknown")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            server().handle(request).await.status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+}
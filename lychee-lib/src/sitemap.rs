@@ -0,0 +1,136 @@
+use std::collections::BTreeSet;
+
+use reqwest::Url;
+
+use crate::{extract::FileType, Response};
+
+/// The pages of a site which were checked successfully, e.g. to write a
+/// `sitemap.xml` after checking all links of the site.
+///
+/// Only pages with the same origin (scheme, host and port) as the site are
+/// part of it. Links to other files (e.g. images) and links which were
+/// redirected are left out, as search engines ignore them in sitemaps.
+///
+/// ```
+/// use lychee_lib::{sitemap::Sitemap, Input, Response, Status, Uri};
+/// use reqwest::Url;
+/// use std::convert::TryFrom;
+///
+/// let mut sitemap = Sitemap::new(&Url::parse("https://example.org").unwrap());
+/// for link in &["https://example.org/docs/", "https://example.org/logo.png", "https://example.com/"] {
+///     let uri = Uri::try_from(*link).unwrap();
+///     let status = Status::Ok(http::StatusCode::OK);
+///     sitemap.add(&Response::new(uri, status, Input::Stdin));
+/// }
+/// assert_eq!(sitemap.urls().count(), 1);
+/// assert!(sitemap.to_xml().contains("<loc>https://example.org/docs/</loc>"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sitemap {
+    site: Url,
+    urls: BTreeSet<Url>,
+}
+
+impl Sitemap {
+    /// An empty sitemap of the site at the given URL
+    #[must_use]
+    pub fn new(site: &Url) -> Self {
+        Self {
+            site: site.clone(),
+            urls: BTreeSet::new(),
+        }
+    }
+
+    /// Add the link of a response if it's a page of the site which was
+    /// checked successfully. Returns whether it was added.
+    pub fn add(&mut self, response: &Response) -> bool {
+        let Response(_, body) = response;
+        let mut url = body.uri.url.clone();
+        let is_page = url.origin() == self.site.origin()
+            && matches!(url.scheme(), "http" | "https")
+            && FileType::from(url.path()) == FileType::Html;
+        if !is_page || !body.status.is_success() || !body.redirects.is_empty() {
+            return false;
+        }
+        url.set_fragment(None);
+        self.urls.insert(url)
+    }
+
+    /// The pages of the sitemap, in order
+    pub fn urls(&self) -> impl Iterator<Item = &Url> {
+        self.urls.iter()
+    }
+
+    /// The sitemap in the XML format of <https://www.sitemaps.org>.
+    /// Note that search engines only read the first 50,000 pages of a sitemap.
+    #[must_use]
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for url in &self.urls {
+            xml.push_str("  <url><loc>");
+            xml.push_str(&escape(url.as_str()));
+            xml.push_str("</loc></url>\n");
+        }
+        xml.push_str("</urlset>\n");
+        xml
+    }
+}
+
+/// Escape the characters of a URL which have a meaning in XML
+fn escape(url: &str) -> String {
+    url.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\'', "&apos;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::StatusCode;
+    use pretty_assertions::assert_eq;
+    use reqwest::Url;
+
+    use super::Sitemap;
+    use crate::{
+        test_utils::{response, website},
+        Redirect, Status,
+    };
+
+    #[test]
+    fn test_sitemap() {
+        let mut sitemap = Sitemap::new(&Url::parse("https://example.org/docs/").unwrap());
+        let ok = || Status::Ok(StatusCode::OK);
+        assert!(sitemap.add(&response("https://example.org/blog/?page=2&tag=rust", ok())));
+        assert!(sitemap.add(&response("https://example.org/", ok())));
+        assert!(sitemap.add(&response("https://example.org/about.html#team", ok())));
+        assert!(!sitemap.add(&response("https://example.org/about.html", ok())));
+        assert!(!sitemap.add(&response("https://example.org/style.css", ok())));
+        assert!(!sitemap.add(&response("http://example.org/", ok())));
+        assert!(!sitemap.add(&response("https://docs.example.org/", ok())));
+        assert!(!sitemap.add(&response("https://example.org/slow", Status::Timeout(None))));
+
+        let mut redirected = response("https://example.org/old", ok());
+        redirected.1.redirects.push(Redirect {
+            uri: website("https://example.org/old"),
+            code: 301,
+            latency: Duration::from_millis(20),
+        });
+        assert!(!sitemap.add(&redirected));
+
+        assert_eq!(
+            sitemap.to_xml(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n  \
+             <url><loc>https://example.org/</loc></url>\n  \
+             <url><loc>https://example.org/about.html</loc></url>\n  \
+             <url><loc>https://example.org/blog/?page=2&amp;tag=rust</loc></url>\n\
+             </urlset>\n"
+        );
+    }
+}
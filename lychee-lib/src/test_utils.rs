@@ -2,7 +2,7 @@ use std::convert::TryFrom;
 
 use reqwest::Url;
 
-use crate::{ClientBuilder, ErrorKind, Request, Uri};
+use crate::{ClientBuilder, ErrorKind, Input, Request, Response, Status, Uri};
 
 #[macro_export]
 /// Creates a mock web server, which responds with a predefined status when
@@ -36,6 +36,11 @@ pub(crate) fn website(url: &str) -> Uri {
     Uri::from(Url::parse(url).expect("Expected valid Website URI"))
 }
 
+/// A response with the given status for a website found on stdin
+pub(crate) fn response(url: &str, status: Status) -> Response {
+    Response::new(website(url), status, Input::Stdin)
+}
+
 pub(crate) fn mail(address: &str) -> Uri {
     if address.starts_with("mailto:") {
         Url::parse(address)
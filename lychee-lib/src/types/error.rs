@@ -1,9 +1,12 @@
-use std::{any::Any, convert::Infallible, fmt::Display, hash::Hash, path::PathBuf};
+use std::{
+    any::Any, convert::Infallible, error::Error, fmt::Display, hash::Hash, path::PathBuf,
+    time::Duration,
+};
 
 use http::header::InvalidHeaderValue;
 use serde::{Serialize, Serializer};
 
-use crate::Uri;
+use crate::{redact::redact_url, Uri};
 
 /// Kinds of status errors.
 #[allow(clippy::module_name_repetitions)]
@@ -13,8 +16,9 @@ pub enum ErrorKind {
     /// Any form of I/O error occurred while reading from a given path
     // TODO: maybe need to be splitted; currently first slot is Some only for reading files
     IoError(Option<PathBuf>, std::io::Error),
-    /// Network error when trying to connect to an endpoint via reqwest
-    ReqwestError(reqwest::Error),
+    /// Network error when trying to connect to an endpoint via reqwest,
+    /// with its class
+    ReqwestError(NetworkError, reqwest::Error),
     /// Network error when trying to connect to an endpoint via hubcaps
     HubcapsError(hubcaps::Error),
     /// The given string can not be parsed into a valid URL or e-mail address
@@ -27,23 +31,334 @@ pub enum ErrorKind {
     InvalidHeader(InvalidHeaderValue),
     /// The given UNIX glob pattern is invalid
     InvalidGlobPattern(glob::PatternError),
+    /// The given regular expression is invalid
+    InvalidRegex(regex::Error),
     /// The Github API could not be called because of a missing Github token
     MissingGitHubToken,
+    /// More redirects than the given maximum were returned
+    TooManyRedirects(usize),
+    /// The page was found, but most likely is a "not found" page.
+    /// Contains the reason why the page is considered missing.
+    Soft404(&'static str),
+    /// The response body doesn't match the expected content pattern
+    MissingContent(String),
+    /// The domain of a mail address is denied by the mail policy
+    DeniedMailDomain(String),
+    /// The host of a link is not on the list of approved hosts
+    HostNotAllowed(String),
+    /// The line anchor of a link to a file on GitHub is beyond the end of the file
+    MissingGitHubLine(usize),
+    /// The budget of GitHub API requests is exhausted
+    /// and won't be reset within the maximum wait time
+    GitHubRateLimited(Duration),
+    /// No installation token could be obtained for the GitHub App
+    GitHubAppAuth(String),
+    /// No token to pull an image could be obtained from a container registry
+    MissingRegistryToken,
+    /// The package exists in its registry, but the linked version doesn't
+    MissingPackageVersion(String),
+    /// The response has a status code which is neither successful, a redirect
+    /// nor an error, e.g. the non-standard 999 of LinkedIn
+    UnexpectedStatusCode(http::StatusCode),
+    /// The inputs can't be watched for changes
+    WatchError(notify::Error),
+    /// The PDF document at the given path (if any) can't be parsed
+    InvalidPdf(Option<PathBuf>, String),
+    /// The mail message or mailbox at the given path can't be parsed
+    InvalidMailbox(PathBuf, String),
+    /// The given CSS selector is not supported
+    InvalidSelector(String),
+    /// The page at the given URL can't be rendered in a browser
+    RenderError(url::Url, String),
+    /// The header with the given name doesn't match the expected pattern.
+    /// Contains the actual value, unless the header is missing.
+    UnexpectedHeader(String, String, Option<String>),
+    /// The line of a checksum manifest isn't a SHA-256 checksum followed by a URL
+    InvalidChecksum(String),
+    /// The content of a download link doesn't match the expected checksum.
+    /// Contains the expected and the actual checksum.
+    ChecksumMismatch(String, String),
+    /// The JSON report of a previous run can't be parsed
+    InvalidReport(String),
+    /// No host can be reached, most likely because the network is unavailable.
+    /// Contains the number of hosts which were tried.
+    NetworkUnavailable(usize),
+    /// The configuration of the client has problems, which are all listed
+    InvalidConfig(Vec<String>),
+    /// A `data:` URI is malformed, e.g. its base64 content is invalid
+    InvalidDataUri(String),
+    /// An IP network is not in CIDR notation (e.g. `10.42.0.0/16`)
+    InvalidNetwork(String),
+}
+
+impl ErrorKind {
+    /// Whether the error is a violation of a configured policy
+    /// (e.g. a link to an unapproved host) rather than a broken link
+    #[must_use]
+    pub const fn is_policy_violation(&self) -> bool {
+        matches!(self, Self::HostNotAllowed(_) | Self::DeniedMailDomain(_))
+    }
+
+    /// The class of a network error
+    #[must_use]
+    pub const fn network_error(&self) -> Option<NetworkError> {
+        match self {
+            Self::ReqwestError(class, _) => Some(*class),
+            _ => None,
+        }
+    }
+
+    /// A stable code of the class of the error, to handle failures
+    /// without matching their messages. Codes are never changed or reused.
+    ///
+    /// | Code | Error |
+    /// |------|-------|
+    /// | `ERR_TIMEOUT` | The request timed out |
+    /// | `ERR_DNS` | The host name can't be resolved |
+    /// | `ERR_TLS` | The TLS handshake failed, e.g. because of an invalid certificate |
+    /// | `ERR_CONNECTION_REFUSED` | Nothing accepts connections on the port of the host |
+    /// | `ERR_CONNECT` | No connection could be established for another reason |
+    /// | `ERR_HTTP_STATUS` | The response has an error status code |
+    /// | `ERR_BODY` | The response body can't be read or decoded |
+    /// | `ERR_PROTOCOL` | The response isn't valid HTTP |
+    /// | `ERR_NETWORK` | Any other error while sending the request |
+    /// | `ERR_NETWORK_UNAVAILABLE` | No host can be reached at all |
+    /// | `ERR_IO` | A file can't be read |
+    /// | `ERR_INVALID_URL` | A link is neither a valid URL nor a mail address |
+    /// | `ERR_INVALID_HEADER` | A request header is invalid |
+    /// | `ERR_INVALID_GLOB` | A glob pattern is invalid |
+    /// | `ERR_INVALID_REGEX` | A regular expression is invalid |
+    /// | `ERR_INVALID_SELECTOR` | A CSS selector is not supported |
+    /// | `ERR_INVALID_PDF` | A PDF document can't be parsed |
+    /// | `ERR_INVALID_MAILBOX` | A mail message or mailbox can't be parsed |
+    /// | `ERR_TOO_MANY_REDIRECTS` | A link redirects too often |
+    /// | `ERR_SOFT_404` | A page seems to be missing, despite a successful status |
+    /// | `ERR_CONTENT_MISSING` | A page doesn't contain the expected content |
+    /// | `ERR_UNEXPECTED_HEADER` | A header of a response doesn't match the expected pattern |
+    /// | `ERR_INVALID_CHECKSUM` | A line of a checksum manifest is invalid |
+    /// | `ERR_CHECKSUM_MISMATCH` | A download doesn't match its expected checksum |
+    /// | `ERR_INVALID_REPORT` | The JSON report of a previous run can't be parsed |
+    /// | `ERR_INVALID_CONFIG` | The configuration has problems, e.g. an unreachable proxy |
+    /// | `ERR_INVALID_DATA_URI` | A `data:` URI is malformed |
+    /// | `ERR_INVALID_NETWORK` | An IP network is not in CIDR notation |
+    /// | `ERR_MAIL_UNREACHABLE` | A mail address is unreachable |
+    /// | `ERR_MAIL_DOMAIN_DENIED` | The domain of a mail address is denied by the mail policy |
+    /// | `ERR_HOST_NOT_ALLOWED` | The host of a link is not approved |
+    /// | `ERR_GITHUB` | A request to the GitHub API failed |
+    /// | `ERR_GITHUB_TOKEN_MISSING` | A GitHub link can't be checked without a token |
+    /// | `ERR_GITHUB_APP_AUTH` | No token could be obtained for the GitHub App |
+    /// | `ERR_GITHUB_LINE_MISSING` | A file on GitHub doesn't have the linked line |
+    /// | `ERR_RATE_LIMITED` | The budget of GitHub API requests is exhausted |
+    /// | `ERR_REGISTRY_TOKEN_MISSING` | No token could be obtained from a container registry |
+    /// | `ERR_PACKAGE_VERSION_MISSING` | A package doesn't have the linked version |
+    /// | `ERR_WATCH` | The inputs can't be watched for changes |
+    /// | `ERR_RENDER` | A page can't be rendered in a browser |
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::IoError(..) => "ERR_IO",
+            Self::ReqwestError(class, _) => class.code(),
+            Self::HubcapsError(_) => "ERR_GITHUB",
+            Self::UrlParseError(..) => "ERR_INVALID_URL",
+            Self::UnreachableEmailAddress(_) => "ERR_MAIL_UNREACHABLE",
+            Self::InvalidHeader(_) => "ERR_INVALID_HEADER",
+            Self::InvalidGlobPattern(_) => "ERR_INVALID_GLOB",
+            Self::InvalidRegex(_) => "ERR_INVALID_REGEX",
+            Self::MissingGitHubToken => "ERR_GITHUB_TOKEN_MISSING",
+            Self::TooManyRedirects(_) => "ERR_TOO_MANY_REDIRECTS",
+            Self::Soft404(_) => "ERR_SOFT_404",
+            Self::MissingContent(_) => "ERR_CONTENT_MISSING",
+            Self::DeniedMailDomain(_) => "ERR_MAIL_DOMAIN_DENIED",
+            Self::HostNotAllowed(_) => "ERR_HOST_NOT_ALLOWED",
+            Self::MissingGitHubLine(_) => "ERR_GITHUB_LINE_MISSING",
+            Self::GitHubRateLimited(_) => "ERR_RATE_LIMITED",
+            Self::GitHubAppAuth(_) => "ERR_GITHUB_APP_AUTH",
+            Self::MissingRegistryToken => "ERR_REGISTRY_TOKEN_MISSING",
+            Self::MissingPackageVersion(_) => "ERR_PACKAGE_VERSION_MISSING",
+            Self::UnexpectedStatusCode(_) => "ERR_HTTP_STATUS",
+            Self::WatchError(_) => "ERR_WATCH",
+            Self::InvalidPdf(..) => "ERR_INVALID_PDF",
+            Self::InvalidMailbox(..) => "ERR_INVALID_MAILBOX",
+            Self::InvalidSelector(_) => "ERR_INVALID_SELECTOR",
+            Self::RenderError(..) => "ERR_RENDER",
+            Self::UnexpectedHeader(..) => "ERR_UNEXPECTED_HEADER",
+            Self::InvalidChecksum(_) => "ERR_INVALID_CHECKSUM",
+            Self::ChecksumMismatch(..) => "ERR_CHECKSUM_MISMATCH",
+            Self::InvalidReport(_) => "ERR_INVALID_REPORT",
+            Self::NetworkUnavailable(_) => "ERR_NETWORK_UNAVAILABLE",
+            Self::InvalidConfig(_) => "ERR_INVALID_CONFIG",
+            Self::InvalidDataUri(_) => "ERR_INVALID_DATA_URI",
+            Self::InvalidNetwork(_) => "ERR_INVALID_NETWORK",
+        }
+    }
+}
+
+/// The class of a failed request, so reports and retries can tell
+/// e.g. DNS errors from refused connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkError {
+    /// The host name can't be resolved
+    Dns,
+    /// The TLS handshake failed, e.g. because of an invalid certificate
+    Tls,
+    /// Nothing accepts connections on the port of the host
+    ConnectionRefused,
+    /// No connection could be established for another reason
+    Connect,
+    /// The request timed out
+    Timeout,
+    /// The response has an error status code
+    Status,
+    /// The response body can't be read or decoded
+    Decode,
+    /// The response isn't valid HTTP
+    Protocol,
+    /// The client followed too many redirects
+    TooManyRedirects,
+    /// Any other error while sending the request
+    Other,
+}
+
+impl NetworkError {
+    /// Classify an error of reqwest. DNS, TLS and protocol errors are only
+    /// known by the messages of their sources, which differ between backends.
+    #[must_use]
+    pub fn classify(e: &reqwest::Error) -> Self {
+        if e.is_timeout() {
+            return Self::Timeout;
+        }
+        if e.is_status() {
+            return Self::Status;
+        }
+        if e.is_redirect() {
+            return Self::TooManyRedirects;
+        }
+        let mut messages = String::new();
+        let mut refused = false;
+        let mut source = e.source();
+        while let Some(error) = source {
+            refused |= error
+                .downcast_ref::<std::io::Error>()
+                .map_or(false, |e| e.kind() == std::io::ErrorKind::ConnectionRefused);
+            messages.push_str(&error.to_string().to_lowercase());
+            messages.push('\n');
+            source = error.source();
+        }
+        let contains = |markers: &[&str]| markers.iter().any(|marker| messages.contains(marker));
+        if contains(&["dns error", "failed to lookup address"]) {
+            Self::Dns
+        } else if contains(&["certificate", "tls", "ssl", "handshake"]) {
+            Self::Tls
+        } else if refused {
+            Self::ConnectionRefused
+        } else if e.is_connect() {
+            Self::Connect
+        } else if e.is_body() || e.is_decode() {
+            Self::Decode
+        } else if contains(&["invalid http", "parse", "http/0.9"]) {
+            Self::Protocol
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Whether a request may succeed when it is repeated.
+    /// Invalid certificates and redirect loops don't go away by retrying.
+    #[must_use]
+    pub const fn is_transient(self) -> bool {
+        !matches!(self, Self::Tls | Self::TooManyRedirects)
+    }
+
+    /// Whether no connection to the host could be established,
+    /// which is also the case if the network is unavailable
+    #[must_use]
+    pub const fn is_connectivity(self) -> bool {
+        matches!(
+            self,
+            Self::Dns | Self::ConnectionRefused | Self::Connect | Self::Timeout
+        )
+    }
+
+    /// The [code](ErrorKind::code) of errors of this class
+    #[must_use]
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Dns => "ERR_DNS",
+            Self::Tls => "ERR_TLS",
+            Self::ConnectionRefused => "ERR_CONNECTION_REFUSED",
+            Self::Connect => "ERR_CONNECT",
+            Self::Timeout => "ERR_TIMEOUT",
+            Self::Status => "ERR_HTTP_STATUS",
+            Self::Decode => "ERR_BODY",
+            Self::Protocol => "ERR_PROTOCOL",
+            Self::TooManyRedirects => "ERR_TOO_MANY_REDIRECTS",
+            Self::Other => "ERR_NETWORK",
+        }
+    }
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Dns => "DNS error",
+            Self::Tls => "TLS error",
+            Self::ConnectionRefused => "connection refused",
+            Self::Connect => "connection error",
+            Self::Timeout => "timeout",
+            Self::Status => "error status",
+            Self::Decode => "invalid body",
+            Self::Protocol => "protocol error",
+            Self::TooManyRedirects => "too many redirects",
+            Self::Other => "network error",
+        })
+    }
 }
 
 impl PartialEq for ErrorKind {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::IoError(p1, e1), Self::IoError(p2, e2)) => p1 == p2 && e1.kind() == e2.kind(),
-            (Self::ReqwestError(e1), Self::ReqwestError(e2)) => e1.to_string() == e2.to_string(),
+            (Self::ReqwestError(c1, e1), Self::ReqwestError(c2, e2)) => {
+                c1 == c2 && e1.to_string() == e2.to_string()
+            }
             (Self::HubcapsError(e1), Self::HubcapsError(e2)) => e1.to_string() == e2.to_string(),
             (Self::UrlParseError(s1, e1), Self::UrlParseError(s2, e2)) => s1 == s2 && e1 == e2,
             (Self::UnreachableEmailAddress(u1), Self::UnreachableEmailAddress(u2)) => u1 == u2,
             (Self::InvalidGlobPattern(e1), Self::InvalidGlobPattern(e2)) => {
                 e1.msg == e2.msg && e1.pos == e2.pos
             }
+            (Self::InvalidRegex(e1), Self::InvalidRegex(e2)) => e1 == e2,
+            (Self::TooManyRedirects(m1), Self::TooManyRedirects(m2)) => m1 == m2,
+            (Self::Soft404(r1), Self::Soft404(r2)) => r1 == r2,
+            (Self::MissingContent(p1), Self::MissingContent(p2)) => p1 == p2,
+            (Self::DeniedMailDomain(d1), Self::DeniedMailDomain(d2)) => d1 == d2,
+            (Self::HostNotAllowed(h1), Self::HostNotAllowed(h2)) => h1 == h2,
+            (Self::MissingGitHubLine(l1), Self::MissingGitHubLine(l2)) => l1 == l2,
+            (Self::GitHubRateLimited(r1), Self::GitHubRateLimited(r2)) => r1 == r2,
+            (Self::GitHubAppAuth(e1), Self::GitHubAppAuth(e2)) => e1 == e2,
+            (Self::MissingPackageVersion(v1), Self::MissingPackageVersion(v2)) => v1 == v2,
+            (Self::UnexpectedStatusCode(c1), Self::UnexpectedStatusCode(c2)) => c1 == c2,
+            (Self::WatchError(e1), Self::WatchError(e2)) => e1.to_string() == e2.to_string(),
+            (Self::InvalidPdf(p1, e1), Self::InvalidPdf(p2, e2)) => p1 == p2 && e1 == e2,
+            (Self::InvalidMailbox(p1, e1), Self::InvalidMailbox(p2, e2)) => p1 == p2 && e1 == e2,
+            (Self::InvalidSelector(s1), Self::InvalidSelector(s2)) => s1 == s2,
+            (Self::RenderError(u1, e1), Self::RenderError(u2, e2)) => u1 == u2 && e1 == e2,
+            (Self::UnexpectedHeader(n1, p1, v1), Self::UnexpectedHeader(n2, p2, v2)) => {
+                n1 == n2 && p1 == p2 && v1 == v2
+            }
+            (Self::InvalidChecksum(l1), Self::InvalidChecksum(l2)) => l1 == l2,
+            (Self::ChecksumMismatch(e1, a1), Self::ChecksumMismatch(e2, a2)) => {
+                e1 == e2 && a1 == a2
+            }
+            (Self::InvalidReport(e1), Self::InvalidReport(e2)) => e1 == e2,
+            (Self::NetworkUnavailable(h1), Self::NetworkUnavailable(h2)) => h1 == h2,
+            (Self::InvalidConfig(p1), Self::InvalidConfig(p2)) => p1 == p2,
+            (Self::InvalidDataUri(p1), Self::InvalidDataUri(p2)) => p1 == p2,
+            (Self::InvalidNetwork(n1), Self::InvalidNetwork(n2)) => n1 == n2,
             (Self::InvalidHeader(_), Self::InvalidHeader(_))
-            | (Self::MissingGitHubToken, Self::MissingGitHubToken) => true,
+            | (Self::MissingGitHubToken, Self::MissingGitHubToken)
+            | (Self::MissingRegistryToken, Self::MissingRegistryToken) => true,
             _ => false,
         }
     }
@@ -58,13 +373,39 @@ impl Hash for ErrorKind {
     {
         match self {
             Self::IoError(p, e) => (p, e.kind()).hash(state),
-            Self::ReqwestError(e) => e.to_string().hash(state),
+            Self::ReqwestError(class, e) => (class, e.to_string()).hash(state),
             Self::HubcapsError(e) => e.to_string().hash(state),
             Self::UrlParseError(s, e) => (s, e.type_id()).hash(state),
             Self::UnreachableEmailAddress(u) => u.hash(state),
             Self::InvalidHeader(e) => e.to_string().hash(state),
             Self::InvalidGlobPattern(e) => e.to_string().hash(state),
-            Self::MissingGitHubToken => std::mem::discriminant(self).hash(state),
+            Self::InvalidRegex(e) => e.to_string().hash(state),
+            Self::MissingGitHubToken | Self::MissingRegistryToken => {
+                std::mem::discriminant(self).hash(state);
+            }
+            Self::TooManyRedirects(max) => max.hash(state),
+            Self::Soft404(reason) => reason.hash(state),
+            Self::MissingContent(pattern) => pattern.hash(state),
+            Self::DeniedMailDomain(domain) => domain.hash(state),
+            Self::HostNotAllowed(host) => host.hash(state),
+            Self::MissingGitHubLine(line) => line.hash(state),
+            Self::GitHubRateLimited(reset) => reset.hash(state),
+            Self::GitHubAppAuth(e) => e.hash(state),
+            Self::MissingPackageVersion(version) => version.hash(state),
+            Self::UnexpectedStatusCode(code) => code.hash(state),
+            Self::WatchError(e) => e.to_string().hash(state),
+            Self::InvalidPdf(p, e) => (p, e).hash(state),
+            Self::InvalidMailbox(p, e) => (p, e).hash(state),
+            Self::InvalidSelector(s) => s.hash(state),
+            Self::RenderError(u, e) => (u, e).hash(state),
+            Self::UnexpectedHeader(n, p, v) => (n, p, v).hash(state),
+            Self::InvalidChecksum(line) => line.hash(state),
+            Self::ChecksumMismatch(e, a) => (e, a).hash(state),
+            Self::InvalidReport(e) => e.hash(state),
+            Self::NetworkUnavailable(hosts) => hosts.hash(state),
+            Self::InvalidConfig(problems) => problems.hash(state),
+            Self::InvalidDataUri(problem) => problem.hash(state),
+            Self::InvalidNetwork(network) => network.hash(state),
         }
     }
 }
@@ -79,7 +420,7 @@ impl Display for ErrorKind {
                 e
             ),
             Self::IoError(None, e) => e.fmt(f),
-            Self::ReqwestError(e) => e.fmt(f),
+            Self::ReqwestError(_, e) => e.fmt(f),
             Self::HubcapsError(e) => e.fmt(f),
             Self::UrlParseError(s, (url_err, Some(mail_err))) => {
                 write!(
@@ -94,10 +435,95 @@ impl Display for ErrorKind {
             Self::UnreachableEmailAddress(uri) => write!(f, "Unreachable mail address: {}", uri),
             Self::InvalidHeader(e) => e.fmt(f),
             Self::InvalidGlobPattern(e) => e.fmt(f),
+            Self::InvalidRegex(e) => e.fmt(f),
             Self::MissingGitHubToken => f.write_str(
                 "GitHub token not specified. To check GitHub links reliably, \
                  use `--github-token` flag / `GITHUB_TOKEN` env var.",
             ),
+            Self::TooManyRedirects(max) => {
+                write!(f, "Too many redirects (maximum: {})", max)
+            }
+            Self::Soft404(reason) => write!(f, "Page seems to be missing: {}", reason),
+            Self::MissingContent(pattern) => {
+                write!(f, "Expected content not found (pattern: {})", pattern)
+            }
+            Self::DeniedMailDomain(domain) => {
+                write!(f, "Mail domain is not allowed: {}", domain)
+            }
+            Self::HostNotAllowed(host) => write!(f, "Host is not approved: {}", host),
+            Self::MissingGitHubLine(line) => write!(f, "File has no line {}", line),
+            Self::GitHubRateLimited(reset) => write!(
+                f,
+                "GitHub API rate limit exceeded, resets in {}s",
+                reset.as_secs()
+            ),
+            Self::GitHubAppAuth(e) => write!(f, "Cannot authenticate as GitHub App: {}", e),
+            Self::MissingRegistryToken => {
+                f.write_str("Cannot get a token to pull the image from the container registry")
+            }
+            Self::MissingPackageVersion(version) => {
+                write!(f, "Package has no version {}", version)
+            }
+            Self::UnexpectedStatusCode(code) => write!(f, "Unexpected status code {}", code),
+            Self::WatchError(e) => write!(f, "Cannot watch inputs for changes: {}", e),
+            Self::InvalidPdf(Some(p), e) => write!(
+                f,
+                "Cannot read PDF: `{}`, reason: {}",
+                p.to_str().unwrap_or("<MALFORMED PATH>"),
+                e
+            ),
+            Self::InvalidPdf(None, e) => write!(f, "Cannot read PDF: {}", e),
+            Self::InvalidMailbox(p, e) => write!(
+                f,
+                "Cannot read mail: `{}`, reason: {}",
+                p.to_str().unwrap_or("<MALFORMED PATH>"),
+                e
+            ),
+            Self::InvalidSelector(s) => write!(
+                f,
+                "Unsupported CSS selector: `{}` (expected e.g. `a.no-check`, `#toc` or `[data-skip]`)",
+                s
+            ),
+            Self::RenderError(u, e) => {
+                let mut u = u.clone();
+                redact_url(&mut u);
+                write!(f, "Cannot render `{}` in a browser: {}", u, e)
+            }
+            Self::UnexpectedHeader(name, pattern, Some(value)) => write!(
+                f,
+                "Header `{}` doesn't match `{}`: {}",
+                name, pattern, value
+            ),
+            Self::UnexpectedHeader(name, pattern, None) => write!(
+                f,
+                "Header `{}` is missing (expected to match `{}`)",
+                name, pattern
+            ),
+            Self::InvalidChecksum(line) => write!(
+                f,
+                "Invalid checksum: `{}` (expected a SHA-256 checksum followed by a URL)",
+                line
+            ),
+            Self::ChecksumMismatch(expected, actual) => write!(
+                f,
+                "Checksum mismatch: expected {}, got {}",
+                expected, actual
+            ),
+            Self::InvalidReport(e) => write!(f, "Invalid JSON report: {}", e),
+            Self::NetworkUnavailable(hosts) => write!(
+                f,
+                "The network seems to be unavailable: none of {} hosts can be reached",
+                hosts
+            ),
+            Self::InvalidConfig(problems) => {
+                write!(f, "Invalid configuration: {}", problems.join("; "))
+            }
+            Self::InvalidDataUri(problem) => write!(f, "Invalid data URI: {}", problem),
+            Self::InvalidNetwork(network) => write!(
+                f,
+                "Invalid IP network: {}, expected CIDR notation like 10.42.0.0/16",
+                network
+            ),
         }
     }
 }
@@ -130,8 +556,12 @@ impl From<tokio::task::JoinError> for ErrorKind {
 }
 
 impl From<reqwest::Error> for ErrorKind {
-    fn from(e: reqwest::Error) -> Self {
-        Self::ReqwestError(e)
+    fn from(mut e: reqwest::Error) -> Self {
+        // The message of the error contains the URL
+        if let Some(url) = e.url_mut() {
+            redact_url(url);
+        }
+        Self::ReqwestError(NetworkError::classify(&e), e)
     }
 }
 
@@ -165,9 +595,107 @@ impl From<glob::PatternError> for ErrorKind {
     }
 }
 
+impl From<notify::Error> for ErrorKind {
+    fn from(e: notify::Error) -> Self {
+        Self::WatchError(e)
+    }
+}
+
+impl From<regex::Error> for ErrorKind {
+    fn from(e: regex::Error) -> Self {
+        Self::InvalidRegex(e)
+    }
+}
+
 impl From<Infallible> for ErrorKind {
     fn from(_: Infallible) -> Self {
         // tautological
         unreachable!()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, path::PathBuf, time::Duration};
+
+    use http::StatusCode;
+
+    use super::{ErrorKind, NetworkError};
+    use crate::{test_utils::website, ResponseBody, Status};
+
+    #[test]
+    fn test_codes() {
+        let errors = vec![
+            ErrorKind::IoError(
+                Some(PathBuf::from("README.md")),
+                std::io::ErrorKind::NotFound.into(),
+            ),
+            ErrorKind::MissingGitHubToken,
+            ErrorKind::TooManyRedirects(5),
+            ErrorKind::Soft404("title"),
+            ErrorKind::HostNotAllowed("example.org".to_owned()),
+            ErrorKind::GitHubRateLimited(Duration::from_secs(60)),
+            ErrorKind::UnexpectedStatusCode(StatusCode::from_u16(999).unwrap()),
+            ErrorKind::ChecksumMismatch("ab".repeat(32), "cd".repeat(32)),
+            ErrorKind::InvalidReport("expected value".to_owned()),
+            ErrorKind::NetworkUnavailable(10),
+            ErrorKind::InvalidConfig(vec!["Adaptive timeouts need a timeout".to_owned()]),
+            ErrorKind::InvalidDataUri("invalid base64".to_owned()),
+            ErrorKind::InvalidNetwork("10.42.0.0/33".to_owned()),
+        ];
+        let codes: Vec<&str> = errors.iter().map(ErrorKind::code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "ERR_IO",
+                "ERR_GITHUB_TOKEN_MISSING",
+                "ERR_TOO_MANY_REDIRECTS",
+                "ERR_SOFT_404",
+                "ERR_HOST_NOT_ALLOWED",
+                "ERR_RATE_LIMITED",
+                "ERR_HTTP_STATUS",
+                "ERR_CHECKSUM_MISMATCH",
+                "ERR_INVALID_REPORT",
+                "ERR_NETWORK_UNAVAILABLE",
+                "ERR_INVALID_CONFIG",
+                "ERR_INVALID_DATA_URI",
+                "ERR_INVALID_NETWORK",
+            ]
+        );
+        assert_eq!(codes.iter().collect::<HashSet<_>>().len(), codes.len());
+    }
+
+    #[test]
+    fn test_serialized_code() {
+        let failed = ResponseBody::new(
+            website("https://example.org"),
+            ErrorKind::HostNotAllowed("example.org".to_owned()).into(),
+        );
+        let json = serde_json::to_value(&failed).unwrap();
+        assert_eq!(json["status"], "Failed: Host is not approved: example.org");
+        assert_eq!(json["error_code"], "ERR_HOST_NOT_ALLOWED");
+
+        let ok = ResponseBody::new(website("https://example.org"), Status::Ok(StatusCode::OK));
+        let json = serde_json::to_value(&ok).unwrap();
+        assert!(json.get("error_code").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_network_errors() {
+        // Nothing listens on port 1
+        let refused = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+        assert_eq!(
+            NetworkError::classify(&refused),
+            NetworkError::ConnectionRefused
+        );
+        let unresolved = reqwest::get("http://example.invalid").await.unwrap_err();
+        assert_eq!(NetworkError::classify(&unresolved), NetworkError::Dns);
+
+        let error = ErrorKind::from(unresolved);
+        assert_eq!(error.network_error(), Some(NetworkError::Dns));
+        assert_eq!(error.code(), "ERR_DNS");
+        assert!(!NetworkError::Tls.is_transient());
+        assert!(NetworkError::Dns.is_connectivity());
+        assert!(!NetworkError::Status.is_connectivity());
+    }
+}
@@ -4,11 +4,13 @@ mod error;
 mod request;
 mod response;
 mod status;
+mod timeline;
 
-pub use error::ErrorKind;
-pub use request::Request;
-pub use response::{Response, ResponseBody};
-pub use status::Status;
+pub use error::{ErrorKind, NetworkError};
+pub use request::{Request, Span};
+pub use response::{Redirect, Response, ResponseBody};
+pub use status::{SkipReason, Status};
+pub use timeline::{CheckPhase, Timeline, TimelineEvent};
 
 /// The lychee `Result` type
 pub type Result<T> = std::result::Result<T, crate::ErrorKind>;
@@ -1,7 +1,28 @@
-use std::{convert::TryFrom, fmt::Display};
+use std::{collections::BTreeMap, convert::TryFrom, fmt::Display};
+
+use serde::Serialize;
 
 use crate::{ErrorKind, Input, Uri};
 
+/// Location of a link in the content of its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Span {
+    /// Byte offset of the start of the link
+    pub start: usize,
+    /// Byte offset after the end of the link
+    pub end: usize,
+    /// Line of the start of the link, starting at 1
+    pub line: usize,
+    /// Column of the start of the link in characters, starting at 1
+    pub column: usize,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 /// A request type that can be handle by lychee
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Request {
@@ -10,6 +31,16 @@ pub struct Request {
     pub uri: Uri,
     /// The resource which contained the given URI
     pub source: Input,
+    /// Labels of the input the URI was found in (e.g. `docs` or `generated`)
+    pub tags: Vec<String>,
+    /// Metadata of the input the URI was found in (e.g. the owning team),
+    /// which is carried through to the response
+    pub metadata: BTreeMap<String, String>,
+    /// Locations of the URI in the input, if it appears there verbatim
+    pub spans: Vec<Span>,
+    /// Problems with the link as written, e.g. invalid characters which had
+    /// to be percent-encoded. They end up in the suggestions of the response.
+    pub warnings: Vec<String>,
 }
 
 impl Request {
@@ -17,7 +48,14 @@ impl Request {
     #[inline]
     #[must_use]
     pub const fn new(uri: Uri, source: Input) -> Self {
-        Request { uri, source }
+        Request {
+            uri,
+            source,
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            spans: Vec::new(),
+            warnings: Vec::new(),
+        }
     }
 }
 
@@ -1,8 +1,13 @@
-use std::fmt::Display;
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
 
-use serde::Serialize;
+use serde::{ser::SerializeMap, Serialize, Serializer};
 
-use crate::{Input, Status, Uri};
+use crate::{Input, Span, Status, Timeline, Uri};
 
 /// Response type returned by lychee after checking a URI
 #[derive(Debug)]
@@ -13,7 +18,7 @@ impl Response {
     #[must_use]
     /// Create new response
     pub const fn new(uri: Uri, status: Status, source: Input) -> Self {
-        Response(source, ResponseBody { uri, status })
+        Response(source, ResponseBody::new(uri, status))
     }
 
     #[inline]
@@ -40,14 +45,126 @@ impl Serialize for Response {
 }
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Serialize, Hash, PartialEq, Eq)]
-/// Encapsulates the state of a URI check
+#[derive(Debug, Serialize)]
+/// Encapsulates the state of a URI check.
+///
+/// Two bodies are equal if they have the same URI, status and spans.
+/// Measurements like the duration, redirect latencies or the timeline
+/// differ between otherwise identical checks, so they are ignored.
 pub struct ResponseBody {
     #[serde(flatten)]
     /// The URI which was checked
     pub uri: Uri,
-    /// The status of the check
+    /// The status of the check, serialized with its error code (if any)
+    #[serde(flatten, serialize_with = "serialize_status")]
     pub status: Status,
+    /// Labels of the input the URI was found in
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Redirects which were followed before the final response, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub redirects: Vec<Redirect>,
+    /// Hints on how to fix or handle the link, e.g. from post-processors
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<String>,
+    /// URI which the link should be replaced with, e.g. after a permanent redirect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<Uri>,
+    /// Locations of the URI in its input
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub spans: Vec<Span>,
+    /// Detailed timing information, only recorded for selected URIs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeline: Option<Timeline>,
+    /// How long the check took, including retries and post-processing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<Duration>,
+    /// Selected headers of the final response by their (lowercase) name,
+    /// if recorded (see [`HeaderRecorder`](crate::post_process::HeaderRecorder))
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Metadata of the input the URI was found in, e.g. the owning team
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl ResponseBody {
+    #[inline]
+    #[must_use]
+    /// Create new response body without tags, redirects, suggestions, spans and metadata
+    pub const fn new(uri: Uri, status: Status) -> Self {
+        ResponseBody {
+            uri,
+            status,
+            tags: Vec::new(),
+            redirects: Vec::new(),
+            suggestions: Vec::new(),
+            replacement: None,
+            spans: Vec::new(),
+            timeline: None,
+            duration: None,
+            headers: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Copy the response to answer later occurrences of its URI, if the
+    /// check succeeded or its outcome is otherwise final (e.g. a warning).
+    /// Failures and timeouts aren't copied, so they get checked again.
+    #[must_use]
+    pub fn try_clone(&self) -> Option<Self> {
+        let status = match self.status {
+            Status::Ok(code) => Status::Ok(code),
+            Status::Redirected(code) => Status::Redirected(code),
+            Status::ProtectedByBotCheck(code) => Status::ProtectedByBotCheck(code),
+            Status::Warning(code) => Status::Warning(code),
+            Status::Excluded => Status::Excluded,
+            Status::Error(_) | Status::Timeout(_) | Status::Unsupported(_) | Status::Skipped(_) => {
+                return None
+            }
+        };
+        Some(ResponseBody {
+            uri: self.uri.clone(),
+            status,
+            tags: self.tags.clone(),
+            redirects: self.redirects.clone(),
+            suggestions: self.suggestions.clone(),
+            replacement: self.replacement.clone(),
+            spans: self.spans.clone(),
+            timeline: self.timeline.clone(),
+            duration: self.duration,
+            headers: self.headers.clone(),
+            metadata: self.metadata.clone(),
+        })
+    }
+}
+
+impl PartialEq for ResponseBody {
+    fn eq(&self, other: &Self) -> bool {
+        self.uri == other.uri && self.status == other.status && self.spans == other.spans
+    }
+}
+
+impl Eq for ResponseBody {}
+
+impl Hash for ResponseBody {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.uri.hash(state);
+        self.status.hash(state);
+        self.spans.hash(state);
+    }
+}
+
+fn serialize_status<S>(status: &Status, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(None)?;
+    map.serialize_entry("status", status)?;
+    if let Some(code) = status.error_code() {
+        map.serialize_entry("error_code", code)?;
+    }
+    map.end()
 }
 
 impl Display for ResponseBody {
@@ -55,17 +172,43 @@ impl Display for ResponseBody {
         let ResponseBody {
             ref uri,
             ref status,
+            ..
         } = self;
 
         // TODO: Other errors?
         let metadata = match status {
-            Status::Ok(code) | Status::Redirected(code) => {
-                format!(" [{}]", code)
-            }
+            Status::Ok(code)
+            | Status::Redirected(code)
+            | Status::ProtectedByBotCheck(code)
+            | Status::Warning(code) => format!(" [{}]", code),
             Status::Timeout(Some(code)) => format!(" [{}]", code),
             Status::Error(e) => format!(" ({})", e),
+            Status::Skipped(reason) => format!(" ({})", reason),
             _ => "".to_owned(),
         };
         write!(f, "{} {}{}", status.icon(), uri, metadata)
     }
 }
+
+/// A single hop of a followed redirect
+#[derive(Debug, Clone, Serialize, Hash, PartialEq, Eq)]
+pub struct Redirect {
+    /// The URI which responded with a redirect
+    pub uri: Uri,
+    /// The redirect status code (e.g. 301)
+    pub code: u16,
+    /// Time until the redirect response was received
+    pub latency: Duration,
+}
+
+impl Display for Redirect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} [{}] ({}ms)",
+            self.uri,
+            self.code,
+            self.latency.as_millis()
+        )
+    }
+}
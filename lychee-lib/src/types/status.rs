@@ -1,10 +1,10 @@
 use std::{collections::HashSet, fmt::Display};
 
-use http::StatusCode;
+use http::{header, StatusCode};
 use reqwest::Response;
 use serde::{Serialize, Serializer};
 
-use crate::ErrorKind;
+use crate::{ErrorKind, NetworkError};
 
 const ICON_OK: &str = "\u{2714}"; // ✔
 const ICON_REDIRECTED: &str = "\u{21c4}"; // ⇄
@@ -12,6 +12,9 @@ const ICON_EXCLUDED: &str = "\u{003f}"; // ?
 const ICON_UNSUPPORTED: &str = "\u{003f}"; // ? (using same icon, but under different name for explicitness)
 const ICON_ERROR: &str = "\u{2717}"; // ✗
 const ICON_TIMEOUT: &str = "\u{29d6}"; // ⧖
+const ICON_BOT_CHECK: &str = "\u{26a0}"; // ⚠
+const ICON_WARNING: &str = "\u{2755}"; // ❕
+const ICON_SKIPPED: &str = "\u{21b7}"; // ↷
 
 /// Response status of the request.
 #[allow(variant_size_differences)]
@@ -31,6 +34,51 @@ pub enum Status {
     /// for example when the URL scheme is `slack://` or `file://`
     /// See https://github.com/lycheeverse/lychee/issues/199
     Unsupported(Box<ErrorKind>),
+    /// A bot protection service (e.g. Cloudflare) answered with a challenge
+    /// instead of the resource. The resource most likely exists,
+    /// but it can't be checked automatically.
+    ProtectedByBotCheck(StatusCode),
+    /// The response has a status code which a rule marks as a warning
+    /// (e.g. 999 from linkedin.com). It is reported, but isn't a failure.
+    Warning(StatusCode),
+    /// The link would have been checked, but wasn't.
+    /// Unlike excluded links, it's unknown whether the link works.
+    Skipped(SkipReason),
+}
+
+/// Why a link wasn't checked
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// No requests may be sent, e.g. by a pipeline stage for offline runs
+    Offline,
+    /// The run was cancelled before the link was checked
+    Cancelled,
+    /// Links with this scheme can't be checked (e.g. `slack://` or `file://`)
+    UnsupportedScheme,
+    /// The budget of API requests (e.g. of GitHub) is exhausted
+    /// and won't be reset in time
+    RateLimited,
+    /// The link contains its content itself (`data:`)
+    DataUri,
+    /// The link runs a script (`javascript:`) instead of pointing to a resource
+    Script,
+    /// The link points to a page of the browser itself (e.g. `about:blank`)
+    BrowserPage,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SkipReason::Offline => "offline",
+            SkipReason::Cancelled => "cancelled",
+            SkipReason::UnsupportedScheme => "unsupported scheme",
+            SkipReason::RateLimited => "rate limited",
+            SkipReason::DataUri => "data URI",
+            SkipReason::Script => "javascript link",
+            SkipReason::BrowserPage => "browser page",
+        })
+    }
 }
 
 impl Display for Status {
@@ -42,6 +90,9 @@ impl Display for Status {
             Status::Timeout(Some(c)) => write!(f, "Timeout ({})", c),
             Status::Timeout(None) => f.write_str("Timeout"),
             Status::Unsupported(e) => write!(f, "Unsupported: {}", e),
+            Status::ProtectedByBotCheck(c) => write!(f, "Protected by bot check ({})", c),
+            Status::Warning(c) => write!(f, "Warning ({})", c),
+            Status::Skipped(reason) => write!(f, "Skipped ({})", reason),
             Status::Error(e) => write!(f, "Failed: {}", e),
         }
     }
@@ -57,7 +108,6 @@ impl Serialize for Status {
 }
 
 impl Status {
-    #[allow(clippy::missing_panics_doc)]
     #[must_use]
     /// Create a status object from a response and the set of accepted status codes
     pub fn new(response: &Response, accepted: Option<HashSet<StatusCode>>) -> Self {
@@ -65,12 +115,15 @@ impl Status {
 
         if let Some(true) = accepted.map(|a| a.contains(&code)) {
             Self::Ok(code)
+        } else if is_bot_challenge(response) {
+            Self::ProtectedByBotCheck(code)
         } else {
             match response.error_for_status_ref() {
                 Ok(_) if code.is_success() => Self::Ok(code),
                 Ok(_) if code.is_redirection() => Self::Redirected(code),
                 Err(e) => e.into(),
-                Ok(_) => unreachable!(),
+                // Informational or non-standard codes, like 999 from LinkedIn
+                Ok(_) => ErrorKind::UnexpectedStatusCode(code).into(),
             }
         }
     }
@@ -110,6 +163,79 @@ impl Status {
         matches!(self, Status::Unsupported(_))
     }
 
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the check was blocked by a bot protection service
+    pub const fn is_bot_check(&self) -> bool {
+        matches!(self, Status::ProtectedByBotCheck(_))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if a rule marked the response as a warning
+    pub const fn is_warning(&self) -> bool {
+        matches!(self, Status::Warning(_))
+    }
+
+    #[inline]
+    #[must_use]
+    /// Returns `true` if the link wasn't checked
+    pub const fn is_skipped(&self) -> bool {
+        matches!(self, Status::Skipped(_))
+    }
+
+    #[must_use]
+    /// Returns `true` if the request timed out or failed with a network error
+    /// which may go away by repeating it. Other errors (e.g. redirect loops,
+    /// soft 404s or checksum mismatches) and responses which rules turned into
+    /// warnings, exclusions or bot checks are final.
+    pub fn is_transient_failure(&self) -> bool {
+        match self {
+            Status::Error(e) => e.network_error().map_or(false, NetworkError::is_transient),
+            Status::Timeout(_) => true,
+            _ => false,
+        }
+    }
+
+    #[must_use]
+    /// Return the class of the network error of a failed request
+    pub fn network_error(&self) -> Option<NetworkError> {
+        match self {
+            Status::Error(e) | Status::Unsupported(e) => e.network_error(),
+            Status::Timeout(_) => Some(NetworkError::Timeout),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Return the [code of the error](ErrorKind::code) of a failed check,
+    /// or `ERR_TIMEOUT` if the check timed out
+    pub fn error_code(&self) -> Option<&'static str> {
+        match self {
+            Status::Error(e) | Status::Unsupported(e) => Some(e.code()),
+            Status::Timeout(_) => Some("ERR_TIMEOUT"),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    /// Return the HTTP status code of the response, if there was one
+    pub fn code(&self) -> Option<StatusCode> {
+        match self {
+            Status::Ok(code)
+            | Status::Redirected(code)
+            | Status::ProtectedByBotCheck(code)
+            | Status::Warning(code) => Some(*code),
+            Status::Timeout(code) => *code,
+            Status::Error(e) => match e.as_ref() {
+                ErrorKind::ReqwestError(_, e) => e.status(),
+                ErrorKind::UnexpectedStatusCode(code) => Some(*code),
+                _ => None,
+            },
+            Status::Excluded | Status::Unsupported(_) | Status::Skipped(_) => None,
+        }
+    }
+
     #[must_use]
     /// Return a unicode icon to visualize the status
     pub const fn icon(&self) -> &str {
@@ -120,10 +246,34 @@ impl Status {
             Status::Error(_) => ICON_ERROR,
             Status::Timeout(_) => ICON_TIMEOUT,
             Status::Unsupported(_) => ICON_UNSUPPORTED,
+            Status::ProtectedByBotCheck(_) => ICON_BOT_CHECK,
+            Status::Warning(_) => ICON_WARNING,
+            Status::Skipped(_) => ICON_SKIPPED,
         }
     }
 }
 
+/// Check if the response is a challenge of a bot protection service
+/// rather than the requested resource. These get served with a 403 or 503
+/// and can be recognized by the headers of the protection service.
+fn is_bot_challenge(response: &Response) -> bool {
+    let code = response.status();
+    if code != StatusCode::FORBIDDEN && code != StatusCode::SERVICE_UNAVAILABLE {
+        return false;
+    }
+    let headers = response.headers();
+    let server = headers
+        .get(header::SERVER)
+        .and_then(|s| s.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    headers.contains_key("cf-mitigated")
+        || headers.contains_key("cf-ray")
+        || headers.contains_key("x-sucuri-id")
+        || server.contains("cloudflare")
+        || server.contains("ddos-guard")
+}
+
 impl From<ErrorKind> for Status {
     fn from(e: ErrorKind) -> Self {
         Self::Error(Box::new(e))
@@ -135,9 +285,9 @@ impl From<reqwest::Error> for Status {
         if e.is_timeout() {
             Self::Timeout(e.status())
         } else if e.is_builder() {
-            Self::Unsupported(Box::new(ErrorKind::ReqwestError(e)))
+            Self::Unsupported(Box::new(e.into()))
         } else {
-            Self::Error(Box::new(ErrorKind::ReqwestError(e)))
+            Self::Error(Box::new(e.into()))
         }
     }
 }
@@ -0,0 +1,144 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Detailed timing information of a single check, for debugging flaky links
+#[derive(Debug, Clone, Serialize, Hash, PartialEq, Eq)]
+pub struct Timeline {
+    #[serde(skip)]
+    started: Instant,
+    /// Everything that happened during the check, in order
+    pub events: Vec<TimelineEvent>,
+}
+
+impl Timeline {
+    /// Start a new, empty timeline
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Record a phase of the check, timestamped relative to the start of the timeline
+    pub fn record(&mut self, phase: CheckPhase) {
+        self.events.push(TimelineEvent {
+            at: self.started.elapsed(),
+            phase,
+        });
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single entry of a [`Timeline`]
+#[derive(Debug, Clone, Serialize, Hash, PartialEq, Eq)]
+pub struct TimelineEvent {
+    /// Time since the start of the check
+    pub at: Duration,
+    /// What happened
+    #[serde(flatten)]
+    pub phase: CheckPhase,
+}
+
+impl Display for TimelineEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "+{}ms {}", self.at.as_millis(), self.phase)
+    }
+}
+
+/// Phases of checking a website.
+///
+/// DNS lookup and TCP connect are measured with separate probes,
+/// as the HTTP client doesn't expose the timings of its own connections.
+/// For the same reason, the TLS handshake is included in `FirstByte`.
+#[derive(Debug, Clone, Serialize, Hash, PartialEq, Eq)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum CheckPhase {
+    /// Host name resolution
+    DnsLookup {
+        /// The resolved host
+        host: String,
+        /// Number of resolved addresses
+        addresses: usize,
+        /// Duration of the lookup
+        took: Duration,
+    },
+    /// TCP connection to the first resolved address
+    Connect {
+        /// The address which was connected to
+        address: String,
+        /// Duration until the connection was established
+        took: Duration,
+    },
+    /// The request was sent
+    Request {
+        /// The requested URL
+        url: String,
+    },
+    /// The response headers arrived
+    FirstByte {
+        /// HTTP status code of the response
+        status: u16,
+        /// Duration since the request was sent
+        took: Duration,
+    },
+    /// The response body was read
+    Body {
+        /// Number of bytes read
+        bytes: usize,
+        /// Duration of reading the body
+        took: Duration,
+    },
+    /// The check failed and will be retried
+    Retry {
+        /// Number of the upcoming retry, starting at 1
+        attempt: usize,
+        /// Why the previous attempt failed
+        reason: String,
+        /// Time to wait before retrying
+        wait: Duration,
+    },
+}
+
+impl Display for CheckPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckPhase::DnsLookup {
+                host,
+                addresses,
+                took,
+            } => write!(
+                f,
+                "DNS lookup of {}: {} address(es) ({}ms)",
+                host,
+                addresses,
+                took.as_millis()
+            ),
+            CheckPhase::Connect { address, took } => {
+                write!(f, "Connected to {} ({}ms)", address, took.as_millis())
+            }
+            CheckPhase::Request { url } => write!(f, "Request {}", url),
+            CheckPhase::FirstByte { status, took } => {
+                write!(f, "First byte [{}] ({}ms)", status, took.as_millis())
+            }
+            CheckPhase::Body { bytes, took } => {
+                write!(f, "Body of {} bytes ({}ms)", bytes, took.as_millis())
+            }
+            CheckPhase::Retry {
+                attempt,
+                reason,
+                wait,
+            } => write!(f, "Retry #{} in {}s: {}", attempt, wait.as_secs(), reason),
+        }
+    }
+}
@@ -1,16 +1,27 @@
-use std::{convert::TryFrom, fmt::Display, net::IpAddr};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::{Debug, Display, Write},
+    net::IpAddr,
+};
 
 use fast_chemail::parse_email;
-use serde::{Deserialize, Serialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use url::Url;
 
-use crate::{ErrorKind, Result};
+use crate::{
+    redact::{has_credentials, redact},
+    ErrorKind, Result,
+};
 
 /// Lychee's own representation of a URI, which encapsulates all support formats.
 ///
 /// If the scheme is `mailto`, it's a mail address.
 /// Otherwise it's treated as a website URL.
-#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Credentials in the URL are replaced with `***` when it is displayed,
+/// debugged or serialized (see [`redact`](crate::redact())).
+#[derive(Clone, PartialOrd, Ord, PartialEq, Eq, Hash, Deserialize)]
 pub struct Uri {
     /// Website URL or mail address
     pub(crate) url: Url,
@@ -34,6 +45,14 @@ impl Uri {
         self.url.scheme()
     }
 
+    #[inline]
+    #[must_use]
+    /// Returns the host of the URI, a domain or an IP address
+    /// (e.g. `www.example.org` or `[::1]`)
+    pub fn host(&self) -> Option<&str> {
+        self.url.host_str()
+    }
+
     #[inline]
     #[must_use]
     /// Returns the domain of the URI (e.g. `example.org`)
@@ -41,6 +60,24 @@ impl Uri {
         self.url.domain()
     }
 
+    #[must_use]
+    /// Returns the registrable domain of the URI according to the
+    /// [public suffix list](https://publicsuffix.org), i.e. the public suffix
+    /// and one more label (e.g. `example.co.uk` for `www.example.co.uk`).
+    ///
+    /// Returns `None` for IP addresses and domains which are public
+    /// suffixes themselves (e.g. `github.io`).
+    pub fn registrable_domain(&self) -> Option<&str> {
+        psl::domain_str(self.domain()?.trim_end_matches('.'))
+    }
+
+    #[must_use]
+    /// Returns the public suffix of the domain of the URI
+    /// (e.g. `co.uk` for `www.example.co.uk`)
+    pub fn public_suffix(&self) -> Option<&str> {
+        psl::suffix_str(self.domain()?.trim_end_matches('.'))
+    }
+
     #[inline]
     #[must_use]
     /// Unless this URL is cannot-be-a-base,
@@ -52,6 +89,20 @@ impl Uri {
         self.url.path_segments()
     }
 
+    #[must_use]
+    /// Returns the query of the URI as a map from the percent-decoded keys
+    /// to their values, in order of appearance if a key is repeated
+    /// (e.g. `?tag=a&tag=b`)
+    pub fn query_map(&self) -> HashMap<String, Vec<String>> {
+        let mut map: HashMap<String, Vec<String>> = HashMap::new();
+        for (key, value) in self.url.query_pairs() {
+            map.entry(key.into_owned())
+                .or_default()
+                .push(value.into_owned());
+        }
+        map
+    }
+
     #[must_use]
     /// Returns the IP address (either IPv4 or IPv6) of the URI,
     /// or `None` if it is a domain
@@ -85,6 +136,73 @@ impl Uri {
     pub(crate) fn is_mail(&self) -> bool {
         self.scheme() == "mailto"
     }
+
+    /// Parse a URI found in a document, which may contain characters that
+    /// aren't valid in URLs (e.g. spaces, braces, non-ASCII characters or a
+    /// `%` which doesn't start an escape). These characters are
+    /// percent-encoded instead of failing, and a warning is returned along
+    /// with the URI.
+    ///
+    /// Only the path, query and fragment are repaired; invalid hosts still fail.
+    ///
+    /// ```
+    /// use lychee_lib::Uri;
+    ///
+    /// let (uri, warning) = Uri::parse_lenient("https://example.com/100%/{id}").unwrap();
+    /// assert_eq!(uri.as_str(), "https://example.com/100%25/%7Bid%7D");
+    /// assert!(warning.is_some());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of [`Uri::try_from`] if the URI is invalid
+    /// even with its invalid characters encoded
+    pub fn parse_lenient(s: &str) -> Result<(Self, Option<String>)> {
+        if let Some(encoded) = encode_invalid(s.trim()) {
+            if let Ok(uri) = Self::try_from(encoded.as_str()) {
+                let warning = format!("Percent-encode invalid characters: {}", uri);
+                return Ok((uri, Some(warning)));
+            }
+        }
+        Self::try_from(s).map(|uri| (uri, None))
+    }
+}
+
+/// Percent-encode the characters of a URL after its authority which aren't
+/// valid in URLs. Returns `None` if there are none or `s` has no authority
+/// (e.g. mail addresses and relative links).
+fn encode_invalid(s: &str) -> Option<String> {
+    let authority = s.find("://")? + 3;
+    let start = s[authority..]
+        .find(&['/', '?', '#'][..])
+        .map_or(s.len(), |end| authority + end);
+    let (head, tail) = s.split_at(start);
+
+    let mut encoded = String::with_capacity(s.len());
+    encoded.push_str(head);
+    let mut changed = false;
+    for (i, c) in tail.char_indices() {
+        if is_url_char(c) || (c == '%' && is_escape(&tail[i + 1..])) {
+            encoded.push(c);
+        } else {
+            changed = true;
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                // Writing to a string can't fail
+                let _ = write!(encoded, "%{:02X}", byte);
+            }
+        }
+    }
+    changed.then(|| encoded)
+}
+
+/// Whether `c` may appear in the path, query or fragment of a URL
+fn is_url_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$&'()*+,-./:;=?@[]_~".contains(c)
+}
+
+/// Whether `rest` starts with the two hex digits of a percent-encoded byte
+fn is_escape(rest: &str) -> bool {
+    rest.len() >= 2 && rest.as_bytes()[..2].iter().all(u8::is_ascii_hexdigit)
 }
 
 impl AsRef<str> for Uri {
@@ -133,7 +251,30 @@ impl TryFrom<&str> for Uri {
 
 impl Display for Uri {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.as_str())
+        if has_credentials(&self.url) {
+            f.write_str(redact(self.clone()).as_str())
+        } else {
+            f.write_str(self.as_str())
+        }
+    }
+}
+
+impl Debug for Uri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Uri")
+            .field("url", &redact(self.clone()).url.as_str())
+            .finish()
+    }
+}
+
+impl Serialize for Uri {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut uri = serializer.serialize_struct("Uri", 1)?;
+        uri.serialize_field("url", &redact(self.clone()).url)?;
+        uri.end()
     }
 }
 
@@ -170,6 +311,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_lenient() {
+        let (uri, warning) = Uri::parse_lenient("https://example.com/a b/{c}?q=ä#x y").unwrap();
+        assert_eq!(
+            uri.as_str(),
+            "https://example.com/a%20b/%7Bc%7D?q=%C3%A4#x%20y"
+        );
+        assert_eq!(
+            warning.unwrap(),
+            "Percent-encode invalid characters: https://example.com/a%20b/%7Bc%7D?q=%C3%A4#x%20y"
+        );
+
+        // Valid escapes and hosts aren't touched
+        assert_eq!(
+            Uri::parse_lenient("https://bücher.example/a%20b"),
+            Ok((website("https://xn--bcher-kva.example/a%20b"), None))
+        );
+        assert_eq!(
+            Uri::parse_lenient("mail@example.org"),
+            Ok((mail("mail@example.org"), None))
+        );
+        assert!(Uri::parse_lenient("https://exa mple.com/").is_err());
+        assert!(Uri::parse_lenient("/docs/a b").is_err());
+    }
+
     #[test]
     fn test_uri_host_ip_v4() {
         assert_eq!(
@@ -191,6 +357,31 @@ mod test {
         assert!(website("https://some.cryptic/url").host_ip().is_none());
     }
 
+    #[test]
+    fn test_uri_accessors() {
+        let uri = website("https://www.example.co.uk/a/b%20c?tag=x&tag=y&q=a%20b");
+        assert_eq!(uri.host(), Some("www.example.co.uk"));
+        assert_eq!(uri.registrable_domain(), Some("example.co.uk"));
+        assert_eq!(uri.public_suffix(), Some("co.uk"));
+        assert_eq!(
+            uri.path_segments().unwrap().collect::<Vec<_>>(),
+            ["a", "b%20c"]
+        );
+        let query = uri.query_map();
+        assert_eq!(query["tag"], ["x", "y"]);
+        assert_eq!(query["q"], ["a b"]);
+
+        assert_eq!(
+            website("https://user.github.io/").registrable_domain(),
+            Some("user.github.io")
+        );
+        assert_eq!(website("https://github.io/").registrable_domain(), None);
+        let ip = website("http://[::1]:8080/");
+        assert_eq!(ip.host(), Some("[::1]"));
+        assert_eq!(ip.registrable_domain(), None);
+        assert!(ip.query_map().is_empty());
+    }
+
     #[test]
     fn test_mail() {
         assert_eq!(
@@ -0,0 +1,313 @@
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use glob::{MatchOptions, Pattern};
+use notify::{DebouncedEvent, RecursiveMode, Watcher as _};
+use shellexpand::tilde;
+use tokio::sync::mpsc;
+
+use crate::{
+    client::Client, concurrency::ConcurrencyLimit, Collector, Input, Response, ResponseBody,
+    Result, Uri,
+};
+
+/// Time to wait for further changes of a file before it gets re-checked
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Checks the links of files once, and again whenever one of the files changes.
+///
+/// Only the changed file is read again. Links which were already checked
+/// successfully are answered from a cache, so after an edit only new and
+/// broken links cause requests. Every response is sent as soon as it is known,
+/// which makes this suitable for previews while writing documentation.
+///
+/// ```no_run
+/// use lychee_lib::{watch::Watcher, ClientBuilder, Collector, Input, Result};
+/// use tokio::sync::mpsc;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let client = ClientBuilder::default().client()?;
+///     let (sender, mut receiver) = mpsc::channel(16);
+///     tokio::spawn(async move {
+///         let inputs = [Input::new("docs/**/*.md", false)];
+///         Watcher::new(client, Collector::default()).watch(&inputs, sender).await
+///     });
+///     while let Some(response) = receiver.recv().await {
+///         println!("{} ({})", response, response.0);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    client: Client,
+    collector: Collector,
+    debounce: Duration,
+    limit: ConcurrencyLimit,
+}
+
+impl Watcher {
+    /// Watch files with the given client and collector
+    #[must_use]
+    pub fn new(client: Client, collector: Collector) -> Self {
+        Watcher {
+            client,
+            collector,
+            debounce: DEFAULT_DEBOUNCE,
+            limit: ConcurrencyLimit::default(),
+        }
+    }
+
+    /// Wait this long for further changes of a file before re-checking it
+    #[must_use]
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Check at most this many links at the same time
+    #[must_use]
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.limit = ConcurrencyLimit::new(max_concurrency);
+        self
+    }
+
+    /// Check all links of `inputs`, then watch the files among them and
+    /// re-check a file whenever it is written or created. Glob patterns also
+    /// cover files which are created later. Other inputs are only checked once.
+    /// Runs until the receiver of `sender` is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the files can't be watched or can't be read on
+    /// the first check. A changed file which can't be read is skipped until
+    /// it changes again.
+    pub async fn watch(self, inputs: &[Input], sender: mpsc::Sender<Response>) -> Result<()> {
+        let (notify_sender, notify_receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::watcher(notify_sender, self.debounce)?;
+        let mut watched = Watched::default();
+        for input in inputs {
+            match input {
+                Input::FsPath(path) => {
+                    watcher.watch(path, RecursiveMode::NonRecursive)?;
+                    watched.files.insert(path.canonicalize()?, input.clone());
+                }
+                Input::FsGlob {
+                    pattern,
+                    ignore_case,
+                } => {
+                    let pattern = absolute(Path::new(tilde(pattern).as_ref()))?;
+                    watcher.watch(glob_root(&pattern), RecursiveMode::Recursive)?;
+                    let pattern = Pattern::new(pattern.to_str().unwrap_or_default())?;
+                    watched.patterns.push((pattern, *ignore_case));
+                }
+                _ => {}
+            }
+        }
+
+        // notify reports changes on a channel of the standard library,
+        // so they are received on a thread of their own
+        let (changes, mut changed) = mpsc::channel(16);
+        std::thread::spawn(move || {
+            // Changes are only reported while the watcher is alive
+            let _watcher = watcher;
+            for event in notify_receiver {
+                let path = match event {
+                    DebouncedEvent::Create(path)
+                    | DebouncedEvent::Write(path)
+                    | DebouncedEvent::Rename(_, path) => path,
+                    _ => continue,
+                };
+                if changes.blocking_send(path).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut cache = HashMap::new();
+        if !self.check(inputs, &mut cache, &sender).await? {
+            return Ok(());
+        }
+        while let Some(path) = changed.recv().await {
+            if let Some(input) = watched.input(&path) {
+                // Editors which save atomically replace the file, and it may
+                // be deleted before it is read, so wait for its next change
+                match self.check(&[input], &mut cache, &sender).await {
+                    Ok(true) | Err(_) => {}
+                    Ok(false) => break,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the links of `inputs` and send the responses.
+    /// Returns `false` once nobody receives the responses anymore.
+    async fn check(
+        &self,
+        inputs: &[Input],
+        cache: &mut HashMap<Uri, ResponseBody>,
+        sender: &mpsc::Sender<Response>,
+    ) -> Result<bool> {
+        let requests = self.collector.clone().collect_links(inputs).await?;
+
+        let mut checks = Vec::with_capacity(requests.len());
+        for request in requests {
+            if let Some(body) = cache.get(&request.uri).and_then(ResponseBody::try_clone) {
+                let body = ResponseBody {
+                    spans: request.spans,
+                    metadata: request.metadata,
+                    ..body
+                };
+                checks.push((request.source, Ok(body)));
+                continue;
+            }
+            let client = self.client.clone();
+            let source = request.source.clone();
+            checks.push((
+                source,
+                Err(self.limit.spawn(async move { client.check(request).await })),
+            ));
+        }
+
+        for (source, check) in checks {
+            let body = match check {
+                Ok(body) => body,
+                Err(handle) => {
+                    let Response(_, body) = handle.await??;
+                    if let Some(cached) = body.try_clone() {
+                        cache.insert(body.uri.clone(), cached);
+                    }
+                    body
+                }
+            };
+            if sender.send(Response(source, body)).await.is_err() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// The files and glob patterns which are watched for changes
+#[derive(Debug, Default)]
+struct Watched {
+    files: HashMap<PathBuf, Input>,
+    patterns: Vec<(Pattern, bool)>,
+}
+
+impl Watched {
+    /// The input to re-check after a change of the file at `path`
+    fn input(&self, path: &Path) -> Option<Input> {
+        let path = path.canonicalize().ok()?;
+        if let Some(input) = self.files.get(&path) {
+            return Some(input.clone());
+        }
+        let matches = self.patterns.iter().any(|(pattern, ignore_case)| {
+            let options = MatchOptions {
+                case_sensitive: !ignore_case,
+                ..MatchOptions::new()
+            };
+            pattern.matches_path_with(&path, options)
+        });
+        if !matches || !path.is_file() {
+            return None;
+        }
+        // Report files relative to the working directory, as on the first check
+        let relative = std::env::current_dir()
+            .ok()
+            .and_then(|dir| path.strip_prefix(dir).ok().map(Path::to_path_buf));
+        Some(Input::FsPath(relative.unwrap_or(path)))
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    Ok(if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    })
+}
+
+/// The directory containing all files a glob pattern can match,
+/// i.e. the part of the pattern before the first wildcard
+fn glob_root(pattern: &Path) -> PathBuf {
+    pattern
+        .components()
+        .take_while(|component| {
+            !matches!(component, Component::Normal(part)
+                if part.to_str().map_or(false, |part| part.contains(&['*', '?', '['][..])))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path, time::Duration};
+
+    use http::StatusCode;
+    use tokio::{sync::mpsc, time::timeout};
+
+    use super::{glob_root, Watcher};
+    use crate::{mock_server, ClientBuilder, Collector, Input, Result};
+
+    #[test]
+    fn test_glob_root() {
+        assert_eq!(
+            glob_root(Path::new("/home/user/docs/**/*.md")),
+            Path::new("/home/user/docs")
+        );
+        assert_eq!(glob_root(Path::new("/docs/[a-z]*.md")), Path::new("/docs"));
+        assert_eq!(
+            glob_root(Path::new("/docs/README.md")),
+            Path::new("/docs/README.md")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch() -> Result<()> {
+        let mock_server = mock_server!(StatusCode::OK);
+        let temp_dir = tempfile::tempdir()?;
+        let file_path = temp_dir.path().join("README.md");
+        fs::write(&file_path, format!("{}/a\n", mock_server.uri()))?;
+
+        let client = ClientBuilder::default().client()?;
+        let watcher = Watcher::new(client, Collector::default())
+            .debounce(Duration::from_millis(50))
+            .max_concurrency(1);
+        let (sender, mut receiver) = mpsc::channel(16);
+        let inputs = [Input::FsPath(file_path.clone())];
+        tokio::spawn(async move { watcher.watch(&inputs, sender).await });
+
+        let first = timeout(Duration::from_secs(10), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(first.status().is_success());
+
+        fs::write(
+            &file_path,
+            format!("{}/a\n{}/b\n", mock_server.uri(), mock_server.uri()),
+        )?;
+        let mut changed = Vec::new();
+        for _ in 0..2 {
+            let response = timeout(Duration::from_secs(10), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(response.status().is_success());
+            changed.push(response.1.uri.to_string());
+        }
+        changed.sort();
+        assert!(changed[0].ends_with("/a"));
+        assert!(changed[1].ends_with("/b"));
+
+        // The first link was answered from the cache
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 2);
+        Ok(())
+    }
+}